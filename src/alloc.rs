@@ -170,6 +170,14 @@ pub struct Allocator {
     alloc_flag: bool,
     /// Flag whether GC is enabled or not.
     pub gc_enabled: bool,
+    /// Finalizers registered by ObjectSpace.define_finalizer. (object, proc)
+    finalizers: Vec<(Value, Value)>,
+    /// Finalizers of the objects collected in GC, which are to be invoked. (object_id, proc)
+    pending_finalizers: Vec<(Value, Value)>,
+    /// Entries of ObjectSpace::WeakMaps. (id of WeakMap => (id of key => (key, value)))
+    ///
+    /// These entries are not marked, and are removed when the key or the value is collected.
+    weak_maps: FxHashMap<u64, FxIndexMap<u64, (Value, Value)>>,
 }
 
 impl Allocator {
@@ -190,6 +198,9 @@ impl Allocator {
             count: 0,
             alloc_flag: false,
             gc_enabled: true,
+            finalizers: vec![],
+            pending_finalizers: vec![],
+            weak_maps: FxHashMap::default(),
         };
         alloc
     }
@@ -305,6 +316,8 @@ impl Allocator {
         }
         self.clear_mark();
         root.mark(self);
+        self.mark_finalizers();
+        self.clear_weak_maps();
         #[cfg(feature = "gc-debug")]
         eprint!("marked: {}  ", self.mark_counter);
         self.dealloc_empty_pages();
@@ -345,8 +358,33 @@ impl Allocator {
     fn mark_ptr(&mut self, ptr: *mut GCBox<RValue>) -> bool {
         #[cfg(feature = "gc-debug")]
         self.check_ptr(ptr);
-        let mut page_ptr = PageRef::from_inner(ptr);
+        let (mut page_ptr, index, bit_mask) = Allocator::bit_position(ptr);
+        let bitmap = &mut page_ptr.mark_bits[index];
+
+        let is_marked = (*bitmap & bit_mask) != 0;
+        *bitmap |= bit_mask;
+        if !is_marked {
+            self.mark_counter += 1;
+        }
+        is_marked
+    }
 
+    /// Check whether `val` is marked or not.
+    /// Objects which are not on the heap (e.g. Fixnum, Symbol, nil) are always regarded as marked.
+    fn is_marked(&self, val: Value) -> bool {
+        match val.as_gcbox() {
+            Some(gcbox) => {
+                let ptr = gcbox as *const GCBox<RValue> as *mut GCBox<RValue>;
+                let (page_ptr, index, bit_mask) = Allocator::bit_position(ptr);
+                page_ptr.mark_bits[index] & bit_mask != 0
+            }
+            None => true,
+        }
+    }
+
+    /// Returns the page which contains `ptr`, and the index and the mask of the bitmap for `ptr`.
+    fn bit_position(ptr: *mut GCBox<RValue>) -> (PageRef, usize, u64) {
+        let page_ptr = PageRef::from_inner(ptr);
         let offset = ptr as usize - page_ptr.get_data_ptr(0) as usize;
         let index = offset / GCBOX_SIZE;
         #[cfg(feature = "gc-debug")]
@@ -354,15 +392,36 @@ impl Allocator {
             assert_eq!(0, offset % GCBOX_SIZE);
             assert!(index < DATA_LEN);
         }
-        let bit_mask = 1 << (index % 64);
-        let bitmap = &mut page_ptr.mark_bits[index / 64];
+        (page_ptr, index / 64, 1 << (index % 64))
+    }
 
-        let is_marked = (*bitmap & bit_mask) != 0;
-        *bitmap |= bit_mask;
-        if !is_marked {
-            self.mark_counter += 1;
+    /// Mark all finalizer procs, and move the finalizers of unmarked objects to the pending list.
+    fn mark_finalizers(&mut self) {
+        let finalizers = std::mem::replace(&mut self.finalizers, vec![]);
+        let pending = std::mem::replace(&mut self.pending_finalizers, vec![]);
+        for (_, proc) in finalizers.iter().chain(pending.iter()) {
+            proc.mark(self);
         }
-        is_marked
+        self.pending_finalizers = pending;
+        for (obj, proc) in finalizers {
+            if self.is_marked(obj) {
+                self.finalizers.push((obj, proc));
+            } else {
+                let id = Value::integer(obj.id() as i64);
+                self.pending_finalizers.push((id, proc));
+            }
+        }
+    }
+
+    /// Remove the entries of WeakMaps whose key or value was not marked,
+    /// and discard WeakMaps which were not marked.
+    fn clear_weak_maps(&mut self) {
+        let mut weak_maps = std::mem::replace(&mut self.weak_maps, FxHashMap::default());
+        weak_maps.retain(|map, _| self.is_marked(Value::from(*map)));
+        for table in weak_maps.values_mut() {
+            table.retain(|_, (k, v)| self.is_marked(*k) && self.is_marked(*v));
+        }
+        self.weak_maps = weak_maps;
     }
 
     pub fn dealloc_empty_pages(&mut self) {
@@ -436,6 +495,86 @@ impl Allocator {
     }
 }
 
+// For ObjectSpace
+impl Allocator {
+    /// Call `f` for each live object in the heap.
+    pub fn each_object(&self, mut f: impl FnMut(Value)) {
+        self.each_slot(|slot| {
+            if slot.inner().kind != ObjKind::Invalid {
+                f(Value::from_ptr(
+                    slot as *const GCBox<RValue> as *mut GCBox<RValue>,
+                ))
+            }
+        });
+    }
+
+    /// Call `f` for each slot in the heap pages, including free slots.
+    pub fn each_slot(&self, mut f: impl FnMut(&GCBox<RValue>)) {
+        for page in self.pages.iter() {
+            for i in 0..DATA_LEN {
+                f(unsafe { &*page.get_data_ptr(i) });
+            }
+        }
+        for i in 0..self.used_in_current {
+            f(unsafe { &*self.current.get_data_ptr(i) });
+        }
+    }
+
+    /// Check whether `id` is a pointer to a live object in the heap.
+    ///
+    /// The page which contains `id` is computed from the address, and the slot is examined directly.
+    pub fn is_live_object(&self, id: u64) -> bool {
+        let page = PageRef::from_inner(id as usize as *mut GCBox<RValue>);
+        let len = if page.as_ptr() == self.current.as_ptr() {
+            self.used_in_current
+        } else if self.pages.iter().any(|p| p.as_ptr() == page.as_ptr()) {
+            DATA_LEN
+        } else {
+            return false;
+        };
+        let index = match (id as usize).checked_sub(page.get_data_ptr(0) as usize) {
+            Some(offset) if offset % GCBOX_SIZE == 0 => offset / GCBOX_SIZE,
+            _ => return false,
+        };
+        index < len && unsafe { (*page.get_data_ptr(index)).inner().kind != ObjKind::Invalid }
+    }
+
+    pub fn add_finalizer(&mut self, obj: Value, proc: Value) {
+        self.finalizers.push((obj, proc));
+    }
+
+    pub fn remove_finalizers(&mut self, obj: Value) {
+        self.finalizers.retain(|(o, _)| o.id() != obj.id());
+    }
+
+    /// Take the finalizers of the objects collected in GC. (object_id, proc)
+    pub fn take_pending_finalizers(&mut self) -> Vec<(Value, Value)> {
+        std::mem::replace(&mut self.pending_finalizers, vec![])
+    }
+
+    pub fn weak_map_get(&self, map: Value, key: Value) -> Option<Value> {
+        self.weak_maps
+            .get(&map.id())
+            .and_then(|table| table.get(&key.id()))
+            .map(|(_, v)| *v)
+    }
+
+    pub fn weak_map_set(&mut self, map: Value, key: Value, val: Value) {
+        self.weak_maps
+            .entry(map.id())
+            .or_default()
+            .insert(key.id(), (key, val));
+    }
+
+    /// Returns all entries of the WeakMap `map`. (key, value)
+    pub fn weak_map_entries(&self, map: Value) -> Vec<(Value, Value)> {
+        match self.weak_maps.get(&map.id()) {
+            Some(table) => table.values().cloned().collect(),
+            None => vec![],
+        }
+    }
+}
+
 // For debug
 impl Allocator {
     fn check_ptr(&self, ptr: *mut GCBox<RValue>) {
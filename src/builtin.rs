@@ -1,5 +1,6 @@
 pub mod array;
 pub mod basicobject;
+pub mod binding;
 pub mod class;
 pub mod comparable;
 pub mod complex;
@@ -14,13 +15,16 @@ pub mod gc;
 pub mod hash;
 pub mod integer;
 pub mod io;
+pub mod json;
 pub mod kernel;
+pub mod marshal;
 pub mod math;
 pub mod method;
 pub mod module;
 pub mod nilclass;
 pub mod numeric;
 pub mod object;
+pub mod objectspace;
 pub mod process;
 pub mod procobj;
 pub mod range;
@@ -87,7 +91,9 @@ pub struct BuiltinClass {
     pub array: Value,
     pub symbol: Value,
     pub procobj: Value,
+    pub binding: Value,
     pub method: Value,
+    pub unbound_method: Value,
     pub range: Value,
     pub hash: Value,
     pub regexp: Value,
@@ -116,7 +122,9 @@ impl BuiltinClass {
             array: nil,
             symbol: nil,
             procobj: nil,
+            binding: nil,
             method: nil,
+            unbound_method: nil,
             range: nil,
             hash: nil,
             regexp: nil,
@@ -150,9 +158,18 @@ impl BuiltinClass {
         init_builtin!(comparable, numeric, kernel);
         init!(module, class, basicobject, object);
         init_builtin!(float, complex, integer, nilclass, trueclass, falseclass);
-        init_builtin!(array, symbol, procobj, range, string, hash);
+        init_builtin!(array, symbol, procobj, binding, range, string, hash);
         init_builtin!(method, regexp, fiber, enumerator);
-        init!(math, dir, process, gc, structobj, time);
+        init!(
+            math,
+            dir,
+            process,
+            gc,
+            structobj,
+            time,
+            objectspace,
+            marshal
+        );
     }
 
     /// Bind `object` to the constant `name` of the root object.
@@ -221,6 +238,10 @@ impl BuiltinClass {
         BUILTINS.with(|b| b.borrow().procobj).into_module()
     }
 
+    pub fn binding() -> Module {
+        BUILTINS.with(|b| b.borrow().binding).into_module()
+    }
+
     pub fn regexp() -> Module {
         BUILTINS.with(|b| b.borrow().regexp).into_module()
     }
@@ -229,6 +250,10 @@ impl BuiltinClass {
         BUILTINS.with(|b| b.borrow().method).into_module()
     }
 
+    pub fn unbound_method() -> Module {
+        BUILTINS.with(|b| b.borrow().unbound_method).into_module()
+    }
+
     pub fn exception() -> Module {
         BUILTINS.with(|b| b.borrow().exception).into_module()
     }
@@ -8,6 +8,7 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("inspect", inspect);
     class.add_builtin_method_by_str("to_s", inspect);
     class.add_builtin_method_by_str("to_a", toa);
+    class.add_builtin_method_by_str("deconstruct", toa);
     class.add_builtin_method_by_str("length", length);
     class.add_builtin_method_by_str("size", length);
     class.add_builtin_method_by_str("empty?", empty);
@@ -392,7 +393,7 @@ fn map(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
 
 fn flat_map(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     let block = args.expect_block()?;
-    let param_num = block.to_iseq().params.req;
+    let param_num = block.to_iseq()?.params.req;
     let mut arg = Args::new(param_num);
 
     let aref = self_val.into_array();
@@ -4,12 +4,12 @@ pub fn init() {
     let mut class = BuiltinClass::object().superclass().unwrap();
     BuiltinClass::set_toplevel_constant("BasicObject", class);
     class.add_builtin_method(IdentId::_ALIAS_METHOD, alias_method);
+    class.add_builtin_method(IdentId::_UNDEF_METHOD, undef_method);
     class.add_builtin_method(IdentId::_METHOD_MISSING, method_missing);
     class.add_builtin_method_by_str("__id__", basicobject_id);
 }
 
 /// An alias statement is compiled to method call for this func.
-/// TODO: Currently, aliasing of global vars does not work.
 fn alias_method(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(2)?;
     let new = args[0].as_symbol().unwrap();
@@ -17,7 +17,7 @@ fn alias_method(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     let is_new_gvar = IdentId::starts_with(new, "$");
     let is_org_gvar = IdentId::starts_with(org, "$");
     match (is_new_gvar, is_org_gvar) {
-        (true, true) => {}
+        (true, true) => vm.globals.alias_global_var(new, org),
         (false, false) => {
             // TODO: Is it right?
             let mut class = vm.class();
@@ -38,6 +38,15 @@ fn alias_method(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     Ok(Value::nil())
 }
 
+/// An undef statement is compiled to method call for this func.
+fn undef_method(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
+    let class = vm.class();
+    for arg in args.iter() {
+        class.undef_method(arg.as_symbol().unwrap())?;
+    }
+    Ok(Value::nil())
+}
+
 fn method_missing(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_min(1)?;
     let method_id = match args[0].as_symbol() {
@@ -100,6 +109,52 @@ mod test {
         assert_script(program);
     }
 
+    #[test]
+    fn alias_global_var() {
+        let program = r#"
+        $foo = 5
+        alias $bar $foo
+        assert 5, $bar
+        $bar = 7
+        assert 7, $foo
+        $foo = "ruby"
+        assert "ruby", $bar
+        alias $baz $bar
+        $baz = 42
+        assert 42, $foo
+        assert_error { eval("alias $qux foo") }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn undef() {
+        let program = r#"
+        class Foo
+          def foo; 1; end
+          def bar; 2; end
+          def baz; 3; end
+        end
+        class Bar < Foo
+          undef foo, :bar
+        end
+        assert 1, Foo.new.foo
+        assert 2, Foo.new.bar
+        assert 3, Bar.new.baz
+        assert_error { Bar.new.foo }
+        assert_error { Bar.new.bar }
+        assert false, Bar.new.respond_to?(:foo)
+        assert false, Bar.instance_methods.include?(:bar)
+        assert_error { Object.new.baz }
+        class Foo
+          undef baz
+        end
+        assert_error { Foo.new.baz }
+        assert_error { eval("undef qux") }
+        "#;
+        assert_script(program);
+    }
+
     #[test]
     fn method_missing() {
         let program = r#"
@@ -0,0 +1,286 @@
+use crate::parse::codegen::ContextKind;
+use crate::*;
+use std::path::PathBuf;
+
+pub fn init() -> Value {
+    let class = Module::class_under_object();
+    BuiltinClass::set_toplevel_constant("Binding", class);
+    class.add_builtin_method_by_str("inspect", inspect);
+    class.add_builtin_method_by_str("to_s", inspect);
+    class.add_builtin_method_by_str("local_variable_get", local_variable_get);
+    class.add_builtin_method_by_str("local_variable_set", local_variable_set);
+    class.add_builtin_method_by_str("local_variable_defined?", local_variable_defined);
+    class.add_builtin_method_by_str("local_variables", local_variables);
+    class.add_builtin_method_by_str("receiver", receiver);
+    class.add_builtin_method_by_str("eval", eval);
+    class.into()
+}
+
+/// Evaluate `program` in the scope of `binding`.
+///
+/// `line` is the line number of the first line of `program`.
+/// Local variables defined in `program` are kept in `binding`.
+pub fn eval_with_binding(
+    vm: &mut VM,
+    binding: Value,
+    program: &str,
+    path: PathBuf,
+    line: i64,
+) -> VMResult {
+    let outer = binding.as_binding().unwrap();
+    let method = vm.parse_program_binding(path, program, line, outer)?;
+    let iseq = method.as_iseq();
+    let context = ContextRef::new_heap(outer.self_value, Block::None, iseq, Some(outer));
+    vm.run_context(context)?;
+    let val = vm.stack_pop();
+    if iseq.lvars != 0 {
+        binding.rvalue_mut().kind = ObjKind::Binding(context);
+    }
+    Ok(val)
+}
+
+/// Find a local variable `id` in `context` or its outer contexts.
+fn find_lvar(mut context: ContextRef, id: IdentId) -> Option<(ContextRef, LvarId)> {
+    loop {
+        if let Some(lvar) = context.iseq_ref.unwrap().lvar.get(&id) {
+            return Some((context, *lvar));
+        }
+        context = context.outer?;
+    }
+}
+
+fn expect_lvar_name(val: Value) -> Result<IdentId, RubyError> {
+    let id = val.expect_symbol_or_string("1st arg")?;
+    let name = IdentId::get_ident_name(id);
+    let mut chars = name.chars();
+    let is_lvar = match chars.next() {
+        Some(ch) if ch.is_ascii_lowercase() || ch == '_' || !ch.is_ascii() => {
+            chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || !ch.is_ascii())
+        }
+        _ => false,
+    };
+    if is_lvar {
+        Ok(id)
+    } else {
+        Err(RubyError::name(format!(
+            "Wrong local variable name `{}' for binding.",
+            name
+        )))
+    }
+}
+
+// Instance methods
+
+fn inspect(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let context = self_val.as_binding().unwrap();
+    Ok(Value::string(format!("#<Binding:0x{:016x}>", context.id())))
+}
+
+fn local_variable_get(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_lvar_name(args[0])?;
+    match find_lvar(self_val.as_binding().unwrap(), id) {
+        Some((context, lvar)) if context[lvar].is_uninitialized() => Ok(Value::nil()),
+        Some((context, lvar)) => Ok(context[lvar]),
+        None => Err(RubyError::name(format!(
+            "Local variable `{:?}' is not defined for {:?}.",
+            id, self_val
+        ))),
+    }
+}
+
+fn local_variable_set(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(2)?;
+    let id = expect_lvar_name(args[0])?;
+    let val = args[1];
+    match find_lvar(self_val.as_binding().unwrap(), id) {
+        Some((mut context, lvar)) => context[lvar] = val,
+        None => define_lvar(vm, self_val, id, val)?,
+    }
+    Ok(val)
+}
+
+/// Define a new local variable `id` in `binding` and set `val` to it.
+///
+/// The variable is held in a new scope nested in the original one,
+/// so that it is not visible from the context where `binding` was created.
+fn define_lvar(vm: &mut VM, mut binding: Value, id: IdentId, val: Value) -> Result<(), RubyError> {
+    let outer = binding.as_binding().unwrap();
+    let mut lvar_collector = LvarCollector::new();
+    let lvar = lvar_collector.insert(id);
+    let mut codegen = Codegen::new(outer.iseq_ref.unwrap().source_info);
+    codegen.set_external_context(outer);
+    let method = codegen.gen_iseq(
+        &mut vm.globals,
+        vec![],
+        Node::new_nil(Loc(0, 0)),
+        lvar_collector,
+        true,
+        ContextKind::Eval,
+        None,
+    )?;
+    let mut context =
+        ContextRef::new_heap(outer.self_value, Block::None, method.as_iseq(), Some(outer));
+    context[lvar] = val;
+    binding.rvalue_mut().kind = ObjKind::Binding(context);
+    Ok(())
+}
+
+fn local_variable_defined(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_lvar_name(args[0])?;
+    let b = find_lvar(self_val.as_binding().unwrap(), id).is_some();
+    Ok(Value::bool(b))
+}
+
+fn local_variables(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let mut context = self_val.as_binding().unwrap();
+    let mut vars = vec![];
+    loop {
+        let mut table: Vec<(IdentId, LvarId)> = context
+            .iseq_ref
+            .unwrap()
+            .lvar
+            .table()
+            .iter()
+            .map(|(id, lvar)| (*id, *lvar))
+            .collect();
+        table.sort_by_key(|(_, lvar)| lvar.as_usize());
+        for (id, _) in table {
+            let sym = Value::symbol(id);
+            if !vars.contains(&sym) {
+                vars.push(sym);
+            }
+        }
+        context = match context.outer {
+            Some(outer) => outer,
+            None => break,
+        };
+    }
+    Ok(Value::array_from(vars))
+}
+
+fn receiver(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val.as_binding().unwrap().self_value)
+}
+
+fn eval(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 3)?;
+    let mut arg0 = args[0];
+    let program = arg0.expect_string("1st arg")?.to_string();
+    let path = if args.len() > 1 {
+        let mut arg1 = args[1];
+        PathBuf::from(arg1.expect_string("2nd arg")?)
+    } else {
+        PathBuf::from("(eval)")
+    };
+    let line = if args.len() > 2 {
+        args[2].expect_integer("3rd arg")?
+    } else {
+        1
+    };
+    eval_with_binding(vm, self_val, &program, path, line)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::*;
+
+    #[test]
+    fn binding_local_variable() {
+        let program = r#"
+        def get_binding
+          a = 100
+          b = "foo"
+          binding
+        end
+        bind = get_binding
+        assert 100, bind.local_variable_get(:a)
+        assert "foo", bind.local_variable_get("b")
+        assert true, bind.local_variable_defined?(:a)
+        assert false, bind.local_variable_defined?(:c)
+        assert_error { bind.local_variable_get(:c) }
+        assert_error { bind.local_variable_get(:C) }
+        assert 77, bind.local_variable_set(:a, 77)
+        assert 77, bind.local_variable_get(:a)
+        bind.local_variable_set(:c, 5)
+        assert 5, bind.local_variable_get(:c)
+        assert [:c, :a, :b], bind.local_variables
+        assert false, binding.local_variable_defined?(:c)
+        assert self, binding.receiver
+        x = 1
+        b = binding
+        b.local_variable_set(:x, 2)
+        assert 2, x
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn binding_eval() {
+        let program = r#"
+        class Foo
+          def initialize
+            @v = 42
+          end
+          def get_binding(x)
+            binding
+          end
+        end
+        bind = Foo.new.get_binding(3)
+        assert 3, bind.eval("x")
+        assert 42, bind.eval("@v")
+        assert Foo, bind.receiver.class
+        assert 10, eval("x + 7", bind)
+        bind.eval("y = x * 2")
+        assert 6, bind.eval("y")
+        assert 6, bind.local_variable_get(:y)
+        assert_error { eval("y") }
+
+        def counter
+          count = 0
+          proc { count += 1 }
+        end
+        c = counter
+        c.call
+        c.call
+        assert 2, c.binding.local_variable_get(:count)
+        c.binding.local_variable_set(:count, 10)
+        assert 11, c.call
+
+        assert_error { eval("1 +", bind, "foo.rb", 10) }
+        begin
+          eval("\n raise 'err'", bind, "foo.rb", 10)
+        rescue => e
+          assert "err", e.message
+        end
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn binding_eval_lineno() {
+        let program = r#"
+        bind = binding
+        assert 11, eval("\n__LINE__", bind, "foo.rb", 10)
+        assert 7, bind.eval("__LINE__", "bar.rb", 7)
+        assert 3, instance_eval("\n\n__LINE__", "baz.rb", 1)
+        assert 1000000000000, eval("__LINE__", nil, "qux.rb", 1000000000000)
+        begin
+          eval("\n\n raise 'err'", bind, "foo.rb", 10)
+        rescue => e
+          assert true, e.backtrace[0].start_with?("foo.rb:12:")
+        end
+        eval("\ndef ev_m\n  __LINE__\nend", bind, "bar.rb", 5)
+        assert 7, ev_m
+        assert ["bar.rb", 6], method(:ev_m).source_location
+        assert 0, eval("__LINE__", bind, "foo.rb", 0)
+        assert(-1, bind.eval("__LINE__", "foo.rb", -1))
+        assert 1, Object.class_eval("\n__LINE__", "foo.rb", 0)
+        "#;
+        assert_script(program);
+    }
+}
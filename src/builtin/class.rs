@@ -8,6 +8,7 @@ pub fn init() {
     class.add_builtin_method_by_str("allocate", allocate);
     class.add_builtin_method_by_str("superclass", superclass);
     class.add_builtin_method_by_str("inspect", inspect);
+    class.add_builtin_method_by_str("inherited", inherited);
 }
 
 // Class methods
@@ -24,6 +25,7 @@ fn class_new(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     };
     let module = Module::class_under(superclass);
     let val = module.into();
+    vm.invoke_hook("inherited", superclass.into(), val)?;
     match &args.block {
         Block::None => {}
         _ => {
@@ -70,6 +72,12 @@ fn inspect(_: &mut VM, self_val: Value, _args: &Args) -> VMResult {
     Ok(Value::string(self_val.into_module().inspect()))
 }
 
+/// Default hook method which is called when a subclass of `self` is created.
+fn inherited(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    Ok(Value::nil())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
@@ -89,4 +97,23 @@ mod tests {
         "#;
         assert_script(program);
     }
+
+    #[test]
+    fn class_inherited() {
+        let program = r#"
+        class Base
+          @subclasses = []
+          def self.inherited(klass)
+            @subclasses << klass
+          end
+          def self.subclasses
+            @subclasses
+          end
+        end
+        class Foo < Base; end
+        Bar = Class.new(Base)
+        assert [Foo, Bar], Base.subclasses
+        "#;
+        assert_script(program);
+    }
 }
@@ -107,7 +107,7 @@ fn glob(_: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     let mut matches = FxHashSet::default();
     match traverse_dir(&fullpath, &path, &glob, 0, &mut matches) {
         Ok(_) => {}
-        Err(err) => return Err(RubyError::internal(format!("{:?}", err))),
+        Err(err) => return Err(RubyError::errno(&err, fullpath.to_string_lossy())),
     };
     Ok(Value::array_from(matches.into_iter().collect()))
 }
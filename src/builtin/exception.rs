@@ -9,33 +9,132 @@ pub fn init() -> Value {
 
     class.add_builtin_method_by_str("inspect", inspect);
     class.add_builtin_method_by_str("to_s", tos);
-    builtin::module::set_attr_accessor(
-        class,
-        &Args::new2(
-            Value::symbol_from_str("message"),
-            Value::symbol_from_str("backtrace"),
-        ),
-    )
-    .unwrap();
+    class.add_builtin_method_by_str("==", eq);
+    class.add_builtin_method_by_str("backtrace", backtrace);
+    class.add_builtin_method_by_str("backtrace_locations", backtrace_locations);
+    class.add_builtin_method_by_str("set_backtrace", set_backtrace);
+    class.add_builtin_method_by_str("cause", cause);
+    class.add_builtin_method_by_str("detailed_message", detailed_message);
+    class.add_builtin_method_by_str("full_message", full_message);
+    builtin::module::set_attr_accessor(class, &Args::new1(Value::symbol_from_str("message")))
+        .unwrap();
+    let define = |name: &str, superclass: Module| {
+        let err = Module::class_under(superclass);
+        BuiltinClass::set_toplevel_constant(name, err);
+        err
+    };
     let standard_error = Module::class_under(class);
     BUILTINS.with(|m| m.borrow_mut().standard = standard_error.into());
     BuiltinClass::set_toplevel_constant("StandardError", standard_error);
+    // Subclasses of Exception.
+    define("NoMemoryError", class);
+    let script_error = define("ScriptError", class);
+    define("LoadError", script_error);
+    define("NotImplementedError", script_error);
+    define("SyntaxError", script_error);
+    define("SecurityError", class);
+    let signal_exception = define("SignalException", class);
+    define("Interrupt", signal_exception);
+    define("SystemExit", class);
+    define("SystemStackError", class);
     // Subclasses of StandardError.
-    let err = Module::class_under(standard_error);
-    BuiltinClass::set_toplevel_constant("ArgumentError", err);
-    let err = Module::class_under(standard_error);
-    BuiltinClass::set_toplevel_constant("TypeError", err);
-    let err = Module::class_under(standard_error);
-    BuiltinClass::set_toplevel_constant("NoMethodError", err);
-    let runtime_error = Module::class_under(standard_error);
-    BuiltinClass::set_toplevel_constant("StopIteration", runtime_error);
-    let runtime_error = Module::class_under(standard_error);
-    BuiltinClass::set_toplevel_constant("RuntimeError", runtime_error);
-    let frozen_error = Module::class_under(runtime_error);
-    BuiltinClass::set_toplevel_constant("FrozenError", frozen_error);
+    let err = define("ArgumentError", standard_error);
+    let uncaught_throw = define("UncaughtThrowError", err);
+    uncaught_throw.add_builtin_method_by_str("tag", uncaught_throw_tag);
+    uncaught_throw.add_builtin_method_by_str("value", uncaught_throw_value);
+    define("EncodingError", standard_error);
+    define("FiberError", standard_error);
+    let io_error = define("IOError", standard_error);
+    define("EOFError", io_error);
+    let index_error = define("IndexError", standard_error);
+    let key_error = define("KeyError", index_error);
+    key_error.add_builtin_class_method("new", key_error_new);
+    key_error.add_builtin_method_by_str("key", key_error_key);
+    key_error.add_builtin_method_by_str("receiver", error_receiver);
+    let stop_iteration = define("StopIteration", index_error);
+    define("ClosedQueueError", stop_iteration);
+    define("LocalJumpError", standard_error);
+    let no_matching_pattern = define("NoMatchingPatternError", standard_error);
+    let no_matching_pattern_key = define("NoMatchingPatternKeyError", no_matching_pattern);
+    no_matching_pattern_key.add_builtin_class_method("new", no_matching_pattern_key_error_new);
+    no_matching_pattern_key.add_builtin_method_by_str("key", key_error_key);
+    no_matching_pattern_key.add_builtin_method_by_str("matchee", error_matchee);
+    let name_error = define("NameError", standard_error);
+    name_error.add_builtin_class_method("new", name_error_new);
+    name_error.add_builtin_method_by_str("name", name_error_name);
+    name_error.add_builtin_method_by_str("receiver", error_receiver);
+    define("NoMethodError", name_error);
+    let range_error = define("RangeError", standard_error);
+    define("FloatDomainError", range_error);
+    define("RegexpError", standard_error);
+    let runtime_error = define("RuntimeError", standard_error);
+    define("FrozenError", runtime_error);
+    let system_call_error = define("SystemCallError", standard_error);
+    system_call_error.add_builtin_class_method("new", system_call_error_new);
+    system_call_error.add_builtin_method_by_str("errno", system_call_error_errno);
+    define("ThreadError", standard_error);
+    define("TypeError", standard_error);
+    define("ZeroDivisionError", standard_error);
+    // Errno::*
+    let mut errno = Module::module();
+    BuiltinClass::set_toplevel_constant("Errno", errno);
+    for (name, num, _) in ERRNO_TABLE {
+        let mut err = Module::class_under(system_call_error);
+        err.set_const_by_str("Errno", Value::integer(*num as i64));
+        errno.set_const_by_str(name, err.into());
+    }
     class.into()
 }
 
+/// Errno classes with errno and description.
+const ERRNO_TABLE: &[(&str, i32, &str)] = &[
+    ("EPERM", 1, "Operation not permitted"),
+    ("ENOENT", 2, "No such file or directory"),
+    ("ESRCH", 3, "No such process"),
+    ("EINTR", 4, "Interrupted system call"),
+    ("EIO", 5, "Input/output error"),
+    ("EBADF", 9, "Bad file descriptor"),
+    ("ECHILD", 10, "No child processes"),
+    ("EAGAIN", 11, "Resource temporarily unavailable"),
+    ("ENOMEM", 12, "Cannot allocate memory"),
+    ("EACCES", 13, "Permission denied"),
+    ("EEXIST", 17, "File exists"),
+    ("ENOTDIR", 20, "Not a directory"),
+    ("EISDIR", 21, "Is a directory"),
+    ("EINVAL", 22, "Invalid argument"),
+    ("EMFILE", 24, "Too many open files"),
+    ("ENOSPC", 28, "No space left on device"),
+    ("ESPIPE", 29, "Illegal seek"),
+    ("EROFS", 30, "Read-only file system"),
+    ("EPIPE", 32, "Broken pipe"),
+    ("EDOM", 33, "Numerical argument out of domain"),
+    ("ERANGE", 34, "Numerical result out of range"),
+    ("ENOTEMPTY", 39, "Directory not empty"),
+    ("ECONNRESET", 104, "Connection reset by peer"),
+    ("ETIMEDOUT", 110, "Connection timed out"),
+    ("ECONNREFUSED", 111, "Connection refused"),
+];
+
+/// Get the name and description of `errno`.
+pub fn errno_entry(errno: i32) -> Option<&'static (&'static str, i32, &'static str)> {
+    ERRNO_TABLE.iter().find(|(_, num, _)| *num == errno)
+}
+
+/// Get Errno::* class for `errno`, or SystemCallError if `errno` is unknown.
+pub fn errno_class(errno: i32) -> Module {
+    let class = errno_entry(errno).and_then(|(name, _, _)| {
+        BuiltinClass::get_toplevel_constant("Errno")?
+            .into_module()
+            .get_const_by_str(name)
+    });
+    match class {
+        Some(class) => class.into_module(),
+        None => BuiltinClass::get_toplevel_constant("SystemCallError")
+            .unwrap()
+            .into_module(),
+    }
+}
+
 // Class methods
 
 fn exception_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
@@ -56,6 +155,81 @@ fn exception_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(new_instance)
 }
 
+/// Get the keyword argument `name` if given.
+fn keyword_arg(args: &Args, name: &str) -> Option<Value> {
+    args.kw_arg
+        .as_hash()?
+        .get(&Value::symbol_from_str(name))
+        .cloned()
+}
+
+/// NameError.new(msg = nil, name = nil, receiver: nil)
+fn name_error_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 2)?;
+    let msg_args = match args.len() {
+        0 => Args::new0(),
+        _ => Args::new1(args[0]),
+    };
+    let new_instance = exception_new(vm, self_val, &msg_args)?;
+    if args.len() == 2 {
+        new_instance.set_var_by_str("@name", args[1]);
+    }
+    if let Some(receiver) = keyword_arg(args, "receiver") {
+        new_instance.set_var_by_str("@receiver", receiver);
+    }
+    Ok(new_instance)
+}
+
+/// KeyError.new(msg = nil, receiver: nil, key: nil)
+fn key_error_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let new_instance = exception_new(vm, self_val, args)?;
+    for name in &["receiver", "key"] {
+        if let Some(val) = keyword_arg(args, name) {
+            new_instance.set_var_by_str(&format!("@{}", name), val);
+        }
+    }
+    Ok(new_instance)
+}
+
+/// NoMatchingPatternKeyError.new(msg = nil, matchee: nil, key: nil)
+fn no_matching_pattern_key_error_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let new_instance = exception_new(vm, self_val, args)?;
+    for name in &["matchee", "key"] {
+        if let Some(val) = keyword_arg(args, name) {
+            new_instance.set_var_by_str(&format!("@{}", name), val);
+        }
+    }
+    Ok(new_instance)
+}
+
+/// SystemCallError.new(msg = nil)
+///
+/// The message is prefixed with the description of errno of the class.
+fn system_call_error_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let errno = self_val
+        .into_module()
+        .get_const_by_str("Errno")
+        .and_then(|errno| errno.as_integer());
+    let description = match errno.and_then(|errno| errno_entry(errno as i32)) {
+        Some((_, _, description)) => *description,
+        None => "unknown error",
+    };
+    let msg = match args.len() {
+        0 => description.to_string(),
+        _ => format!(
+            "{} - {}",
+            description,
+            args[0].clone().expect_string("1st arg")?
+        ),
+    };
+    let new_instance = exception_new(vm, self_val, &Args::new1(Value::string(msg)))?;
+    if let Some(errno) = errno {
+        new_instance.set_var_by_str("@errno", Value::integer(errno));
+    }
+    Ok(new_instance)
+}
+
 fn exception_allocate(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     let self_val = self_val.into_module();
@@ -89,6 +263,240 @@ fn tos(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::string(err.message()))
 }
 
+fn eq(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let other = args[0];
+    if self_val.id() == other.id() {
+        return Ok(Value::true_val());
+    }
+    if other.if_exception().is_none() || self_val.get_class().id() != other.get_class().id() {
+        return Ok(Value::false_val());
+    }
+    for method in &["message", "backtrace"] {
+        let method = IdentId::get_id(method);
+        let lhs = vm.send0(method, self_val)?;
+        let rhs = vm.send0(method, other)?;
+        if !vm.eval_eq(rhs, lhs)? {
+            return Ok(Value::false_val());
+        }
+    }
+    Ok(Value::true_val())
+}
+
+/// Get frames where `self_val` was raised.
+fn frames(self_val: Value) -> Vec<Frame> {
+    match self_val.if_exception() {
+        Some(err) => err.backtrace.clone(),
+        None => vec![],
+    }
+}
+
+fn backtrace(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    if let Some(backtrace) = self_val.get_var(IdentId::get_id("@backtrace")) {
+        return Ok(backtrace);
+    }
+    let frames = frames(self_val);
+    if frames.is_empty() {
+        return Ok(Value::nil());
+    }
+    let backtrace = frames
+        .iter()
+        .map(|frame| Value::string(frame.to_s()))
+        .collect();
+    Ok(Value::array_from(backtrace))
+}
+
+fn backtrace_locations(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    // Locations are not available when the backtrace was given by set_backtrace.
+    if self_val.get_var(IdentId::get_id("@backtrace")).is_some() {
+        return Ok(Value::nil());
+    }
+    let frames = frames(self_val);
+    if frames.is_empty() {
+        return Ok(Value::nil());
+    }
+    let mut locations = vec![];
+    for frame in &frames {
+        locations.push(location(vm, frame)?);
+    }
+    Ok(Value::array_from(locations))
+}
+
+/// Create Thread::Backtrace::Location object for `frame`.
+pub fn location(vm: &mut VM, frame: &Frame) -> VMResult {
+    let class = BuiltinClass::get_toplevel_constant("Thread")
+        .and_then(|thread| thread.into_module().get_const_by_str("Backtrace"))
+        .and_then(|backtrace| backtrace.into_module().get_const_by_str("Location"))
+        .unwrap();
+    let args = Args::new3(
+        Block::None,
+        Value::string(frame.path()),
+        Value::integer(frame.line()),
+        Value::string(&frame.label),
+    );
+    vm.send(IdentId::NEW, class, &args)
+}
+
+fn set_backtrace(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let backtrace = args[0];
+    let backtrace = if backtrace.is_nil() {
+        backtrace
+    } else if backtrace.as_string().is_some() {
+        Value::array_from(vec![backtrace])
+    } else {
+        match backtrace.as_array() {
+            Some(ary) if ary.elements.iter().all(|elem| elem.as_string().is_some()) => backtrace,
+            _ => return Err(RubyError::typeerr("Backtrace must be Array of String.")),
+        }
+    };
+    self_val.set_var_by_str("@backtrace", backtrace);
+    Ok(backtrace)
+}
+
+fn cause(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val
+        .get_var(IdentId::get_id("/cause"))
+        .unwrap_or_default())
+}
+
+/// Get the `highlight:` keyword argument.
+fn highlight_arg(args: &Args) -> bool {
+    keyword_arg(args, "highlight").map_or(false, |val| val.to_bool())
+}
+
+/// Return "message (ClassName)", decorated with escape sequences if `highlight` is true.
+fn message_with_class(vm: &mut VM, self_val: Value, highlight: bool) -> Result<String, RubyError> {
+    let message = vm.send0(IdentId::get_id("message"), self_val)?;
+    let message = message.val_to_s(vm)?;
+    let class_name = self_val.get_class_name();
+    Ok(match (message.is_empty(), highlight) {
+        (true, false) => class_name,
+        (true, true) => format!("\x1b[1;4m{}\x1b[m", class_name),
+        (false, false) => format!("{} ({})", message, class_name),
+        (false, true) => format!(
+            "\x1b[1m{} (\x1b[1;4m{}\x1b[m\x1b[1m)\x1b[m",
+            message, class_name
+        ),
+    })
+}
+
+/// detailed_message(highlight: false) -> String
+fn detailed_message(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let message = message_with_class(vm, self_val, highlight_arg(args))?;
+    Ok(Value::string(message))
+}
+
+/// full_message(highlight: false, order: :top) -> String
+fn full_message(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let highlight = highlight_arg(args);
+    let top = match keyword_arg(args, "order") {
+        None => true,
+        Some(order) if order.as_symbol() == Some(IdentId::get_id("top")) => true,
+        Some(order) if order.as_symbol() == Some(IdentId::get_id("bottom")) => false,
+        Some(order) => {
+            let order = vm.val_inspect(order)?;
+            return Err(RubyError::argument(format!(
+                "expected :top or :bottom as order: {}",
+                order
+            )));
+        }
+    };
+    let message = message_with_class(vm, self_val, highlight)?;
+    let backtrace = vm.send0(IdentId::get_id("backtrace"), self_val)?;
+    let backtrace: Vec<String> = match backtrace.as_array() {
+        Some(ary) => {
+            let mut backtrace = vec![];
+            for elem in &ary.elements {
+                backtrace.push(elem.val_to_s(vm)?.to_string());
+            }
+            backtrace
+        }
+        None => vec![],
+    };
+    let (first, rest) = match backtrace.split_first() {
+        Some((first, rest)) => (format!("{}: ", first), rest),
+        None => (String::new(), &backtrace[..]),
+    };
+    let mut full = String::new();
+    if top {
+        full += &format!("{}{}\n", first, message);
+        for frame in rest {
+            full += &format!("\tfrom {}\n", frame);
+        }
+    } else {
+        if !rest.is_empty() {
+            full += "Traceback (most recent call last):\n";
+        }
+        for (i, frame) in rest.iter().enumerate().rev() {
+            full += &format!("\t{}: from {}\n", i + 1, frame);
+        }
+        full += &format!("{}{}\n", first, message);
+    }
+    Ok(Value::string(full))
+}
+
+// Instance methods of NameError, KeyError, NoMatchingPatternKeyError and SystemCallError
+
+fn name_error_name(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val
+        .get_var(IdentId::get_id("@name"))
+        .unwrap_or_default())
+}
+
+fn key_error_key(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    match self_val.get_var(IdentId::get_id("@key")) {
+        Some(key) => Ok(key),
+        None => Err(RubyError::argument("No key is available.")),
+    }
+}
+
+fn error_receiver(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    match self_val.get_var(IdentId::get_id("@receiver")) {
+        Some(receiver) => Ok(receiver),
+        None => Err(RubyError::argument("No receiver is available.")),
+    }
+}
+
+fn error_matchee(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    match self_val.get_var(IdentId::get_id("@matchee")) {
+        Some(matchee) => Ok(matchee),
+        None => Err(RubyError::argument("No matchee is available.")),
+    }
+}
+
+fn system_call_error_errno(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val
+        .get_var(IdentId::get_id("@errno"))
+        .unwrap_or_default())
+}
+
+// Instance methods of UncaughtThrowError
+
+fn uncaught_throw_tag(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val
+        .get_var(IdentId::get_id("@tag"))
+        .unwrap_or_default())
+}
+
+fn uncaught_throw_value(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val
+        .get_var(IdentId::get_id("@value"))
+        .unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
@@ -99,9 +507,10 @@ mod tests {
         assert Exception, StandardError.superclass
         assert StandardError, RuntimeError.superclass
         assert StandardError, ArgumentError.superclass
-        assert StandardError, NoMethodError.superclass
+        assert NameError, NoMethodError.superclass
         assert StandardError, TypeError.superclass
         assert RuntimeError, FrozenError.superclass
+        assert ArgumentError, UncaughtThrowError.superclass
 
         assert "#<Exception: Exception>", Exception.new.inspect
         assert "#<Exception: foo>", Exception.new("foo").inspect
@@ -118,10 +527,182 @@ mod tests {
         assert "#<NoMethodError: foo>", NoMethodError.new("foo").inspect
         assert "NoMethodError", NoMethodError.new.to_s
         assert "foo", NoMethodError.new("foo").to_s
-        assert StandardError.singleton_class, NoMethodError.singleton_class.superclass
+        assert NameError.singleton_class, NoMethodError.singleton_class.superclass
 
         assert StandardError.singleton_class, TypeError.singleton_class.superclass
         "##;
         assert_script(program);
     }
+
+    #[test]
+    fn exception_hierarchy() {
+        let program = r##"
+        assert StandardError, NameError.superclass
+        assert IndexError, KeyError.superclass
+        assert IndexError, StopIteration.superclass
+        assert StandardError, IOError.superclass
+        assert IOError, EOFError.superclass
+        assert RangeError, FloatDomainError.superclass
+        assert ScriptError, LoadError.superclass
+        assert ScriptError, NotImplementedError.superclass
+        assert Exception, ScriptError.superclass
+        assert Exception, SecurityError.superclass
+        assert SignalException, Interrupt.superclass
+        assert StandardError, SystemCallError.superclass
+        assert SystemCallError, Errno::ENOENT.superclass
+        assert "Errno::ENOENT", Errno::ENOENT.name
+        assert 2, Errno::ENOENT::Errno
+        assert 13, Errno::EACCES.new.errno
+        assert "No such file or directory - foo", Errno::ENOENT.new("foo").message
+
+        e = NameError.new("msg", :foo, receiver: 3)
+        assert :foo, e.name
+        assert 3, e.receiver
+        e = KeyError.new("msg", receiver: {}, key: :k)
+        assert :k, e.key
+        assert({}, e.receiver)
+        assert StandardError, NoMatchingPatternError.superclass
+        assert NoMatchingPatternError, NoMatchingPatternKeyError.superclass
+        e = NoMatchingPatternKeyError.new("msg", matchee: {a: 1}, key: :b)
+        assert :b, e.key
+        assert({a: 1}, e.matchee)
+        "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn rescue_builtin_errors() {
+        let program = r##"
+        def err
+          yield
+        rescue => e
+          e
+        end
+        e = err { 1.no_such_method }
+        assert NoMethodError, e.class
+        assert :no_such_method, e.name
+        assert 1, e.receiver
+        e = err { NoSuchConst }
+        assert NameError, e.class
+        assert :NoSuchConst, e.name
+        e = err { {a: 1}.fetch(:b) }
+        assert KeyError, e.class
+        assert :b, e.key
+        assert({a: 1}, e.receiver)
+        assert ArgumentError, err { Integer("x") }.class
+        assert TypeError, err { Integer(nil) }.class
+        assert ZeroDivisionError, err { 1 / 0 }.class
+        assert FloatDomainError, err { Integer(1e308 * 10.0) }.class
+        assert RuntimeError, err { raise "foo" }.class
+        assert ArgumentError, err { raise ArgumentError, "bar" }.class
+        assert "bar", err { raise ArgumentError, "bar" }.message
+        e = err { File.read("/no/such/file") }
+        assert Errno::ENOENT, e.class
+        assert 2, e.errno
+        begin
+          raise IndexError
+        rescue StandardError => e
+          assert IndexError, e.class
+        end
+        begin
+          1.no_such_method
+        rescue NameError => e
+          assert NoMethodError, e.class
+        end
+        "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn exception_backtrace() {
+        let program = r##"
+        def foo
+          raise "boom"
+        end
+        def bar
+          [1].each { foo }
+        end
+        begin
+          bar
+        rescue => e
+          locs = e.backtrace_locations
+          assert ["foo", "block in bar", "bar"], locs[0, 3].map(&:label)
+          assert [3, 6, 6], locs[0, 3].map(&:lineno)
+          assert "bar", locs[1].base_label
+          assert e.backtrace[0].end_with?(":3:in `foo'"), true
+          assert e.backtrace.size, locs.size
+          assert "#{e.backtrace[0]}: boom (RuntimeError)\n", e.full_message(highlight: false).lines[0]
+          assert e.backtrace.size, e.full_message(highlight: false).lines.size
+          assert "boom (RuntimeError)\n", e.full_message(order: :bottom).lines[-1].sub(/.*: /, "")
+          assert "boom (RuntimeError)", e.detailed_message
+          assert true, e == e.dup
+        end
+
+        assert nil, Exception.new.backtrace
+        assert nil, Exception.new.backtrace_locations
+        e = RuntimeError.new("x")
+        assert ["a:1"], e.set_backtrace("a:1")
+        assert ["a:1"], e.backtrace
+        assert nil, e.backtrace_locations
+        assert "a:1: x (RuntimeError)\n", e.full_message
+        def err_at(class_, msg)
+          e = class_.new(msg)
+          e.set_backtrace(["a:1"])
+          e
+        end
+        assert true, e == err_at(RuntimeError, "x")
+        assert false, e == err_at(RuntimeError, "y")
+        assert false, e == err_at(ArgumentError, "x")
+        "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn exception_cause() {
+        let program = r##"
+        begin
+          begin
+            raise "first"
+          rescue => e1
+            raise ArgumentError, "second"
+          end
+        rescue => e2
+          assert "second", e2.message
+          assert e1, e2.cause
+          assert nil, e1.cause
+        end
+        begin
+          begin
+            raise "first"
+          rescue
+            1.no_such_method
+          end
+        rescue => e
+          assert NoMethodError, e.class
+          assert "first", e.cause.message
+        end
+        begin
+          begin
+            raise "first"
+          rescue
+            raise
+          end
+        rescue => e
+          assert "first", e.message
+          assert nil, e.cause
+        end
+        c = StandardError.new("c")
+        begin
+          raise "x", cause: c
+        rescue => e
+          assert c, e.cause
+        end
+        begin
+          raise "x", cause: nil
+        rescue => e
+          assert nil, e.cause
+        end
+        "##;
+        assert_script(program);
+    }
 }
@@ -44,10 +44,7 @@ fn string_to_path(_: &mut VM, mut string: Value, msg: &str) -> Result<PathBuf, R
 fn canonicalize_path(_: &mut VM, path: PathBuf) -> Result<PathBuf, RubyError> {
     match path.canonicalize() {
         Ok(file) => Ok(file),
-        Err(_) => Err(RubyError::argument(format!(
-            "Invalid file path. {:?}",
-            path
-        ))),
+        Err(err) => Err(RubyError::errno(&err, path.to_string_lossy())),
     }
 }
 
@@ -60,10 +57,7 @@ pub fn string_to_canonicalized_path(
     let path = string_to_path(vm, string, msg)?;
     match path.canonicalize() {
         Ok(file) => Ok(file),
-        Err(_) => Err(RubyError::argument(format!(
-            "{} is an invalid filename. {:?}",
-            msg, path
-        ))),
+        Err(err) => Err(RubyError::errno(&err, path.to_string_lossy())),
     }
 }
 
@@ -133,17 +127,12 @@ fn binread(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     let filename = string_to_canonicalized_path(vm, args[0], "1st arg")?;
     let mut file = match File::open(&filename) {
         Ok(file) => file,
-        Err(_) => {
-            return Err(RubyError::internal(format!(
-                "Can not open file. {:?}",
-                &filename
-            )))
-        }
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     let mut contents = vec![];
     match file.read_to_end(&mut contents) {
         Ok(file) => file,
-        Err(_) => return Err(RubyError::internal("Could not read the file.")),
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     Ok(Value::bytes(contents))
 }
@@ -154,17 +143,12 @@ fn read(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     let filename = string_to_path(vm, args[0], "1st arg")?;
     let mut file = match File::open(&filename) {
         Ok(file) => file,
-        Err(_) => {
-            return Err(RubyError::internal(format!(
-                "Can not open file. {:?}",
-                &filename
-            )))
-        }
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     let mut contents = String::new();
     match file.read_to_string(&mut contents) {
         Ok(file) => file,
-        Err(_) => return Err(RubyError::internal("Could not read the file.")),
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     Ok(Value::string(contents))
 }
@@ -175,17 +159,12 @@ fn readlines(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     let filename = string_to_path(vm, args[0], "1st arg")?;
     let mut file = match File::open(&filename) {
         Ok(file) => file,
-        Err(_) => {
-            return Err(RubyError::internal(format!(
-                "Can not open file. {:?}",
-                &filename
-            )))
-        }
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     let mut contents = String::new();
     match file.read_to_string(&mut contents) {
         Ok(file) => file,
-        Err(_) => return Err(RubyError::internal("Could not read the file.")),
+        Err(err) => return Err(RubyError::errno(&err, filename.to_string_lossy())),
     };
     let ary = contents.split('\n').map(|s| Value::string(s)).collect();
     Ok(Value::array_from(ary))
@@ -200,12 +179,7 @@ fn write(_: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     let contents = arg1.expect_string("2nd arg")?;
     match std::fs::write(&filename, contents) {
         Ok(()) => {}
-        Err(err) => {
-            return Err(RubyError::internal(format!(
-                "Can not create or write file. {:?}\n{:?}",
-                &filename, err
-            )))
-        }
+        Err(err) => return Err(RubyError::errno(&err, filename)),
     };
     Ok(Value::integer(contents.len() as i64))
 }
@@ -12,21 +12,44 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("<=>", cmp);
     class.add_builtin_method_by_str("floor", floor);
     class.add_builtin_method_by_str("to_i", toi);
+    class.add_builtin_method_by_str("to_f", tof);
+    class.add_builtin_method_by_str("to_r", tor);
     class.into()
 }
 
+/// Convert `f` to a string in the same format as Float#to_s.
+///
+/// Numbers less than 1e-4 or not less than 1e16 in magnitude are shown in scientific notation
+/// like `1.0e+20`.
+pub fn float_to_s(f: f64) -> String {
+    if f.is_nan() {
+        return "NaN".to_string();
+    } else if f.is_infinite() {
+        return if f > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    let sci = format!("{:e}", f);
+    let (mantissa, exp) = sci.split_at(sci.find('e').unwrap());
+    let exp: i32 = exp[1..].parse().unwrap();
+    if -4 <= exp && exp < 16 {
+        if f.fract() == 0.0 {
+            format!("{:.1}", f)
+        } else {
+            f.to_string()
+        }
+    } else {
+        let dot = if mantissa.contains('.') { "" } else { ".0" };
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{}{}e{}{:02}", mantissa, dot, sign, exp.abs())
+    }
+}
+
 // Class methods
 
 // Instance methods
 fn inspect(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     let f = self_val.as_float().unwrap();
-    let s = if f.fract() == 0.0 {
-        format!("{:.1}", f)
-    } else {
-        f.to_string()
-    };
-    Ok(Value::string(s))
+    Ok(Value::string(float_to_s(f)))
 }
 
 fn add(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
@@ -118,6 +141,17 @@ fn toi(_: &mut VM, self_val: Value, _: &Args) -> VMResult {
     Ok(Value::integer(num))
 }
 
+fn tof(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(self_val)
+}
+
+fn tor(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let (num, den) = numeric::float_to_rational(self_val.as_float().unwrap())?;
+    numeric::rational(num, den)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
@@ -127,6 +161,12 @@ mod tests {
         assert "34.5", 34.5.to_s
         assert "34.5", 34.5.inspect
         assert "34.0", 34.000.to_s
+        assert "1.0e+20", 1e20.to_s
+        assert "1.0e+16", 1e16.to_s
+        assert "1000000000000000.0", 1e15.to_s
+        assert "-1.5e-05", (-0.000015).to_s
+        assert "0.0001", 0.0001.to_s
+        assert "-0.0", (-0.0).to_s
         "#;
         assert_script(program);
     }
@@ -41,6 +41,7 @@ fn disable(_: &mut VM, _: Value, args: &Args) -> VMResult {
 
 fn start(vm: &mut VM, _: Value, _: &Args) -> VMResult {
     vm.globals.gc();
+    vm.run_finalizers();
     Ok(Value::nil())
 }
 
@@ -29,6 +29,9 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("compare_by_identity", compare_by_identity);
     class.add_builtin_method_by_str("sort", sort);
     class.add_builtin_method_by_str("invert", invert);
+    class.add_builtin_method_by_str("default", default);
+    class.add_builtin_method_by_str("default=", set_default);
+    class.add_builtin_method_by_str("deconstruct_keys", deconstruct_keys);
 
     class.add_builtin_class_method("new", hash_new);
     class.into()
@@ -37,12 +40,21 @@ pub fn init() -> Value {
 // Class methods
 
 fn hash_new(_: &mut VM, _: Value, args: &Args) -> VMResult {
-    args.check_args_num(0)?;
+    args.check_args_range(0, 1)?;
     let map = FxIndexMap::default();
     let hash = Value::hash_from_map(map);
+    if args.len() == 1 {
+        hash.set_var_by_str("/default", args[0]);
+    }
     Ok(hash)
 }
 
+/// Get the default value of `hash`, which is returned for missing keys.
+pub fn get_default(hash: Value) -> Value {
+    hash.get_var(IdentId::get_id("/default"))
+        .unwrap_or_default()
+}
+
 // Instance methods
 
 fn inspect(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
@@ -62,7 +74,12 @@ fn clear(_: &mut VM, mut self_val: Value, args: &Args) -> VMResult {
 fn clone(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     let hash = self_val.as_hash().unwrap();
-    Ok(Value::hash_from(hash.clone()))
+    let new_hash = Value::hash_from(hash.clone());
+    let default = get_default(self_val);
+    if !default.is_nil() {
+        new_hash.set_var_by_str("/default", default);
+    }
+    Ok(new_hash)
 }
 
 fn compact(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
@@ -208,8 +225,12 @@ fn fetch(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
                     if args.len() == 2 {
                         args[1]
                     } else {
-                        // TODO: Should be KeyError.
-                        return Err(RubyError::argument("Key not found."));
+                        let inspect = vm.val_inspect(key)?;
+                        return Err(RubyError::key(
+                            format!("Key not found: {}", inspect),
+                            self_val,
+                            key,
+                        ));
                     }
                 }
                 block => vm.eval_block(block, &Args::new1(key))?,
@@ -255,6 +276,22 @@ fn invert(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::hash_from_map(new_hash))
 }
 
+fn default(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(get_default(self_val))
+}
+
+fn set_default(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    self_val.set_var_by_str("/default", args[0]);
+    Ok(args[0])
+}
+
+fn deconstruct_keys(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    Ok(self_val)
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::*;
@@ -263,6 +300,17 @@ mod test {
     fn hash_new() {
         let program = r#"
             assert ({}), Hash.new
+            h = Hash.new(5)
+            h[:a] = 1
+            assert 1, h[:a]
+            assert 5, h[:b]
+            assert 5, h.default
+            assert({a: 1}, h)
+            assert 5, h.clone[:c]
+            h.default = 0
+            assert 0, h[:b]
+            assert nil, {}.default
+            assert_error { Hash.new(1, 2) }
             "#;
         assert_script(program);
     }
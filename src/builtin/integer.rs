@@ -3,7 +3,7 @@ use crate::*;
 pub fn init() -> Value {
     let class = Module::class_under(BuiltinClass::numeric());
     BuiltinClass::set_toplevel_constant("Integer", class);
-    class.add_builtin_method_by_str("to_s", inspect);
+    class.add_builtin_method_by_str("to_s", to_s);
     class.add_builtin_method_by_str("inspect", inspect);
     class.add_builtin_method_by_str("+", add);
     class.add_builtin_method_by_str("-", sub);
@@ -28,6 +28,7 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("to_f", tof);
     class.add_builtin_method_by_str("to_i", toi);
     class.add_builtin_method_by_str("to_int", toi);
+    class.add_builtin_method_by_str("to_r", tor);
     class.add_builtin_method_by_str("floor", floor);
     class.add_builtin_method_by_str("even?", even);
     class.add_builtin_method_by_str("odd?", odd);
@@ -46,6 +47,32 @@ fn inspect(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::string(i.to_string()))
 }
 
+/// to_s(base = 10) -> String
+fn to_s(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let i = self_val.as_integer().unwrap();
+    let base = match args.len() {
+        0 => 10,
+        _ => numeric::expect_radix(args[0].expect_integer("Base")?)?,
+    };
+    if base == 10 {
+        return Ok(Value::string(i.to_string()));
+    }
+    let mut n = (i as i128).abs() as u64;
+    let mut digits = vec![];
+    loop {
+        digits.push(std::char::from_digit((n % base as u64) as u32, base).unwrap());
+        n /= base as u64;
+        if n == 0 {
+            break;
+        }
+    }
+    if i < 0 {
+        digits.push('-');
+    }
+    Ok(Value::string(digits.iter().rev().collect::<String>()))
+}
+
 fn add(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let lhs = self_val.to_real().unwrap();
@@ -307,6 +334,11 @@ fn toi(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(self_val)
 }
 
+fn tor(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    numeric::rational(self_val.as_integer().unwrap(), 1)
+}
+
 fn even(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     let num = self_val.as_integer().unwrap();
@@ -349,6 +381,23 @@ mod tests {
         assert_script(program);
     }
 
+    #[test]
+    fn integer_to_s() {
+        let program = r#"
+        assert "255", 255.to_s
+        assert "11111111", 255.to_s(2)
+        assert "-ff", (-255).to_s(16)
+        assert "z", 35.to_s(36)
+        assert "0", 0.to_s(7)
+        assert_error { 1.to_s(1) }
+        assert_error { 1.to_s(37) }
+        assert Rational(5, 1), 5.to_r
+        assert Rational(3, 4), 0.75.to_r
+        assert Rational(-5, 2), (-2.5).to_r
+        "#;
+        assert_script(program);
+    }
+
     #[test]
     fn integer_cmp() {
         let program = r#"
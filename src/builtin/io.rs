@@ -6,6 +6,12 @@ pub fn init(globals: &mut Globals) -> Value {
     io_class.add_builtin_method_by_str("isatty", isatty);
     io_class.add_builtin_method_by_str("tty?", isatty);
     io_class.add_builtin_method_by_str("flush", flush);
+    io_class.add_builtin_method_by_str("read", read);
+    io_class.add_builtin_method_by_str("gets", gets);
+    io_class.add_builtin_method_by_str("readlines", readlines);
+    io_class.add_builtin_method_by_str("each_line", each_line);
+    io_class.add_builtin_method_by_str("rewind", rewind);
+    io_class.add_builtin_method_by_str("eof?", eof);
     BuiltinClass::set_toplevel_constant("IO", io_class);
     let stdout = Value::ordinary_object(io_class);
     BuiltinClass::set_toplevel_constant("STDOUT", stdout);
@@ -17,6 +23,42 @@ pub fn init(globals: &mut Globals) -> Value {
 
 use std::io::{self, Write};
 
+/// Create a readable IO object which holds `data`. (used for DATA)
+pub fn new_data_io(data: String) -> Value {
+    let io_class = BuiltinClass::get_toplevel_constant("IO").unwrap();
+    let io = Value::ordinary_object(Module::new(io_class));
+    io.set_var_by_str("/data", Value::string(data));
+    io.set_var_by_str("/pos", Value::integer(0));
+    io
+}
+
+/// Get the contents and the current position of a readable IO.
+fn read_state(self_val: Value) -> Result<(String, usize), RubyError> {
+    let data = match self_val.get_var(IdentId::get_id("/data")) {
+        Some(data) => data.as_string().unwrap().to_string(),
+        None => return Err(RubyError::io("Not opened for reading.")),
+    };
+    let pos = match self_val.get_var(IdentId::get_id("/pos")) {
+        Some(pos) => pos.expect_integer("Position")? as usize,
+        None => 0,
+    };
+    Ok((data, pos))
+}
+
+fn set_pos(self_val: Value, pos: usize) {
+    self_val.set_var_by_str("/pos", Value::integer(pos as i64));
+}
+
+/// Read lines from the current position to the end.
+fn read_lines(self_val: Value) -> Result<Vec<Value>, RubyError> {
+    let (data, pos) = read_state(self_val)?;
+    set_pos(self_val, data.len());
+    Ok(data[pos..]
+        .split_inclusive('\n')
+        .map(|line| Value::string(line))
+        .collect())
+}
+
 fn output(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     match args[0].as_string() {
@@ -40,3 +82,52 @@ fn flush(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     io::stdout().flush().unwrap();
     Ok(self_val)
 }
+
+fn read(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let (data, pos) = read_state(self_val)?;
+    set_pos(self_val, data.len());
+    Ok(Value::string(&data[pos..]))
+}
+
+fn gets(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let (data, pos) = read_state(self_val)?;
+    if pos >= data.len() {
+        return Ok(Value::nil());
+    }
+    let end = match data[pos..].find('\n') {
+        Some(i) => pos + i + 1,
+        None => data.len(),
+    };
+    set_pos(self_val, end);
+    Ok(Value::string(&data[pos..end]))
+}
+
+fn readlines(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let lines = read_lines(self_val)?;
+    Ok(Value::array_from(lines))
+}
+
+fn each_line(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let block = args.expect_block()?;
+    for line in read_lines(self_val)? {
+        vm.eval_block(block, &Args::new1(line))?;
+    }
+    Ok(self_val)
+}
+
+fn rewind(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    read_state(self_val)?;
+    set_pos(self_val, 0);
+    Ok(Value::integer(0))
+}
+
+fn eof(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let (data, pos) = read_state(self_val)?;
+    Ok(Value::bool(pos >= data.len()))
+}
@@ -0,0 +1,783 @@
+use crate::*;
+
+/// Default value of `max_nesting`.
+const MAX_NESTING: usize = 100;
+
+pub fn init() {
+    let mut module = Module::module();
+    BuiltinClass::set_toplevel_constant("JSON", module);
+    let json_error = Module::class_under(BuiltinClass::standard());
+    module.set_const_by_str("JSONError", json_error.into());
+    let parser_error = Module::class_under(json_error);
+    module.set_const_by_str("ParserError", parser_error.into());
+    module.set_const_by_str("NestingError", Module::class_under(parser_error).into());
+    module.set_const_by_str("GeneratorError", Module::class_under(json_error).into());
+    module.add_builtin_class_method("parse", parse);
+    module.add_builtin_class_method("parse!", parse);
+    module.add_builtin_class_method("load", load);
+    module.add_builtin_class_method("generate", generate);
+    module.add_builtin_class_method("dump", dump);
+    module.add_builtin_class_method("pretty_generate", pretty_generate);
+    BuiltinClass::object().add_builtin_method_by_str("to_json", to_json);
+}
+
+/// Generate an exception of JSON::`name` class.
+fn json_error(name: &str, msg: impl Into<String>) -> RubyError {
+    let class = BuiltinClass::get_toplevel_constant("JSON")
+        .unwrap()
+        .into_module()
+        .get_const_by_str(name)
+        .unwrap()
+        .into_module();
+    RubyError::value(Value::exception(class, RubyError::argument(msg)))
+}
+
+/// Get the value of option `name` from `opts`, which is a Hash or nil.
+fn get_option(opts: Value, name: &str) -> Option<Value> {
+    let hash = opts.as_hash()?;
+    hash.get(&Value::symbol_from_str(name))
+        .or_else(|| hash.get(&Value::string(name)))
+        .cloned()
+}
+
+/// Get options from the `index`-th argument or keyword arguments.
+fn options(args: &Args, index: usize) -> Value {
+    if args.len() > index {
+        args[index]
+    } else {
+        args.kw_arg
+    }
+}
+
+struct JsonParser<'a> {
+    source: &'a [u8],
+    pos: usize,
+    symbolize_names: bool,
+    max_nesting: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(source: &'a [u8]) -> Self {
+        JsonParser {
+            source,
+            pos: 0,
+            symbolize_names: false,
+            max_nesting: MAX_NESTING,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, msg: &str) -> RubyError {
+        let consumed = &self.source[..self.pos];
+        let line = consumed.iter().filter(|b| **b == b'\n').count() + 1;
+        let column = match consumed.iter().rposition(|b| *b == b'\n') {
+            Some(nl) => self.pos - nl,
+            None => self.pos + 1,
+        };
+        json_error(
+            "ParserError",
+            format!("{} at line {} column {}", msg, line, column),
+        )
+    }
+
+    fn error_unexpected(&self) -> RubyError {
+        if self.pos >= self.source.len() {
+            return self.error("unexpected end of input");
+        }
+        let rest = String::from_utf8_lossy(&self.source[self.pos..]);
+        let snippet: String = rest.chars().take(32).collect();
+        self.error(&format!("unexpected character: '{}'", snippet))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> Result<(), RubyError> {
+        if self.source[self.pos..].starts_with(keyword.as_bytes()) {
+            self.pos += keyword.len();
+            Ok(())
+        } else {
+            Err(self.error_unexpected())
+        }
+    }
+
+    fn parse_document(&mut self) -> VMResult {
+        self.skip_whitespace();
+        let val = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos < self.source.len() {
+            return Err(self.error_unexpected());
+        }
+        Ok(val)
+    }
+
+    fn parse_value(&mut self) -> VMResult {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => {
+                let s = self.parse_string()?;
+                Ok(Value::string(s))
+            }
+            Some(b't') => {
+                self.consume_keyword("true")?;
+                Ok(Value::true_val())
+            }
+            Some(b'f') => {
+                self.consume_keyword("false")?;
+                Ok(Value::false_val())
+            }
+            Some(b'n') => {
+                self.consume_keyword("null")?;
+                Ok(Value::nil())
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.error_unexpected()),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), RubyError> {
+        self.depth += 1;
+        if self.max_nesting != 0 && self.depth > self.max_nesting {
+            return Err(self.error(&format!("nesting of {} is too deep", self.depth)));
+        }
+        Ok(())
+    }
+
+    fn parse_array(&mut self) -> VMResult {
+        self.enter()?;
+        self.pos += 1;
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                elements.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error_unexpected()),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::array_from(elements))
+    }
+
+    fn parse_object(&mut self) -> VMResult {
+        self.enter()?;
+        self.pos += 1;
+        let mut map = FxIndexMap::default();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some(b'"') {
+                    return Err(self.error_unexpected());
+                }
+                let name = self.parse_string()?;
+                let key = if self.symbolize_names {
+                    Value::symbol_from_str(&name)
+                } else {
+                    Value::string(name)
+                };
+                self.skip_whitespace();
+                if self.peek() != Some(b':') {
+                    return Err(self.error_unexpected());
+                }
+                self.pos += 1;
+                self.skip_whitespace();
+                let val = self.parse_value()?;
+                map.insert(HashKey(key), val);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error_unexpected()),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::hash_from_map(map))
+    }
+
+    fn read_hex4(&mut self) -> Result<u32, RubyError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = match self.peek().and_then(|b| (b as char).to_digit(16)) {
+                Some(digit) => digit,
+                None => return Err(self.error("incomplete unicode character escape sequence")),
+            };
+            code = code * 16 + digit;
+            self.pos += 1;
+        }
+        Ok(code)
+    }
+
+    fn parse_string(&mut self) -> Result<String, RubyError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let b = match self.peek() {
+                Some(b) => b,
+                None => {
+                    self.pos = start;
+                    return Err(self.error_unexpected());
+                }
+            };
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let esc = match self.peek() {
+                        Some(esc) => esc,
+                        None => {
+                            self.pos = start;
+                            return Err(self.error_unexpected());
+                        }
+                    };
+                    self.pos += 1;
+                    let ch = match esc {
+                        b'b' => '\x08',
+                        b'f' => '\x0c',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let code = self.read_hex4()?;
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.source[self.pos..].starts_with(b"\\u")
+                            {
+                                // A surrogate pair.
+                                let save = self.pos;
+                                self.pos += 2;
+                                let low = self.read_hex4()?;
+                                if (0xdc00..0xe000).contains(&low) {
+                                    let code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                                    std::char::from_u32(code).unwrap()
+                                } else {
+                                    self.pos = save;
+                                    '\u{fffd}'
+                                }
+                            } else {
+                                std::char::from_u32(code).unwrap_or('\u{fffd}')
+                            }
+                        }
+                        esc => esc as char,
+                    };
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                }
+                b if b < 0x20 => {
+                    self.pos -= 1;
+                    return Err(self.error("invalid ASCII control character in string"));
+                }
+                b => bytes.push(b),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn parse_number(&mut self) -> VMResult {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => {
+                self.pos = start;
+                return Err(self.error_unexpected());
+            }
+        }
+        let mut is_float = false;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.pos = start;
+                return Err(self.error_unexpected());
+            }
+            self.skip_digits();
+            is_float = true;
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.pos = start;
+                return Err(self.error_unexpected());
+            }
+            self.skip_digits();
+            is_float = true;
+        }
+        if let Some(b'0'..=b'9') = self.peek() {
+            // Leading zeros are not allowed.
+            self.pos = start;
+            return Err(self.error_unexpected());
+        }
+        let s = std::str::from_utf8(&self.source[start..self.pos]).unwrap();
+        if is_float {
+            Ok(Value::float(s.parse::<f64>().unwrap()))
+        } else {
+            match s.parse::<i64>() {
+                Ok(i) => Ok(Value::integer(i)),
+                // Bignum is not supported yet. Do not lose precision silently.
+                Err(_) => Err(RubyError::range(format!(
+                    "Integer {} is out of range of Fixnum.",
+                    s
+                ))),
+            }
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+}
+
+struct Generator<'a> {
+    vm: &'a mut VM,
+    buf: String,
+    indent: String,
+    space: String,
+    object_nl: String,
+    array_nl: String,
+    allow_nan: bool,
+    max_nesting: usize,
+    depth: usize,
+    /// Arrays and Hashes being written, to detect circular references.
+    visiting: Vec<u64>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(vm: &'a mut VM) -> Self {
+        Generator {
+            vm,
+            buf: String::new(),
+            indent: String::new(),
+            space: String::new(),
+            object_nl: String::new(),
+            array_nl: String::new(),
+            allow_nan: false,
+            max_nesting: MAX_NESTING,
+            depth: 0,
+            visiting: vec![],
+        }
+    }
+
+    fn pretty(vm: &'a mut VM) -> Self {
+        let mut gen = Generator::new(vm);
+        gen.indent = "  ".to_string();
+        gen.space = " ".to_string();
+        gen.object_nl = "\n".to_string();
+        gen.array_nl = "\n".to_string();
+        gen
+    }
+
+    /// Apply options in `opts`, which is a Hash or nil.
+    fn configure(&mut self, opts: Value) -> Result<(), RubyError> {
+        if opts.is_nil() {
+            return Ok(());
+        }
+        opts.expect_hash("Options")?;
+        for (name, field) in &mut [
+            ("indent", &mut self.indent),
+            ("space", &mut self.space),
+            ("object_nl", &mut self.object_nl),
+            ("array_nl", &mut self.array_nl),
+        ] {
+            if let Some(mut val) = get_option(opts, name) {
+                **field = val.expect_string(name)?.to_string();
+            }
+        }
+        if let Some(val) = get_option(opts, "allow_nan") {
+            self.allow_nan = val.to_bool();
+        }
+        if let Some(val) = get_option(opts, "max_nesting") {
+            self.max_nesting = match val.as_integer() {
+                Some(i) if i > 0 => i as usize,
+                _ => 0,
+            };
+        }
+        Ok(())
+    }
+
+    fn newline_indent(&mut self, nl: bool) {
+        if nl {
+            self.buf += &self.object_nl;
+        } else {
+            self.buf += &self.array_nl;
+        }
+        for _ in 0..self.depth {
+            self.buf += &self.indent;
+        }
+    }
+
+    fn write_string(&mut self, s: &str) {
+        self.buf.push('"');
+        for ch in s.chars() {
+            match ch {
+                '"' => self.buf += "\\\"",
+                '\\' => self.buf += "\\\\",
+                '\x08' => self.buf += "\\b",
+                '\x0c' => self.buf += "\\f",
+                '\n' => self.buf += "\\n",
+                '\r' => self.buf += "\\r",
+                '\t' => self.buf += "\\t",
+                ch if (ch as u32) < 0x20 => self.buf += &format!("\\u{:04x}", ch as u32),
+                ch => self.buf.push(ch),
+            }
+        }
+        self.buf.push('"');
+    }
+
+    fn write_float(&mut self, f: f64) -> Result<(), RubyError> {
+        if f.is_nan() || f.is_infinite() {
+            let s = if f.is_nan() {
+                "NaN"
+            } else if f > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            };
+            if !self.allow_nan {
+                return Err(json_error(
+                    "GeneratorError",
+                    format!("{} not allowed in JSON", s),
+                ));
+            }
+            self.buf += s;
+        } else {
+            self.buf += &builtin::float::float_to_s(f);
+        }
+        Ok(())
+    }
+
+    /// Enter an Array or Hash `val`.
+    fn enter(&mut self, val: Value) -> Result<(), RubyError> {
+        self.depth += 1;
+        if self.max_nesting != 0 && self.depth > self.max_nesting {
+            return Err(json_error(
+                "NestingError",
+                format!("nesting of {} is too deep", self.depth),
+            ));
+        }
+        if self.visiting.contains(&val.id()) {
+            return Err(json_error(
+                "NestingError",
+                "circular data structures not supported",
+            ));
+        }
+        self.visiting.push(val.id());
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+        self.visiting.pop().unwrap();
+    }
+
+    fn write_value(&mut self, val: Value) -> Result<(), RubyError> {
+        match val.unpack() {
+            RV::Nil => self.buf += "null",
+            RV::True => self.buf += "true",
+            RV::False => self.buf += "false",
+            RV::Integer(i) => self.buf += &i.to_string(),
+            RV::Float(f) => self.write_float(f)?,
+            RV::Symbol(id) => self.write_string(&IdentId::get_ident_name(id)),
+            RV::Uninitialized => unreachable!(),
+            RV::Object(info) => match &info.kind {
+                ObjKind::String(RString::Bytes(_)) => {
+                    return Err(json_error(
+                        "GeneratorError",
+                        "source sequence is illegal/malformed utf-8",
+                    ))
+                }
+                ObjKind::String(rs) => {
+                    let s = rs.as_str().to_string();
+                    self.write_string(&s);
+                }
+                ObjKind::Array(aref) => {
+                    let elements = aref.elements.clone();
+                    self.write_array(val, &elements)?;
+                }
+                ObjKind::Hash(href) => {
+                    let entries: Vec<(Value, Value)> = href.iter().collect();
+                    self.write_object(val, &entries)?;
+                }
+                _ => self.write_other(val)?,
+            },
+        };
+        Ok(())
+    }
+
+    fn write_array(&mut self, val: Value, elements: &[Value]) -> Result<(), RubyError> {
+        if elements.is_empty() {
+            self.buf += "[]";
+            return Ok(());
+        }
+        self.enter(val)?;
+        self.buf.push('[');
+        for (i, elem) in elements.iter().enumerate() {
+            if i > 0 {
+                self.buf.push(',');
+            }
+            self.newline_indent(false);
+            self.write_value(*elem)?;
+        }
+        self.leave();
+        self.newline_indent(false);
+        self.buf.push(']');
+        Ok(())
+    }
+
+    fn write_object(&mut self, val: Value, entries: &[(Value, Value)]) -> Result<(), RubyError> {
+        if entries.is_empty() {
+            self.buf += "{}";
+            return Ok(());
+        }
+        self.enter(val)?;
+        self.buf.push('{');
+        for (i, (k, v)) in entries.iter().enumerate() {
+            if i > 0 {
+                self.buf.push(',');
+            }
+            self.newline_indent(true);
+            let key = match k.as_symbol() {
+                Some(id) => IdentId::get_ident_name(id),
+                None => k.val_to_s(self.vm)?.into_owned(),
+            };
+            self.write_string(&key);
+            self.buf.push(':');
+            self.buf += &self.space.clone();
+            self.write_value(*v)?;
+        }
+        self.leave();
+        self.newline_indent(true);
+        self.buf.push('}');
+        Ok(())
+    }
+
+    /// Write an object other than core classes, using its `to_json` if redefined.
+    fn write_other(&mut self, val: Value) -> Result<(), RubyError> {
+        let id = IdentId::get_id("to_json");
+        let method = MethodRepo::find_method_from_receiver(val, id);
+        if method.is_some() && method != BuiltinClass::object().get_method(id) {
+            let mut res = self.vm.send1(id, val, Value::nil())?;
+            self.buf += res.expect_string("Result of to_json")?;
+        } else {
+            let s = val.val_to_s(self.vm)?.into_owned();
+            self.write_string(&s);
+        }
+        Ok(())
+    }
+}
+
+/// Generate a JSON text from `val`.
+fn generate_json(mut gen: Generator, val: Value, opts: Value) -> VMResult {
+    gen.configure(opts)?;
+    gen.write_value(val)?;
+    Ok(Value::string(gen.buf))
+}
+
+// Class methods
+
+fn parse(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let opts = options(args, 1);
+    let source = args[0].expect_bytes("1st arg")?;
+    let mut parser = JsonParser::new(source);
+    if let Some(val) = get_option(opts, "symbolize_names") {
+        parser.symbolize_names = val.to_bool();
+    }
+    if let Some(val) = get_option(opts, "max_nesting") {
+        parser.max_nesting = match val.as_integer() {
+            Some(i) if i > 0 => i as usize,
+            _ => 0,
+        };
+    }
+    parser.parse_document()
+}
+
+fn load(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let source = args[0];
+    if source.is_nil() {
+        return Ok(Value::nil());
+    }
+    let source = if source.as_rstring().is_some() {
+        source
+    } else {
+        vm.send0(IdentId::get_id("read"), source)?
+    };
+    if source.expect_bytes("1st arg")?.is_empty() {
+        return Ok(Value::nil());
+    }
+    parse(vm, self_val, &Args::new1(source))
+}
+
+fn generate(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    generate_json(Generator::new(vm), args[0], options(args, 1))
+}
+
+fn pretty_generate(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    generate_json(Generator::pretty(vm), args[0], options(args, 1))
+}
+
+fn dump(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let mut gen = Generator::new(vm);
+    gen.allow_nan = true;
+    gen.max_nesting = 0;
+    let json = generate_json(gen, args[0], Value::nil())?;
+    if args.len() == 2 {
+        vm.send1(IdentId::get_id("write"), args[1], json)?;
+        Ok(args[1])
+    } else {
+        Ok(json)
+    }
+}
+
+// Instance methods of Object
+
+fn to_json(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let mut gen = Generator::new(vm);
+    gen.configure(options(args, 0))?;
+    let is_core = match self_val.as_rvalue() {
+        Some(info) => matches!(
+            info.kind,
+            ObjKind::Integer(_)
+                | ObjKind::Float(_)
+                | ObjKind::String(_)
+                | ObjKind::Array(_)
+                | ObjKind::Hash(_)
+        ),
+        None => true,
+    };
+    if is_core {
+        gen.write_value(self_val)?;
+    } else {
+        // Calling `write_value` here would invoke this method again.
+        let s = self_val.val_to_s(gen.vm)?.into_owned();
+        gen.write_string(&s);
+    }
+    Ok(Value::string(gen.buf))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::*;
+
+    #[test]
+    fn json_parse() {
+        let program = r##"
+        require 'json'
+        assert({"a" => 1, "b" => [true, false, nil], "c" => {"d" => -2.5}},
+          JSON.parse('{"a": 1, "b": [true, false, null], "c": {"d": -2.5e0}}'))
+        assert({a: [1, {b: 2}]}, JSON.parse('{"a":[1,{"b":2}]}', symbolize_names: true))
+        assert [], JSON.parse(" [ ] ")
+        assert 3, JSON.parse("3")
+        assert "str", JSON.parse('"str"')
+        assert 1000.0, JSON.parse("1e3")
+        assert 9223372036854775807, JSON.parse("9223372036854775807")
+        assert_error { JSON.parse("92233720368547758070") }
+        assert_error { JSON.parse("[-9223372036854775809]") }
+        assert -9223372036854775807 - 1, JSON.parse("-9223372036854775808")
+        assert 9.2233720368547758e+19, JSON.parse("92233720368547758070.0")
+        assert "\"\\/\b\f\n\r\t", JSON.parse('"\\"\\\\\\/\\b\\f\\n\\r\\t"')
+        assert "A€😀", JSON.parse('"\\u0041\\u20AC\\uD83D\\uDE00"')
+        assert "日本", JSON.parse('"日本"')
+        assert nil, JSON.load(nil)
+        assert_error { JSON.parse("") }
+        assert_error { JSON.parse("[1,]") }
+        assert_error { JSON.parse("01") }
+        assert_error { JSON.parse("[1] x") }
+        assert_error { JSON.parse("[" * 101 + "]" * 101) }
+        begin
+          JSON.parse("{\n  \"a\": tru }")
+        rescue JSON::ParserError => e
+          assert "unexpected character: 'tru }' at line 2 column 8", e.message
+        end
+        assert JSON::JSONError, JSON::ParserError.superclass
+        assert StandardError, JSON::JSONError.superclass
+        "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn json_generate() {
+        let program = r##"
+        assert '{"a":1,"b":[true,false,null],"c":"x"}', JSON.generate({a: 1, "b" => [true, false, nil], c: :x})
+        assert '[1.5,2.0,-3]', JSON.generate([1.5, 2.0, -3])
+        assert '"\"\\\\\n\u0001/é"', JSON.generate("\"\\\n\x01/é")
+        assert '{"1":2}', JSON.generate({1 => 2})
+        assert '[]', [].to_json
+        assert '{"a":{}}', {a: {}}.to_json
+        assert 'null', nil.to_json
+        assert '12', 12.to_json
+        assert '"sym"', :sym.to_json
+        assert '"str"', "str".to_json
+        assert '"1..2"', (1..2).to_json
+        expected = "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {\n    \"c\": null\n  },\n  \"d\": []\n}"
+        assert expected, JSON.pretty_generate({a: [1, 2], b: {c: nil}, d: []})
+        assert "[\n1,\n2\n]", JSON.generate([1, 2], {array_nl: "\n"})
+        assert '[1]', JSON.dump([1])
+
+        class Point
+          def initialize(x, y)
+            @x = x
+            @y = y
+          end
+          def to_json(*args)
+            {x: @x, y: @y}.to_json(*args)
+          end
+        end
+        assert '[{"x":1,"y":2}]', JSON.generate([Point.new(1, 2)])
+        assert '{"x":1,"y":2}', Point.new(1, 2).to_json
+        assert '[1.0e+20,1.5e-05,0.0001]', JSON.generate([1e20, 0.000015, 0.0001])
+        a = []
+        a << a
+        assert_error { JSON.generate(a) }
+        begin
+          JSON.dump(a)
+        rescue JSON::NestingError => e
+          assert "circular data structures not supported", e.message
+        end
+        h = {}
+        h[:h] = [h]
+        assert_error { JSON.dump(h) }
+        b = [1]
+        assert '[[1],[1]]', JSON.dump([b, b])
+        assert JSON::ParserError, JSON::NestingError.superclass
+        "##;
+        assert_script(program);
+    }
+}
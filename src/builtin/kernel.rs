@@ -19,6 +19,10 @@ pub fn init() -> Module {
     class.add_builtin_module_func("kind_of?", isa);
     class.add_builtin_module_func("__dir__", dir);
     class.add_builtin_module_func("__FILE__", file_);
+    class.add_builtin_module_func("__LINE__", line_);
+    class.add_builtin_module_func("__method__", method_);
+    class.add_builtin_module_func("caller", caller);
+    class.add_builtin_module_func("caller_locations", caller_locations);
     class.add_builtin_module_func("raise", raise);
     class.add_builtin_module_func("rand", rand_);
     class.add_builtin_module_func("loop", loop_);
@@ -28,11 +32,18 @@ pub fn init() -> Module {
     class.add_builtin_module_func("proc", proc);
     class.add_builtin_module_func("lambda", lambda);
     class.add_builtin_module_func("Integer", kernel_integer);
+    class.add_builtin_module_func("Float", kernel_float);
+    class.add_builtin_module_func("String", kernel_string);
+    class.add_builtin_module_func("Hash", kernel_hash);
+    class.add_builtin_module_func("Rational", kernel_rational);
     class.add_builtin_module_func("Complex", kernel_complex);
     class.add_builtin_module_func("Array", kernel_array);
     class.add_builtin_module_func("at_exit", at_exit);
     class.add_builtin_module_func("`", command);
     class.add_builtin_module_func("eval", eval);
+    class.add_builtin_module_func("binding", binding);
+    class.add_builtin_module_func("catch", catch);
+    class.add_builtin_module_func("throw", throw);
     class
 }
 /// Built-in function "puts".
@@ -120,12 +131,18 @@ fn assert_error(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     }
 }
 
+/// Libraries which are implemented natively and need not be loaded.
+const BUILTIN_FEATURES: &[&str] = &["json"];
+
 fn require(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let file_name = match args[0].as_string() {
         Some(string) => string,
         None => return Err(RubyError::argument("file name must be a string.")),
     };
+    if BUILTIN_FEATURES.contains(&file_name) {
+        return Ok(Value::false_val());
+    }
     let mut path = PathBuf::from(file_name);
     if path.is_absolute() {
         path.set_extension("rb");
@@ -239,6 +256,99 @@ fn file_(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     Ok(Value::string(path.to_string_lossy()))
 }
 
+fn line_(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let line = vm.backtrace().first().map_or(0, |frame| frame.line());
+    Ok(Value::integer(line))
+}
+
+fn method_(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    match vm.get_method_iseq().kind {
+        ISeqKind::Method(Some(id)) => Ok(Value::symbol(id)),
+        _ => Ok(Value::nil()),
+    }
+}
+
+/// Get frames of the call stack for caller(start = 1, length = nil)
+/// and caller_locations(start = 1, length = nil).
+fn caller_frames(vm: &mut VM, args: &Args) -> Result<Option<Vec<Frame>>, RubyError> {
+    args.check_args_range(0, 2)?;
+    let frames = vm.backtrace();
+    let (start, len) = match args.len() {
+        0 => (1, None),
+        _ => {
+            if let Some(range) = args[0].as_range() {
+                if args.len() == 2 {
+                    return Err(RubyError::argument("Wrong number of arguments."));
+                }
+                let start = range.start.expect_integer("Start of range")?;
+                let end = range.end.expect_integer("End of range")?;
+                let end = if end < 0 {
+                    frames.len() as i64 + end
+                } else {
+                    end
+                };
+                let len = end - start + if range.exclude { 0 } else { 1 };
+                (start, Some(std::cmp::max(0, len)))
+            } else {
+                let start = args[0].expect_integer("1st arg")?;
+                let len = match args.len() {
+                    2 => Some(args[1].expect_integer("2nd arg")?),
+                    _ => None,
+                };
+                (start, len)
+            }
+        }
+    };
+    if start < 0 {
+        return Err(RubyError::argument(format!("Negative level ({}).", start)));
+    }
+    if let Some(len) = len {
+        if len < 0 {
+            return Err(RubyError::argument(format!("Negative size ({}).", len)));
+        }
+    }
+    let start = start as usize;
+    if start > frames.len() {
+        return Ok(None);
+    }
+    let frames = frames.into_iter().skip(start);
+    Ok(Some(match len {
+        Some(len) => frames.take(len as usize).collect(),
+        None => frames.collect(),
+    }))
+}
+
+/// caller(start = 1, length = nil) -> [String] | nil
+/// caller(range) -> [String] | nil
+fn caller(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    match caller_frames(vm, args)? {
+        Some(frames) => Ok(Value::array_from(
+            frames
+                .iter()
+                .map(|frame| Value::string(frame.to_s()))
+                .collect(),
+        )),
+        None => Ok(Value::nil()),
+    }
+}
+
+/// caller_locations(start = 1, length = nil) -> [Thread::Backtrace::Location] | nil
+/// caller_locations(range) -> [Thread::Backtrace::Location] | nil
+fn caller_locations(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    match caller_frames(vm, args)? {
+        Some(frames) => {
+            let mut locations = vec![];
+            for frame in &frames {
+                locations.push(builtin::exception::location(vm, frame)?);
+            }
+            Ok(Value::array_from(locations))
+        }
+        None => Ok(Value::nil()),
+    }
+}
+
 /// raise -> ()
 /// fail -> ()
 /// raise(message, cause: $!) -> ()
@@ -246,27 +356,62 @@ fn file_(vm: &mut VM, _: Value, args: &Args) -> VMResult {
 /// raise(error_type, message = nil, backtrace = caller(0), cause: $!) -> ()
 /// fail(error_type, message = nil, backtrace = caller(0), cause: $!) -> ()
 fn raise(vm: &mut VM, _: Value, args: &Args) -> VMResult {
-    args.check_args_range(0, 2)?;
+    args.check_args_range(0, 3)?;
+    let mut err = raise_error(vm, args)?;
+    if args.len() == 3 {
+        let val = err.to_exception_val();
+        vm.send1(IdentId::get_id("set_backtrace"), val, args[2])?;
+        err = RubyError::value(val);
+    }
+    let cause = args
+        .kw_arg
+        .as_hash()
+        .and_then(|kw| kw.get(&Value::symbol_from_str("cause")).cloned());
+    if let Some(cause) = cause {
+        if !cause.is_nil() && cause.if_exception().is_none() {
+            return Err(RubyError::typeerr("Exception object expected."));
+        }
+        let val = err.to_exception_val();
+        val.set_var_by_str("/cause", cause);
+        err = RubyError::value(val);
+    }
+    Err(err)
+}
+
+/// Build an error to be raised by `raise`.
+fn raise_error(vm: &mut VM, args: &Args) -> Result<RubyError, RubyError> {
     match args.len() {
-        0 => Err(RubyError::none("")),
+        // Re-raise the exception being handled.
+        0 => match vm.handled_exception() {
+            Some(val) => Ok(RubyError::value(val)),
+            None => Ok(RubyError::none("unhandled exception")),
+        },
         1 => {
             if let Some(s) = args[0].as_string() {
-                Err(RubyError::none(s))
+                Ok(RubyError::none(s))
             } else if args[0].is_class() {
                 if args[0].is_exception_class() {
                     let method = vm.get_method_from_receiver(args[0], IdentId::NEW)?;
                     let val = vm.eval_method(method, args[0], &Args::new0())?;
-                    Err(RubyError::value(val))
+                    Ok(RubyError::value(val))
                 } else {
                     Err(RubyError::typeerr("Exception class/object expected."))
                 }
             } else if args[0].if_exception().is_some() {
-                Err(RubyError::value(args[0]))
+                Ok(RubyError::value(args[0]))
+            } else {
+                Err(RubyError::typeerr("Exception class/object expected."))
+            }
+        }
+        _ => {
+            if args[0].is_class() && args[0].is_exception_class() {
+                let method = vm.get_method_from_receiver(args[0], IdentId::NEW)?;
+                let val = vm.eval_method(method, args[0], &Args::new1(args[1]))?;
+                Ok(RubyError::value(val))
             } else {
                 Err(RubyError::typeerr("Exception class/object expected."))
             }
         }
-        _ => Err(RubyError::none(args[1].clone().expect_string("2nd arg")?)),
     }
 }
 
@@ -353,31 +498,196 @@ fn lambda(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     Ok(procobj)
 }
 
+/// Get `exception:` keyword argument, which defaults to true.
+fn exception_flag(args: &Args) -> bool {
+    match args
+        .kw_arg
+        .as_hash()
+        .and_then(|hash| hash.get(&Value::symbol_from_str("exception")).cloned())
+    {
+        Some(flag) => flag.to_bool(),
+        None => true,
+    }
+}
+
+/// Convert an error of conversion functions to nil if `exception: false` is given.
+fn conversion_result(args: &Args, res: VMResult) -> VMResult {
+    match res {
+        Err(_) if !exception_flag(args) => Ok(Value::nil()),
+        res => res,
+    }
+}
+
+/// Call the first conversion method in `methods` which `arg` responds to,
+/// and check the result with `check`.
+fn convert_by_method(
+    vm: &mut VM,
+    arg: Value,
+    methods: &[&str],
+    class_name: &str,
+    check: fn(Value) -> bool,
+) -> VMResult {
+    for method in methods {
+        let id = IdentId::get_id(method);
+        if let Some(method) = MethodRepo::find_method_from_receiver(arg, id) {
+            let res = vm.eval_method(method, arg, &Args::new0())?;
+            if check(res) {
+                return Ok(res);
+            }
+            return Err(RubyError::typeerr(format!(
+                "Can't convert {} to {} ({}#{} gives {}).",
+                arg.get_class_name(),
+                class_name,
+                arg.get_class_name(),
+                IdentId::get_ident_name(id),
+                res.get_class_name()
+            )));
+        }
+    }
+    Err(RubyError::no_implicit_conv(arg, class_name))
+}
+
+/// Integer(arg, base = nil, exception: true) -> Integer | nil
 fn kernel_integer(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let res = to_integer(vm, args);
+    conversion_result(args, res)
+}
+
+fn to_integer(vm: &mut VM, args: &Args) -> VMResult {
+    let arg = args[0];
+    let base = match args.len() {
+        2 if !args[1].is_nil() => Some(args[1].expect_integer("Base")?),
+        _ => None,
+    };
+    if let Some(rs) = arg.as_rstring() {
+        return match numeric::parse_integer(&rs.to_s(), base.unwrap_or(0), true)? {
+            Some(num) => Ok(Value::integer(num)),
+            None => {
+                let inspect = vm.val_inspect(arg)?;
+                Err(RubyError::argument(format!(
+                    "Invalid value for Integer(): {}",
+                    inspect
+                )))
+            }
+        };
+    }
+    if base.is_some() {
+        return Err(RubyError::argument("Base specified for non string value."));
+    }
+    match arg.unpack() {
+        RV::Integer(_) => Ok(arg),
+        RV::Float(num) if num.is_nan() || num.is_infinite() => {
+            Err(numeric::float_domain_error(num))
+        }
+        RV::Float(num) => Ok(Value::integer(num.trunc() as i64)),
+        RV::Nil => Err(RubyError::typeerr("Can't convert nil into Integer.")),
+        _ => convert_by_method(vm, arg, &["to_int", "to_i"], "Integer", |v| {
+            v.as_integer().is_some()
+        }),
+    }
+}
+
+/// Float(arg, exception: true) -> Float | nil
+fn kernel_float(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let res = to_float(vm, args[0]);
+    conversion_result(args, res)
+}
+
+fn to_float(vm: &mut VM, arg: Value) -> VMResult {
+    if let Some(rs) = arg.as_rstring() {
+        return match numeric::parse_float(&rs.to_s(), true) {
+            Some(num) => Ok(Value::float(num)),
+            None => {
+                let inspect = vm.val_inspect(arg)?;
+                Err(RubyError::argument(format!(
+                    "Invalid value for Float(): {}",
+                    inspect
+                )))
+            }
+        };
+    }
+    match arg.unpack() {
+        RV::Integer(num) => Ok(Value::float(num as f64)),
+        RV::Float(_) => Ok(arg),
+        RV::Nil => Err(RubyError::typeerr("Can't convert nil into Float.")),
+        _ => convert_by_method(vm, arg, &["to_f"], "Float", |v| v.as_float().is_some()),
+    }
+}
+
+/// String(arg) -> String
+fn kernel_string(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let arg = args[0];
+    if arg.as_rstring().is_some() {
+        return Ok(arg);
+    }
+    convert_by_method(vm, arg, &["to_str", "to_s"], "String", |v| {
+        v.as_rstring().is_some()
+    })
+}
+
+/// Hash(arg) -> Hash
+fn kernel_hash(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
-    let val = match args[0].unpack() {
-        RV::Integer(num) => num,
-        RV::Float(num) => num as i64,
-        RV::Object(obj) => match &obj.kind {
-            ObjKind::String(s) => match s.parse::<i64>() {
-                Some(num) => num,
+    let arg = args[0];
+    if arg.is_nil() || arg.as_array().map_or(false, |a| a.elements.is_empty()) {
+        return Ok(Value::hash_from_map(FxIndexMap::default()));
+    }
+    if arg.as_hash().is_some() {
+        return Ok(arg);
+    }
+    convert_by_method(vm, arg, &["to_hash"], "Hash", |v| v.as_hash().is_some())
+}
+
+/// Rational(num, den = 1, exception: true) -> Rational | nil
+fn kernel_rational(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let res = to_rational(vm, args);
+    conversion_result(args, res)
+}
+
+fn to_rational(vm: &mut VM, args: &Args) -> VMResult {
+    fn pair(vm: &mut VM, arg: Value) -> Result<(i64, i64), RubyError> {
+        if let Some(rs) = arg.as_rstring() {
+            return match numeric::parse_rational(&rs.to_s(), true)? {
+                Some(pair) => Ok(pair),
                 None => {
-                    let inspect = vm.val_inspect(args[0])?;
-                    return Err(RubyError::typeerr(format!(
-                        "Invalid value for Integer(): {}",
+                    let inspect = vm.val_inspect(arg)?;
+                    Err(RubyError::argument(format!(
+                        "Invalid value for convert(): {}",
                         inspect
-                    )));
+                    )))
                 }
-            },
+            };
+        }
+        match arg.unpack() {
+            RV::Integer(num) => Ok((num, 1)),
+            RV::Float(num) => numeric::float_to_rational(num),
+            RV::Nil => Err(RubyError::typeerr("Can't convert nil into Rational.")),
             _ => {
-                return Err(RubyError::no_implicit_conv(args[0], "Integer"));
+                let ivar = |name| {
+                    arg.get_var(IdentId::get_id(name))
+                        .and_then(|val| val.as_integer())
+                };
+                match (ivar("@numerator"), ivar("@denominator")) {
+                    (Some(num), Some(den)) if arg.get_class_name() == "Rational" => Ok((num, den)),
+                    _ => Err(RubyError::no_implicit_conv(arg, "Rational")),
+                }
             }
-        },
-        _ => {
-            return Err(RubyError::no_implicit_conv(args[0], "Integer"));
         }
+    }
+    let (num1, den1) = pair(vm, args[0])?;
+    let (num2, den2) = if args.len() == 2 {
+        pair(vm, args[1])?
+    } else {
+        (1, 1)
     };
-    Ok(Value::integer(val))
+    let overflow = || RubyError::range("Rational is too big to convert into i64.");
+    let num = num1.checked_mul(den2).ok_or_else(overflow)?;
+    let den = den1.checked_mul(num2).ok_or_else(overflow)?;
+    numeric::rational(num, den)
 }
 
 fn kernel_complex(_: &mut VM, _: Value, args: &Args) -> VMResult {
@@ -416,8 +726,13 @@ fn kernel_array(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
     Ok(res)
 }
 
-fn at_exit(_vm: &mut VM, _self_val: Value, _args: &Args) -> VMResult {
-    Ok(_self_val)
+/// at_exit { block } -> Proc
+fn at_exit(vm: &mut VM, _self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let block = args.expect_block()?;
+    let procobj = vm.create_proc(block)?;
+    vm.globals.at_exit_procs.push(procobj);
+    Ok(procobj)
 }
 
 /// TODO: Can not handle command args including ' or ".
@@ -442,12 +757,7 @@ fn command(_: &mut VM, _: Value, args: &Args) -> VMResult {
 fn eval(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     args.check_args_range(1, 4)?;
     let mut arg0 = args[0];
-    let program = arg0.expect_string("1st arg")?;
-    if args.len() > 1 {
-        if !args[1].is_nil() {
-            return Err(RubyError::argument("Currently, 2nd arg must be Nil."));
-        }
-    }
+    let program = arg0.expect_string("1st arg")?.to_string();
     let path = if args.len() > 2 {
         let mut arg2 = args[2];
         let name = arg2.expect_string("3rd arg")?;
@@ -455,14 +765,64 @@ fn eval(vm: &mut VM, _: Value, args: &Args) -> VMResult {
     } else {
         std::path::PathBuf::from("(eval)")
     };
-
-    let method = vm.parse_program_eval(path, program)?;
+    let line = if args.len() > 3 {
+        args[3].expect_integer("4th arg")?
+    } else {
+        1
+    };
+    if args.len() > 1 && !args[1].is_nil() {
+        if args[1].as_binding().is_none() {
+            return Err(RubyError::typeerr(format!(
+                "Wrong argument type {} (expected binding).",
+                args[1].get_class_name()
+            )));
+        }
+        return builtin::binding::eval_with_binding(vm, args[1], &program, path, line);
+    }
+    let method = vm.parse_program_eval(path, &program, line)?;
     let args = Args::new0();
     let outer = vm.context();
     let res = vm.eval_block(&Block::Block(method, outer), &args)?;
     Ok(res)
 }
 
+fn binding(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    Ok(vm.create_binding())
+}
+
+fn catch(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let block = args.expect_block()?;
+    let tag = if args.len() == 1 {
+        args[0]
+    } else {
+        Value::ordinary_object(BuiltinClass::object())
+    };
+    vm.eval_catch(tag, block)
+}
+
+fn throw(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let tag = args[0];
+    let val = if args.len() == 2 {
+        args[1]
+    } else {
+        Value::nil()
+    };
+    if vm.is_catch_tag(tag) {
+        return Err(RubyError::throw(tag, val));
+    }
+    let message = format!("uncaught throw {}", vm.val_inspect(tag)?);
+    let err_class = BuiltinClass::get_toplevel_constant("UncaughtThrowError")
+        .unwrap()
+        .into_module();
+    let err = Value::exception(err_class, RubyError::argument(message));
+    err.set_var_by_str("@tag", tag);
+    err.set_var_by_str("@value", val);
+    Err(RubyError::value(err))
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::*;
@@ -516,6 +876,73 @@ mod test {
         assert_error { Integer("13.55") }
         assert_error { Integer([1,3,6]) }
         assert_error { Integer(:"2") }
+        assert 255, Integer("0xff")
+        assert 5, Integer("0b101")
+        assert 8, Integer("0o10")
+        assert 8, Integer("010")
+        assert -1_000, Integer(" -1_000 ")
+        assert 255, Integer("ff", 16)
+        assert 7, Integer("111", 2)
+        assert_error { Integer("1__0") }
+        assert_error { Integer("09") }
+        assert_error { Integer(nil) }
+        assert_error { Integer(10, 2) }
+        assert nil, Integer("xyz", exception: false)
+        assert nil, Integer(nil, exception: false)
+        assert 3, Integer(Rational(7, 2))
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn kernel_conversions() {
+        let program = r#"
+        assert 1.0, Float(1)
+        assert 2.5, Float(2.5)
+        assert 150.0, Float("1.5e2")
+        assert -0.5, Float(" -.5 ")
+        assert 255.0, Float("0xff")
+        assert_error { Float("1.5x") }
+        assert_error { Float("") }
+        assert_error { Float(nil) }
+        assert nil, Float("x", exception: false)
+        assert "1", String(1)
+        assert "str", String("str")
+        assert "[1, 2]", String([1, 2])
+        assert({}, Hash(nil))
+        assert({}, Hash([]))
+        assert({a: 1}, Hash({a: 1}))
+        assert_error { Hash(1) }
+        assert_error { Hash([1]) }
+        r = Rational(6, 4)
+        assert 3, r.numerator
+        assert 2, r.denominator
+        assert "3/2", r.to_s
+        assert "(3/2)", r.inspect
+        assert Rational(-1, 2), Rational(1, -2)
+        assert Rational(1, 4), Rational(0.25)
+        assert Rational(3, 4), Rational("3/4")
+        assert Rational(5, 2), Rational("1.25", "0.5")
+        assert Rational(2, 1), Rational(1, Rational(1, 2))
+        assert_error { Rational(1, 0) }
+        assert_error { Rational("x") }
+        assert nil, Rational("x", exception: false)
+        assert Rational(5, 6), Rational(1, 2) + Rational(1, 3)
+        assert Rational(1, 6), Rational(1, 2) - Rational(1, 3)
+        assert Rational(3, 2), Rational(1, 2) * 3
+        assert Rational(1, 6), Rational(1, 2) / 3
+        assert 0.75, Rational(1, 2) + 0.25
+        assert true, Rational(1, 2) < Rational(2, 3)
+        assert true, Rational(4, 2) == 2
+        assert 1, Rational(3, 2).to_i
+        assert -1, Rational(-3, 2).to_i
+        assert 1.5, Rational(3, 2).to_f
+        min = -9223372036854775807 - 1
+        assert min, Rational(min, 1).numerator
+        assert -4611686018427387904, Rational(min, 2).numerator
+        assert 1, Rational(min, min).numerator
+        assert_error { Rational(min, -1) }
+        assert_error { Rational(1, min) }
         "#;
         assert_script(program);
     }
@@ -541,7 +968,7 @@ mod test {
         print "Ruby"
         print 3
         puts
-        at_exit
+        at_exit {}
         "###;
         assert_script(program);
     }
@@ -650,4 +1077,102 @@ mod test {
         "#;
         assert_script(program);
     }
+
+    #[test]
+    fn kernel_catch_throw() {
+        let program = r#"
+        assert 100, catch(:foo) { throw :foo, 100; 200 }
+        assert nil, catch(:foo) { throw :foo }
+        assert 200, catch(:foo) { 200 }
+        def deep(n)
+          if n == 0
+            throw :done, :deep
+          end
+          [1].each { deep(n - 1) }
+        end
+        assert :deep, catch(:done) { deep(5) }
+        $res = []
+        def with_ensure
+          begin
+            throw :out, 7
+          ensure
+            $res << :ensure
+          end
+        end
+        r = catch(:out) do
+          begin
+            with_ensure
+          rescue => e
+            $res << :rescued
+          end
+        end
+        assert 7, r
+        assert [:ensure], $res
+        assert 3, catch(:outer) { catch(:inner) { throw :outer, 3 }; 4 }
+        assert 5, catch { |tag| throw tag, 5 }
+        assert true, catch { |tag| tag.instance_of?(Object) }
+        e = begin
+          throw :nothing, 9
+        rescue UncaughtThrowError => e
+          e
+        end
+        assert :nothing, e.tag
+        assert 9, e.value
+        assert "uncaught throw :nothing", e.message
+        assert_error { catch(:foo) { throw :bar } }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn ensure_without_rescue() {
+        let program = r#"
+        $res = []
+        def foo
+          begin
+            raise "error"
+          ensure
+            $res << :ensure
+          end
+        end
+        begin
+          foo
+        rescue => e
+          $res << e.message
+        end
+        assert [:ensure, "error"], $res
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn kernel_caller() {
+        let program = r#"
+        def foo
+          bar { return caller_locations }
+        end
+        def bar
+          yield
+        end
+        locs = foo
+        assert ["bar", "foo", "<main>"], locs.map(&:label)
+        assert [6, 3, 8], locs.map(&:lineno)
+        assert ":6:in `bar'", locs[0].to_s
+        def baz
+          [caller(0)[0], caller(0, 1).size, caller.size - caller(1..-1).size]
+        end
+        res = baz
+        assert true, res[0].end_with?(":13:in `baz'")
+        assert [1, 0], res[1..2]
+        assert nil, caller(100)
+        assert_error { caller(-1) }
+        assert 20, __LINE__
+        def qux
+          [__method__, [1].map { __method__ }[0]]
+        end
+        assert [:qux, :qux], qux
+        assert nil, __method__
+        "#;
+        assert_script(program);
+    }
 }
@@ -0,0 +1,929 @@
+use crate::*;
+
+const MAJOR_VERSION: u8 = 4;
+const MINOR_VERSION: u8 = 8;
+
+pub fn init() -> Value {
+    let mut module = Module::module();
+    BuiltinClass::set_toplevel_constant("Marshal", module);
+    module.set_const_by_str("MAJOR_VERSION", Value::integer(MAJOR_VERSION as i64));
+    module.set_const_by_str("MINOR_VERSION", Value::integer(MINOR_VERSION as i64));
+    module.add_builtin_class_method("dump", dump);
+    module.add_builtin_class_method("load", load);
+    module.add_builtin_class_method("restore", load);
+    module.into()
+}
+
+/// Serializer for the marshal format 4.8.
+struct Dumper<'a> {
+    vm: &'a mut VM,
+    buf: Vec<u8>,
+    symbols: FxHashMap<IdentId, usize>,
+    /// Indices of objects already written, keyed by object id.
+    objects: FxHashMap<u64, usize>,
+    /// The number of entries in the object table, including objects which are never linked.
+    num_objects: usize,
+}
+
+impl<'a> Dumper<'a> {
+    fn new(vm: &'a mut VM) -> Self {
+        Dumper {
+            vm,
+            buf: vec![MAJOR_VERSION, MINOR_VERSION],
+            symbols: FxHashMap::default(),
+            objects: FxHashMap::default(),
+            num_objects: 0,
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        self.buf.push(byte);
+    }
+
+    fn write_long(&mut self, num: i64) {
+        if num == 0 {
+            self.buf.push(0);
+        } else if 0 < num && num < 123 {
+            self.buf.push(num as u8 + 5);
+        } else if -124 < num && num < 0 {
+            self.buf.push((num - 5) as u8);
+        } else {
+            let mut bytes = vec![];
+            let mut x = num;
+            loop {
+                bytes.push(x as u8);
+                x >>= 8;
+                if x == 0 {
+                    self.buf.push(bytes.len() as u8);
+                    break;
+                }
+                if x == -1 {
+                    self.buf.push((-(bytes.len() as i8)) as u8);
+                    break;
+                }
+            }
+            self.buf.extend_from_slice(&bytes);
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_long(bytes.len() as i64);
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn write_symbol(&mut self, id: IdentId) {
+        if let Some(idx) = self.symbols.get(&id) {
+            let idx = *idx as i64;
+            self.write_byte(b';');
+            self.write_long(idx);
+            return;
+        }
+        let name = IdentId::get_ident_name(id);
+        let is_ascii = name.is_ascii();
+        if !is_ascii {
+            self.write_byte(b'I');
+        }
+        self.write_byte(b':');
+        self.write_bytes(name.as_bytes());
+        let idx = self.symbols.len();
+        self.symbols.insert(id, idx);
+        if !is_ascii {
+            self.write_long(1);
+            self.write_encoding();
+        }
+    }
+
+    /// Write the encoding ivar for UTF-8.
+    fn write_encoding(&mut self) {
+        self.write_symbol(IdentId::get_id("E"));
+        self.write_byte(b'T');
+    }
+
+    fn write_class(&mut self, tag: u8, class: Module) -> Result<(), RubyError> {
+        let name = match class.op_name() {
+            Some(name) if !name.starts_with('#') => name,
+            _ => {
+                return Err(RubyError::typeerr(format!(
+                    "Can't dump anonymous class {}.",
+                    class.name()
+                )))
+            }
+        };
+        self.write_byte(tag);
+        self.write_symbol(IdentId::get_id(name));
+        Ok(())
+    }
+
+    /// Write the class of `obj` with 'C' tag if `obj` is an instance of a subclass of `base`.
+    fn write_user_class(&mut self, obj: Value, base: Module) -> Result<(), RubyError> {
+        let class = obj.get_class();
+        if class != base {
+            self.write_class(b'C', class)?;
+        }
+        Ok(())
+    }
+
+    fn write_float(&mut self, f: f64) {
+        self.num_objects += 1;
+        self.write_byte(b'f');
+        self.write_bytes(float_to_string(f).as_bytes());
+    }
+
+    fn write_integer(&mut self, i: i64) {
+        if -(1 << 30) <= i && i < (1 << 30) {
+            self.write_byte(b'i');
+            self.write_long(i);
+            return;
+        }
+        self.num_objects += 1;
+        self.write_byte(b'l');
+        self.write_byte(if i < 0 { b'-' } else { b'+' });
+        let mut abs = (i as i128).abs() as u64;
+        let mut bytes = vec![];
+        while abs != 0 {
+            bytes.push(abs as u8);
+            abs >>= 8;
+        }
+        if bytes.len() % 2 != 0 {
+            bytes.push(0);
+        }
+        self.write_long(bytes.len() as i64 / 2);
+        self.buf.extend_from_slice(&bytes);
+    }
+
+    /// Write instance variables of `obj` whose names begin with '@'.
+    ///
+    /// Instance variables are ordered by their ids, which reflects the order of appearance
+    /// in the source code.
+    fn write_ivars(&mut self, obj: Value, limit: i64) -> Result<(), RubyError> {
+        let mut ivars: Vec<(IdentId, Value)> = match obj.rvalue().var_table() {
+            Some(table) => table
+                .iter()
+                .filter(|(id, _)| IdentId::starts_with(**id, "@"))
+                .map(|(id, v)| (*id, *v))
+                .collect(),
+            None => vec![],
+        };
+        ivars.sort_by_key(|(id, _)| Into::<u32>::into(*id));
+        self.write_long(ivars.len() as i64);
+        for (id, val) in ivars {
+            self.write_symbol(id);
+            self.write_object(val, limit)?;
+        }
+        Ok(())
+    }
+
+    fn remember(&mut self, obj: Value) {
+        self.objects.insert(obj.id(), self.num_objects);
+        self.num_objects += 1;
+    }
+
+    fn write_object(&mut self, obj: Value, limit: i64) -> Result<(), RubyError> {
+        if limit == 0 {
+            return Err(RubyError::argument("Exceed depth limit."));
+        }
+        let limit = limit - 1;
+        match obj.unpack() {
+            RV::Nil => self.write_byte(b'0'),
+            RV::True => self.write_byte(b'T'),
+            RV::False => self.write_byte(b'F'),
+            RV::Integer(i) => self.write_integer(i),
+            RV::Float(f) => self.write_float(f),
+            RV::Symbol(id) => self.write_symbol(id),
+            RV::Uninitialized => unreachable!(),
+            RV::Object(info) => return self.write_rvalue(obj, info, limit),
+        };
+        Ok(())
+    }
+
+    fn write_rvalue(&mut self, obj: Value, info: &RValue, limit: i64) -> Result<(), RubyError> {
+        if let Some(idx) = self.objects.get(&obj.id()) {
+            let idx = *idx as i64;
+            self.write_byte(b'@');
+            self.write_long(idx);
+            return Ok(());
+        }
+        let marshal_dump = IdentId::get_id("marshal_dump");
+        if MethodRepo::find_method_from_receiver(obj, marshal_dump).is_some() {
+            self.remember(obj);
+            let data = self.vm.send0(marshal_dump, obj)?;
+            self.vm.temp_push(data);
+            self.write_class(b'U', obj.get_class())?;
+            return self.write_object(data, limit);
+        }
+        let user_dump = IdentId::get_id("_dump");
+        if MethodRepo::find_method_from_receiver(obj, user_dump).is_some() {
+            let data = self.vm.send1(user_dump, obj, Value::integer(limit))?;
+            self.vm.temp_push(data);
+            let bytes = match data.as_bytes() {
+                Some(bytes) => bytes.to_vec(),
+                None => return Err(RubyError::typeerr("_dump() must return string.")),
+            };
+            let has_encoding = data.as_string().is_some();
+            if has_encoding {
+                self.write_byte(b'I');
+            }
+            self.write_class(b'u', obj.get_class())?;
+            self.write_bytes(&bytes);
+            if has_encoding {
+                self.write_long(1);
+                self.write_encoding();
+            }
+            self.remember(obj);
+            return Ok(());
+        }
+        self.remember(obj);
+        match &info.kind {
+            ObjKind::String(rs) => {
+                let has_encoding = !matches!(rs, RString::Bytes(_));
+                let bytes = rs.as_bytes().to_vec();
+                if has_encoding {
+                    self.write_byte(b'I');
+                }
+                self.write_user_class(obj, BuiltinClass::string())?;
+                self.write_byte(b'"');
+                self.write_bytes(&bytes);
+                if has_encoding {
+                    self.write_long(1);
+                    self.write_encoding();
+                }
+            }
+            ObjKind::Array(aref) => {
+                let elements = aref.elements.clone();
+                self.write_user_class(obj, BuiltinClass::array())?;
+                self.write_byte(b'[');
+                self.write_long(elements.len() as i64);
+                for elem in elements {
+                    self.write_object(elem, limit)?;
+                }
+            }
+            ObjKind::Hash(href) => {
+                let entries: Vec<(Value, Value)> = href.iter().collect();
+                let default = builtin::hash::get_default(obj);
+                self.write_user_class(obj, BuiltinClass::hash())?;
+                self.write_byte(if default.is_nil() { b'{' } else { b'}' });
+                self.write_long(entries.len() as i64);
+                for (k, v) in entries {
+                    self.write_object(k, limit)?;
+                    self.write_object(v, limit)?;
+                }
+                if !default.is_nil() {
+                    self.write_object(default, limit)?;
+                }
+            }
+            ObjKind::Range(info) => {
+                let (start, end, exclude) = (info.start, info.end, info.exclude);
+                self.write_class(b'o', obj.get_class())?;
+                self.write_long(3);
+                self.write_symbol(IdentId::get_id("excl"));
+                self.write_object(Value::bool(exclude), limit)?;
+                self.write_symbol(IdentId::get_id("begin"));
+                self.write_object(start, limit)?;
+                self.write_symbol(IdentId::get_id("end"));
+                self.write_object(end, limit)?;
+            }
+            ObjKind::Module(cinfo) => {
+                let tag = if cinfo.is_module() { b'm' } else { b'c' };
+                let class = obj.into_module();
+                match class.op_name() {
+                    Some(name) if !name.starts_with('#') => {
+                        self.write_byte(tag);
+                        self.write_bytes(name.as_bytes());
+                    }
+                    _ => {
+                        return Err(RubyError::typeerr(format!(
+                            "Can't dump anonymous {} {}.",
+                            if cinfo.is_module() { "module" } else { "class" },
+                            class.name()
+                        )))
+                    }
+                }
+            }
+            ObjKind::Ordinary if obj.get_class_for_method().is_singleton() => {
+                return Err(RubyError::typeerr("Singleton can't be dumped."));
+            }
+            ObjKind::Ordinary => match struct_members(obj.get_class()) {
+                Some(members) => {
+                    self.write_class(b'S', obj.get_class())?;
+                    self.write_long(members.len() as i64);
+                    for id in members {
+                        self.write_symbol(id);
+                        let val = obj
+                            .get_var(IdentId::add_prefix(id, "@"))
+                            .unwrap_or_default();
+                        self.write_object(val, limit)?;
+                    }
+                }
+                None => {
+                    self.write_class(b'o', obj.get_class())?;
+                    self.write_ivars(obj, limit)?;
+                }
+            },
+            _ => {
+                return Err(RubyError::typeerr(format!(
+                    "No _dump_data is defined for class {}.",
+                    obj.get_class_name()
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert `f` to the shortest representation used in the marshal format.
+fn float_to_string(f: f64) -> String {
+    if f.is_infinite() {
+        return if f < 0.0 { "-inf" } else { "inf" }.to_string();
+    } else if f.is_nan() {
+        return "nan".to_string();
+    } else if f == 0.0 {
+        return if f.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+    // `{:e}` gives the shortest digits which round-trip, e.g. "1.25e-3".
+    let s = format!("{:e}", f.abs());
+    let e = s.find('e').unwrap();
+    let digits: String = s[..e].chars().filter(|ch| *ch != '.').collect();
+    let decpt = s[e + 1..].parse::<i64>().unwrap() + 1;
+    let digs = digits.len() as i64;
+    let mut res = if f < 0.0 {
+        "-".to_string()
+    } else {
+        String::new()
+    };
+    if decpt < -3 || decpt > digs {
+        res += &digits[..1];
+        if digs > 1 {
+            res += ".";
+            res += &digits[1..];
+        }
+        res += &format!("e{}", decpt - 1);
+    } else if decpt > 0 {
+        res += &digits[..decpt as usize];
+        if digs > decpt {
+            res += ".";
+            res += &digits[decpt as usize..];
+        }
+    } else {
+        res += "0.";
+        res += &"0".repeat(-decpt as usize);
+        res += &digits;
+    }
+    res
+}
+
+/// Get member names of `class` if `class` is a Struct class.
+fn struct_members(class: Module) -> Option<Vec<IdentId>> {
+    let id = IdentId::get_id("/members");
+    let mut class = Some(class);
+    while let Some(c) = class {
+        if let Some(members) = c.get_var(id) {
+            let members = members.into_array();
+            return Some(
+                members
+                    .elements
+                    .iter()
+                    .map(|m| m.as_symbol().unwrap())
+                    .collect(),
+            );
+        }
+        class = c.superclass();
+    }
+    None
+}
+
+/// Deserializer for the marshal format 4.8.
+struct Loader<'a> {
+    vm: &'a mut VM,
+    buf: Vec<u8>,
+    pos: usize,
+    symbols: Vec<IdentId>,
+    objects: Vec<Value>,
+}
+
+impl<'a> Loader<'a> {
+    fn new(vm: &'a mut VM, buf: Vec<u8>) -> Self {
+        Loader {
+            vm,
+            buf,
+            pos: 0,
+            symbols: vec![],
+            objects: vec![],
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<u8, RubyError> {
+        match self.buf.get(self.pos) {
+            Some(byte) => {
+                self.pos += 1;
+                Ok(*byte)
+            }
+            None => Err(RubyError::argument("Marshal data too short.")),
+        }
+    }
+
+    fn read_long(&mut self) -> Result<i64, RubyError> {
+        let c = self.read_byte()? as i8 as i64;
+        if c == 0 {
+            return Ok(0);
+        }
+        if c > 0 {
+            if 4 < c {
+                return Ok(c - 5);
+            }
+            let mut x = 0;
+            for i in 0..c {
+                x |= (self.read_byte()? as i64) << (8 * i);
+            }
+            Ok(x)
+        } else {
+            if c < -4 {
+                return Ok(c + 5);
+            }
+            let mut x = -1;
+            for i in 0..-c {
+                x &= !(0xff << (8 * i));
+                x |= (self.read_byte()? as i64) << (8 * i);
+            }
+            Ok(x)
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, RubyError> {
+        let len = self.read_long()?;
+        if len < 0 || self.pos + len as usize > self.buf.len() {
+            return Err(RubyError::argument("Marshal data too short."));
+        }
+        let bytes = self.buf[self.pos..self.pos + len as usize].to_vec();
+        self.pos += len as usize;
+        Ok(bytes)
+    }
+
+    fn read_symbol(&mut self) -> Result<IdentId, RubyError> {
+        match self.read_object()?.as_symbol() {
+            Some(id) => Ok(id),
+            None => Err(RubyError::argument("Dump format error (symbol expected).")),
+        }
+    }
+
+    fn read_symbol_body(&mut self) -> Result<IdentId, RubyError> {
+        let bytes = self.read_bytes()?;
+        let id = IdentId::get_id(String::from_utf8_lossy(&bytes).into_owned());
+        self.symbols.push(id);
+        Ok(id)
+    }
+
+    fn read_class(&mut self) -> Result<Module, RubyError> {
+        let id = self.read_symbol()?;
+        path_to_class(&IdentId::get_ident_name(id))
+    }
+
+    fn remember(&mut self, obj: Value) -> usize {
+        self.vm.temp_push(obj);
+        self.objects.push(obj);
+        self.objects.len() - 1
+    }
+
+    fn read_object(&mut self) -> VMResult {
+        let tag = self.read_byte()?;
+        let obj = match tag {
+            b'0' => Value::nil(),
+            b'T' => Value::true_val(),
+            b'F' => Value::false_val(),
+            b'i' => Value::integer(self.read_long()?),
+            b'l' => {
+                let sign = self.read_byte()?;
+                let len = self.read_long()? as usize * 2;
+                let mut abs: u64 = 0;
+                for i in 0..len {
+                    let byte = self.read_byte()? as u64;
+                    if byte != 0 && i >= 8 {
+                        return Err(RubyError::range("Bignum too big to convert into i64."));
+                    }
+                    abs |= byte << (8 * i.min(7));
+                }
+                let num = if sign == b'-' {
+                    -(abs as i128)
+                } else {
+                    abs as i128
+                };
+                if num < i64::MIN as i128 || num > i64::MAX as i128 {
+                    return Err(RubyError::range("Bignum too big to convert into i64."));
+                }
+                let obj = Value::integer(num as i64);
+                self.remember(obj);
+                obj
+            }
+            b'f' => {
+                let bytes = self.read_bytes()?;
+                let s = String::from_utf8_lossy(&bytes);
+                let f = match s.as_ref() {
+                    "inf" => f64::INFINITY,
+                    "-inf" => f64::NEG_INFINITY,
+                    "nan" => f64::NAN,
+                    s => match s.parse::<f64>() {
+                        Ok(f) => f,
+                        Err(_) => return Err(RubyError::argument("Dump format error (float).")),
+                    },
+                };
+                let obj = Value::float(f);
+                self.remember(obj);
+                obj
+            }
+            b'"' => {
+                let obj = Value::bytes(self.read_bytes()?);
+                self.remember(obj);
+                obj
+            }
+            b':' => Value::symbol(self.read_symbol_body()?),
+            b';' => {
+                let idx = self.read_long()? as usize;
+                match self.symbols.get(idx) {
+                    Some(id) => Value::symbol(*id),
+                    None => return Err(RubyError::argument("Bad symbol.")),
+                }
+            }
+            b'@' => {
+                let idx = self.read_long()? as usize;
+                match self.objects.get(idx) {
+                    Some(obj) => *obj,
+                    None => return Err(RubyError::argument("Dump format error (unlinked).")),
+                }
+            }
+            b'I' => {
+                let obj = self.read_object()?;
+                let len = self.read_long()?;
+                for _ in 0..len {
+                    let id = self.read_symbol()?;
+                    let val = self.read_object()?;
+                    // Encodings of strings and symbols are not kept.
+                    let name = IdentId::get_ident_name(id);
+                    if name != "E" && name != "encoding" && !obj.is_packed_value() {
+                        obj.set_var(id, val);
+                    }
+                }
+                obj
+            }
+            b'[' => {
+                let obj = Value::array_empty();
+                self.remember(obj);
+                let len = self.read_long()?;
+                for _ in 0..len {
+                    let elem = self.read_object()?;
+                    obj.into_array().elements.push(elem);
+                }
+                obj
+            }
+            b'{' | b'}' => {
+                let mut obj = Value::hash_from_map(FxIndexMap::default());
+                self.remember(obj);
+                let len = self.read_long()?;
+                for _ in 0..len {
+                    let k = self.read_object()?;
+                    let v = self.read_object()?;
+                    obj.as_mut_hash().unwrap().insert(k, v);
+                }
+                if tag == b'}' {
+                    let default = self.read_object()?;
+                    obj.set_var_by_str("/default", default);
+                }
+                obj
+            }
+            b'o' => {
+                let class = self.read_class()?;
+                if class == BuiltinClass::range() {
+                    let idx = self.remember(Value::nil());
+                    let (mut start, mut end, mut exclude) = (Value::nil(), Value::nil(), false);
+                    let len = self.read_long()?;
+                    for _ in 0..len {
+                        let id = self.read_symbol()?;
+                        let val = self.read_object()?;
+                        match IdentId::get_ident_name(id).as_str() {
+                            "begin" => start = val,
+                            "end" => end = val,
+                            "excl" => exclude = val.to_bool(),
+                            _ => {}
+                        }
+                    }
+                    let obj = Value::range(start, end, exclude);
+                    self.vm.temp_push(obj);
+                    self.objects[idx] = obj;
+                    obj
+                } else {
+                    let obj = Value::ordinary_object(class);
+                    self.remember(obj);
+                    let len = self.read_long()?;
+                    for _ in 0..len {
+                        let id = self.read_symbol()?;
+                        let val = self.read_object()?;
+                        obj.set_var(id, val);
+                    }
+                    obj
+                }
+            }
+            b'S' => {
+                let class = self.read_class()?;
+                let members = match struct_members(class) {
+                    Some(members) => members,
+                    None => {
+                        return Err(RubyError::argument(format!(
+                            "Class {} is not a Struct.",
+                            class.name()
+                        )))
+                    }
+                };
+                let obj = Value::ordinary_object(class);
+                self.remember(obj);
+                let len = self.read_long()?;
+                if len as usize != members.len() {
+                    return Err(RubyError::typeerr(format!(
+                        "Struct {} not compatible (struct size differs).",
+                        class.name()
+                    )));
+                }
+                for member in members {
+                    let id = self.read_symbol()?;
+                    if id != member {
+                        return Err(RubyError::typeerr(format!(
+                            "Struct {} not compatible (:{:?} for :{:?}).",
+                            class.name(),
+                            id,
+                            member
+                        )));
+                    }
+                    let val = self.read_object()?;
+                    obj.set_var(IdentId::add_prefix(id, "@"), val);
+                }
+                obj
+            }
+            b'U' => {
+                let class = self.read_class()?;
+                let obj = Value::ordinary_object(class);
+                self.remember(obj);
+                let data = self.read_object()?;
+                let marshal_load = IdentId::get_id("marshal_load");
+                if MethodRepo::find_method_from_receiver(obj, marshal_load).is_none() {
+                    return Err(RubyError::typeerr(format!(
+                        "Instance of {} needs to have method `marshal_load'.",
+                        class.name()
+                    )));
+                }
+                self.vm.send1(marshal_load, obj, data)?;
+                obj
+            }
+            b'u' => {
+                let class = self.read_class()?;
+                let data = Value::bytes(self.read_bytes()?);
+                self.vm.temp_push(data);
+                let user_load = IdentId::get_id("_load");
+                if MethodRepo::find_method_from_receiver(class.into(), user_load).is_none() {
+                    return Err(RubyError::typeerr(format!(
+                        "Class {} needs to have method `_load'.",
+                        class.name()
+                    )));
+                }
+                let obj = self.vm.send1(user_load, class.into(), data)?;
+                self.remember(obj);
+                obj
+            }
+            b'C' => {
+                let class = self.read_class()?;
+                let obj = self.read_object()?;
+                obj.set_class(class);
+                obj
+            }
+            b'c' | b'm' => {
+                let bytes = self.read_bytes()?;
+                let path = String::from_utf8_lossy(&bytes).into_owned();
+                let class = path_to_class(&path)?;
+                if class.is_module() != (tag == b'm') {
+                    return Err(RubyError::argument(format!(
+                        "{} does not refer to {}.",
+                        path,
+                        if tag == b'm' { "module" } else { "class" }
+                    )));
+                }
+                self.remember(class.into());
+                class.into()
+            }
+            _ => {
+                return Err(RubyError::argument(format!(
+                    "Dump format error (0x{:x}).",
+                    tag
+                )))
+            }
+        };
+        Ok(obj)
+    }
+}
+
+/// Find a class or module from its path, e.g. "Foo::Bar".
+fn path_to_class(path: &str) -> Result<Module, RubyError> {
+    let mut class = BuiltinClass::object();
+    for name in path.split("::") {
+        class = match class.get_const_by_str(name) {
+            Some(val) => match val.if_mod_class() {
+                Some(class) => class,
+                None => {
+                    return Err(RubyError::typeerr(format!(
+                        "{} does not refer to class/module.",
+                        path
+                    )))
+                }
+            },
+            None => {
+                return Err(RubyError::argument(format!(
+                    "Undefined class/module {}.",
+                    path
+                )))
+            }
+        };
+    }
+    Ok(class)
+}
+
+// Class methods
+
+fn dump(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 3)?;
+    let (port, limit) = match args.len() {
+        1 => (None, -1),
+        2 if args[1].as_integer().is_some() => (None, args[1].as_integer().unwrap()),
+        2 => (Some(args[1]), -1),
+        _ => (Some(args[1]), args[2].expect_integer("3rd arg")?),
+    };
+    let len = vm.temp_len();
+    let mut dumper = Dumper::new(vm);
+    let res = dumper.write_object(args[0], limit);
+    let buf = dumper.buf;
+    vm.temp_pop_vec(len);
+    res?;
+    let data = Value::bytes(buf);
+    match port {
+        Some(port) => {
+            vm.send1(IdentId::get_id("write"), port, data)?;
+            Ok(port)
+        }
+        None => Ok(data),
+    }
+}
+
+fn load(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let source = args[0];
+    let bytes = match source.as_bytes() {
+        Some(bytes) => bytes.to_vec(),
+        None => {
+            let data = vm.send0(IdentId::get_id("read"), source)?;
+            data.expect_bytes("Result of read")?.to_vec()
+        }
+    };
+    if bytes.len() < 2 {
+        return Err(RubyError::argument("Marshal data too short."));
+    }
+    if bytes[0] != MAJOR_VERSION || bytes[1] > MINOR_VERSION {
+        return Err(RubyError::typeerr(format!(
+            "Incompatible marshal file format (can't be read). format version {}.{} required; {}.{} given",
+            MAJOR_VERSION, MINOR_VERSION, bytes[0], bytes[1]
+        )));
+    }
+    let len = vm.temp_len();
+    let mut loader = Loader::new(vm, bytes);
+    loader.pos = 2;
+    let res = loader.read_object();
+    vm.temp_pop_vec(len);
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::*;
+
+    #[test]
+    fn marshal_dump_format() {
+        let program = r#"
+        def d(obj)
+          Marshal.dump(obj).bytes
+        end
+        assert [4, 8, 48], d(nil)
+        assert [4, 8, 84], d(true)
+        assert [4, 8, 70], d(false)
+        assert [4, 8, 105, 0], d(0)
+        assert [4, 8, 105, 6], d(1)
+        assert [4, 8, 105, 127], d(122)
+        assert [4, 8, 105, 1, 123], d(123)
+        assert [4, 8, 105, 250], d(-1)
+        assert [4, 8, 105, 255, 131], d(-125)
+        assert [4, 8, 105, 2, 0, 1], d(256)
+        assert [4, 8, 105, 4, 255, 255, 255, 63], d(2**30 - 1)
+        assert [4, 8, 108, 43, 7, 0, 0, 0, 64], d(2**30)
+        assert [4, 8, 108, 45, 7, 1, 0, 0, 64], d(-2**30 - 1)
+        assert [4, 8, 102, 8, 49, 46, 53], d(1.5)
+        assert "\x04\bf\b1e2", Marshal.dump(100.0)
+        assert "\x04\bf\n0.001", Marshal.dump(0.001)
+        assert "\x04\bI\"\babc\x06:\x06ET", Marshal.dump("abc")
+        assert "\x04\b[\a:\bfoo;\x00", Marshal.dump([:foo, :foo])
+        assert "\x04\b[\aI\"\x06a\x06:\x06ET@\x06", Marshal.dump([s = "a", s])
+        assert "\x04\b{\x06i\x06i\a", Marshal.dump({1 => 2})
+        assert "\x04\b}\x00i\x06", Marshal.dump(Hash.new(1))
+        assert "\x04\bo:\nRange\b:\texclF:\nbegini\x06:\bendi\a", Marshal.dump(1..2)
+        assert "\x04\bc\vString", Marshal.dump(String)
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn marshal_round_trip() {
+        let program = r#"
+        def rt(obj)
+          Marshal.load(Marshal.dump(obj))
+        end
+        [nil, true, false, 0, 1, -1, 300, -300, 2**40, -2**40, 2**62, 1.5, -0.25, 1e100,
+         1.0e-10, "", "abc", "ルビー", :sym, "ルビー".to_sym, [1, [2, 3]], {a: 1, "b" => [2]},
+         1..3, 1...3, ("a".."z"), String, Kernel].each do |obj|
+          assert obj, rt(obj)
+        end
+        h = Hash.new(7)
+        h[:a] = 1
+        h = rt(h)
+        assert 1, h[:a]
+        assert 7, h[:x]
+
+        s = "shared"
+        a = rt([s, s])
+        assert true, a[0].equal?(a[1])
+        a = rt([:x, "y", :x, "y"])
+        assert [:x, "y", :x, "y"], a
+        assert false, a[1].equal?(a[3])
+
+        assert_error { Marshal.dump(proc {}) }
+        assert_error { Marshal.dump(Class.new) }
+        assert_error { Marshal.load("\x04\x09") }
+        assert_error { Marshal.load("\x04\b[\x06") }
+        assert_error { Marshal.dump([[[1]]], 2) }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn marshal_object() {
+        let program = r#"
+        class Point
+          attr_accessor :x, :y
+          def initialize(x, y)
+            @x = x
+            @y = y
+          end
+        end
+        assert "\x04\bo:\nPoint\a:\a@xi\x06:\a@yi\a", Marshal.dump(Point.new(1, 2))
+        p = Marshal.load(Marshal.dump(Point.new(1, [2])))
+        assert Point, p.class
+        assert 1, p.x
+        assert [2], p.y
+
+        node = Point.new(nil, nil)
+        node.x = node
+        n = Marshal.load(Marshal.dump(node))
+        assert true, n.x.equal?(n)
+
+        S = Struct.new(:a, :b)
+        assert "\x04\bS:\x06S\a:\x06ai\x06:\x06bi\a", Marshal.dump(S.new(1, 2))
+        s = Marshal.load(Marshal.dump(S.new(1, "x")))
+        assert S, s.class
+        assert 1, s.a
+        assert "x", s.b
+        Struct.new("Pair", :l, :r)
+        assert "r", Marshal.load(Marshal.dump(Struct::Pair.new("l", "r"))).r
+
+        class Money
+          attr_reader :cents
+          def initialize(cents)
+            @cents = cents
+          end
+          def marshal_dump
+            [@cents]
+          end
+          def marshal_load(data)
+            @cents = data[0] * 2
+          end
+        end
+        assert "\x04\bU:\nMoney[\x06i\x06", Marshal.dump(Money.new(1))
+        assert 20, Marshal.load(Marshal.dump(Money.new(10))).cents
+
+        class Temp
+          attr_reader :deg
+          def initialize(deg)
+            @deg = deg
+          end
+          def _dump(level)
+            @deg.to_s
+          end
+          def self._load(str)
+            new(str.to_i + 1)
+          end
+        end
+        assert "\x04\bIu:\tTemp\a36\x06:\x06ET", Marshal.dump(Temp.new(36))
+        assert 37, Marshal.load(Marshal.dump(Temp.new(36))).deg
+        "#;
+        assert_script(program);
+    }
+}
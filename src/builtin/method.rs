@@ -5,18 +5,182 @@ pub fn init() -> Value {
     BuiltinClass::set_toplevel_constant("Method", class);
     class.add_builtin_method_by_str("call", call);
     class.add_builtin_method_by_str("[]", call);
+    class.add_builtin_method_by_str("===", call);
+    class.add_builtin_method_by_str("to_s", inspect);
+    class.add_builtin_method_by_str("inspect", inspect);
+    class.add_builtin_method_by_str("arity", arity);
+    class.add_builtin_method_by_str("parameters", parameters);
+    class.add_builtin_method_by_str("owner", owner);
+    class.add_builtin_method_by_str("receiver", receiver);
+    class.add_builtin_method_by_str("name", name);
+    class.add_builtin_method_by_str("source_location", source_location);
+    class.add_builtin_method_by_str("unbind", unbind);
+    class.add_builtin_method_by_str("super_method", super_method);
+
+    let unbound = Module::class_under_object();
+    BuiltinClass::set_toplevel_constant("UnboundMethod", unbound);
+    unbound.add_builtin_method_by_str("to_s", inspect);
+    unbound.add_builtin_method_by_str("inspect", inspect);
+    unbound.add_builtin_method_by_str("arity", arity);
+    unbound.add_builtin_method_by_str("parameters", parameters);
+    unbound.add_builtin_method_by_str("owner", owner);
+    unbound.add_builtin_method_by_str("name", name);
+    unbound.add_builtin_method_by_str("source_location", source_location);
+    unbound.add_builtin_method_by_str("bind", bind);
+    unbound.add_builtin_method_by_str("bind_call", bind_call);
+    BUILTINS.with(|m| m.borrow_mut().unbound_method = unbound.into());
     class.into()
 }
 
+fn method_info(self_val: Value) -> Result<MethodObjInfo, RubyError> {
+    match self_val.as_method() {
+        Some(method) => Ok(method.clone()),
+        None => Err(RubyError::internal("Expected Method object.")),
+    }
+}
+
+/// Check whether the method of `info` can be bound to `receiver`.
+fn check_bind(info: &MethodObjInfo, receiver: Value) -> Result<(), RubyError> {
+    if receiver.get_class_for_method().include_module(info.owner) {
+        Ok(())
+    } else {
+        Err(RubyError::typeerr(format!(
+            "bind argument must be an instance of {}.",
+            info.owner.name()
+        )))
+    }
+}
+
 pub fn call(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
-    let method = match self_val.as_method() {
-        Some(method) => method,
-        None => return Err(RubyError::internal("Expected Method object.")),
-    };
+    let method = method_info(self_val)?;
     let res = vm.eval_method(method.method, method.receiver, args)?;
     Ok(res)
 }
 
+fn inspect(_: &mut VM, self_val: Value, _: &Args) -> VMResult {
+    let info = method_info(self_val)?;
+    let class_name = self_val.get_class_name();
+    let attached = info
+        .owner
+        .singleton_for()
+        .and_then(|obj| obj.if_mod_class());
+    let s = match attached {
+        Some(module) => format!("#<{}: {}.{:?}>", class_name, module.name(), info.name),
+        None => format!("#<{}: {}#{:?}>", class_name, info.owner.name(), info.name),
+    };
+    Ok(Value::string(s))
+}
+
+fn arity(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    let arity = match MethodRepo::get(info.method) {
+        MethodInfo::RubyFunc { iseq } => iseq.params.arity(),
+        MethodInfo::ProcFunc { proc } => proc_iseq(proc).params.arity(),
+        MethodInfo::AttrReader { .. } => 0,
+        MethodInfo::AttrWriter { .. } => 1,
+        _ => -1,
+    };
+    Ok(Value::integer(arity))
+}
+
+/// Get ISeq of the Proc which is the body of a method defined by `define_method`.
+fn proc_iseq(proc: Value) -> ISeqRef {
+    proc.as_proc().unwrap().context.iseq_ref.unwrap()
+}
+
+fn parameters(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    let params = match MethodRepo::get(info.method) {
+        MethodInfo::RubyFunc { iseq } => iseq.params.parameters(false),
+        MethodInfo::ProcFunc { proc } => proc_iseq(proc).params.parameters(false),
+        MethodInfo::AttrReader { .. } => Value::array_from(vec![]),
+        MethodInfo::AttrWriter { .. } => {
+            let param = Value::array_from(vec![Value::symbol_from_str("req")]);
+            Value::array_from(vec![param])
+        }
+        _ => {
+            let param = Value::array_from(vec![Value::symbol_from_str("rest")]);
+            Value::array_from(vec![param])
+        }
+    };
+    Ok(params)
+}
+
+fn owner(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    Ok(info.owner.into())
+}
+
+fn receiver(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    Ok(info.receiver)
+}
+
+fn name(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    Ok(Value::symbol(info.name))
+}
+
+fn source_location(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    match MethodRepo::get(info.method) {
+        MethodInfo::RubyFunc { iseq } => Ok(iseq.source_location()),
+        MethodInfo::ProcFunc { proc } => Ok(proc_iseq(proc).source_location()),
+        _ => Ok(Value::nil()),
+    }
+}
+
+fn unbind(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    Ok(Value::unbound_method(info.name, info.method, info.owner))
+}
+
+fn super_method(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let info = method_info(self_val)?;
+    let mut class = info.receiver.get_class_for_method();
+    // Skip the ancestors up to the owner of the method.
+    while class.real_module().id() != info.owner.id() {
+        class = match class.upper() {
+            Some(upper) => upper,
+            None => return Ok(Value::nil()),
+        };
+    }
+    let superclass = match class.upper() {
+        Some(upper) => upper,
+        None => return Ok(Value::nil()),
+    };
+    match superclass.get_method_with_owner(info.name) {
+        Some((method, owner)) => Ok(Value::method(info.name, info.receiver, method, owner)),
+        None => Ok(Value::nil()),
+    }
+}
+
+fn bind(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let info = method_info(self_val)?;
+    check_bind(&info, args[0])?;
+    Ok(Value::method(info.name, args[0], info.method, info.owner))
+}
+
+fn bind_call(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_min(1)?;
+    let info = method_info(self_val)?;
+    let receiver = args[0];
+    check_bind(&info, receiver)?;
+    let mut new_args = Args::from_slice(&args[1..args.len()]);
+    new_args.block = args.block.clone();
+    new_args.kw_arg = args.kw_arg;
+    vm.eval_method(info.method, receiver, &new_args)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
@@ -61,4 +225,83 @@ mod tests {
     "#;
         assert_script(program);
     }
+
+    #[test]
+    fn method_introspection() {
+        let program = r##"
+        class Foo
+          def foo(a, b = 1, *c, d, e: 2, **f, &g); end
+          def bar(x); x * 2; end
+        end
+        class Bar < Foo
+          def bar(x); x + 1; end
+        end
+
+        m = Foo.new.method(:foo)
+        assert(-3, m.arity)
+        assert [[:req, :a], [:opt, :b], [:rest, :c], [:req, :d], [:key, :e], [:keyrest, :f], [:block, :g]], m.parameters
+        assert :foo, m.name
+        assert Foo, m.owner
+        assert 3, m.source_location[1]
+        assert 1, Foo.new.method(:bar).arity
+
+        obj = Bar.new
+        m = obj.method(:bar)
+        assert obj, m.receiver
+        assert Bar, m.owner
+        assert "#<Method: Bar#bar>", m.inspect
+        def Foo.baz; end
+        assert "#<Method: Foo.baz>", Foo.method(:baz).inspect
+        assert 4, m.call(3)
+        assert 6, m.super_method.call(3)
+        assert Foo, m.super_method.owner
+        assert nil, Foo.new.method(:bar).super_method
+    "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn unbound_method() {
+        let program = r##"
+        class Foo
+          def bar(x); x * 2; end
+        end
+        class Bar < Foo
+          def bar(x); x + 1; end
+        end
+
+        u = Bar.new.method(:bar).unbind
+        assert UnboundMethod, u.class
+        assert :bar, u.name
+        assert Bar, u.owner
+        assert 4, u.bind(Bar.new).call(3)
+        um = Foo.instance_method(:bar)
+        assert "#<UnboundMethod: Foo#bar>", um.inspect
+        assert 6, um.bind_call(Bar.new, 3)
+        assert 1, um.arity
+        assert_error { um.bind(1) }
+        assert_error { Foo.instance_method(:baz) }
+    "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn method_to_proc() {
+        let program = r#"
+        class Foo
+          def bar(x); x * 2; end
+        end
+        def add3(a, b, c); a + b + c; end
+
+        m = Foo.new.method(:bar)
+        assert 6, m.to_proc.call(3)
+        assert [2, 4, 6], [1, 2, 3].map(&m)
+        assert 2, m === 1
+        c = method(:add3).curry
+        assert 6, c[1][2][3]
+        assert 6, c[1, 2][3]
+        assert 6, method(:add3).curry(3)[1][2, 3]
+    "#;
+        assert_script(program);
+    }
 }
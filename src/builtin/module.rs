@@ -1,4 +1,5 @@
 use crate::*;
+use fxhash::FxHashSet;
 use std::path::PathBuf;
 
 pub fn init() {
@@ -13,8 +14,12 @@ pub fn init() {
     class.add_builtin_method_by_str("inspect", inspect);
     class.add_builtin_method_by_str("constants", constants);
     class.add_builtin_method_by_str("class_variables", class_variables);
+    class.add_builtin_method_by_str("class_variable_get", class_variable_get);
+    class.add_builtin_method_by_str("class_variable_set", class_variable_set);
+    class.add_builtin_method_by_str("class_variable_defined?", class_variable_defined);
     class.add_builtin_method_by_str("const_defined?", const_defined);
     class.add_builtin_method_by_str("instance_methods", instance_methods);
+    class.add_builtin_method_by_str("instance_method", instance_method);
     class.add_builtin_method_by_str("attr_accessor", attr_accessor);
     class.add_builtin_method_by_str("attr", attr_reader);
     class.add_builtin_method_by_str("attr_reader", attr_reader);
@@ -22,17 +27,30 @@ pub fn init() {
     class.add_builtin_method_by_str("module_function", module_function);
     class.add_builtin_method_by_str("singleton_class?", singleton_class);
     class.add_builtin_method_by_str("const_get", const_get);
+    class.add_builtin_method_by_str("const_set", const_set);
+    class.add_builtin_method_by_str("remove_const", remove_const);
     class.add_builtin_method_by_str("include", include);
     class.add_builtin_method_by_str("prepend", prepend);
     class.add_builtin_method_by_str("included_modules", included_modules);
     class.add_builtin_method_by_str("ancestors", ancestors);
     class.add_builtin_method_by_str("module_eval", module_eval);
     class.add_builtin_method_by_str("class_eval", module_eval);
+    class.add_builtin_method_by_str("module_exec", module_exec);
+    class.add_builtin_method_by_str("class_exec", module_exec);
     class.add_builtin_method_by_str("alias_method", module_alias_method);
+    class.add_builtin_method_by_str("undef_method", module_undef_method);
+    class.add_builtin_method_by_str("define_method", define_method);
     class.add_builtin_method_by_str("public", public);
     class.add_builtin_method_by_str("private", private);
     class.add_builtin_method_by_str("protected", protected);
     class.add_builtin_method_by_str("include?", include_);
+    class.add_builtin_method_by_str("included", hook_noop);
+    class.add_builtin_method_by_str("extended", hook_noop);
+    class.add_builtin_method_by_str("prepended", hook_noop);
+    class.add_builtin_method_by_str("method_added", hook_noop);
+    class.add_builtin_method_by_str("const_missing", const_missing);
+    class.add_builtin_method_by_str("refine", refine);
+    class.add_builtin_method_by_str("using", using);
 }
 
 /// Create new module.
@@ -160,6 +178,43 @@ fn class_variables(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::array_from(res))
 }
 
+/// Check whether `val` is a valid name of class variables.
+fn expect_cvar_name(val: Value) -> Result<IdentId, RubyError> {
+    let id = val.expect_symbol_or_string("1st arg")?;
+    if IdentId::starts_with(id, "@@") {
+        Ok(id)
+    } else {
+        Err(RubyError::name(format!(
+            "`{:?}' is not allowed as a class variable name.",
+            id
+        )))
+    }
+}
+
+fn class_variable_get(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_cvar_name(args[0])?;
+    match self_val.into_module().get_class_var(id) {
+        Some(val) => Ok(val),
+        None => Err(RubyError::uninitialized_class_var(id)),
+    }
+}
+
+fn class_variable_set(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(2)?;
+    let id = expect_cvar_name(args[0])?;
+    self_val.into_module().set_class_var(id, args[1]);
+    Ok(args[1])
+}
+
+fn class_variable_defined(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_cvar_name(args[0])?;
+    Ok(Value::bool(
+        self_val.into_module().get_class_var(id).is_some(),
+    ))
+}
+
 fn const_defined(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_range(1, 2)?;
     let name = args[0].expect_string_or_symbol("1st arg")?;
@@ -178,6 +233,40 @@ fn const_get(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(val)
 }
 
+/// Check whether `val` is a valid name of constants.
+fn expect_const_name(val: Value) -> Result<IdentId, RubyError> {
+    let id = val.expect_symbol_or_string("1st arg")?;
+    let name = IdentId::get_ident_name(id);
+    let is_const = match name.chars().nth(0) {
+        Some(ch) => ch.is_ascii_uppercase(),
+        None => false,
+    };
+    if is_const && name.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+        Ok(id)
+    } else {
+        Err(RubyError::name(format!("Wrong constant name {}.", name)))
+    }
+}
+
+fn const_set(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(2)?;
+    let id = expect_const_name(args[0])?;
+    self_val.into_module().set_const(id, args[1]);
+    Ok(args[1])
+}
+
+fn remove_const(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_const_name(args[0])?;
+    match self_val.into_module().remove_const(id) {
+        Some(val) => Ok(val),
+        None => Err(RubyError::name(format!(
+            "Constant {:?}::{:?} not defined.",
+            self_val, id
+        ))),
+    }
+}
+
 pub(crate) fn instance_methods(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_range(0, 1)?;
     let mut module = self_val.into_module();
@@ -186,16 +275,22 @@ pub(crate) fn instance_methods(_: &mut VM, self_val: Value, args: &Args) -> VMRe
         false => {
             let v = module
                 .method_table()
-                .keys()
-                .map(|k| Value::symbol(*k))
+                .iter()
+                .filter(|(_, method)| !method.is_undef())
+                .map(|(k, _)| Value::symbol(*k))
                 .collect();
             Ok(Value::array_from(v))
         }
         true => {
             let mut v = FxIndexSet::default();
+            let mut undefs = FxHashSet::default();
             loop {
-                for k in module.method_table().keys() {
-                    v.insert(Value::symbol(*k));
+                for (k, method) in module.method_table() {
+                    if method.is_undef() {
+                        undefs.insert(*k);
+                    } else if !undefs.contains(k) {
+                        v.insert(Value::symbol(*k));
+                    }
                 }
                 match module.upper() {
                     None => break,
@@ -209,6 +304,16 @@ pub(crate) fn instance_methods(_: &mut VM, self_val: Value, args: &Args) -> VMRe
     }
 }
 
+fn instance_method(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let name = args[0].expect_string_or_symbol("1st arg")?;
+    let module = self_val.into_module();
+    match module.get_method_with_owner(name) {
+        Some((method, owner)) => Ok(Value::unbound_method(name, method, owner)),
+        None => Err(RubyError::undefined_method_for_class(name, module)),
+    }
+}
+
 fn attr_accessor(_vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     set_attr_accessor(self_val.into_module(), args)
 }
@@ -279,18 +384,20 @@ fn singleton_class(_: &mut VM, self_val: Value, _: &Args) -> VMResult {
     Ok(Value::bool(self_val.into_module().is_singleton()))
 }
 
-fn include(_vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+fn include(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let module = args[0].expect_module("1st arg")?;
     self_val.into_module().append_include(module);
+    vm.invoke_hook("included", module.into(), self_val)?;
     Ok(Value::nil())
 }
 
-fn prepend(_vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+fn prepend(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let self_val = self_val.into_module();
     let module = args[0].expect_module("1st arg")?;
     self_val.clone().append_prepend(self_val, module);
+    vm.invoke_hook("prepended", module.into(), self_val.into())?;
     Ok(Value::nil())
 }
 
@@ -328,45 +435,103 @@ fn ancestors(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::array_from(ary))
 }
 
-fn module_eval(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
-    let self_val = self_val.into_module();
+/// Evaluate a string given to `module_eval` or `instance_eval` with `self_val` as self.
+///
+/// `args` are (string [, filename [, lineno]]).
+/// The scopes of constants and class variables are same as module definition of `class`.
+pub fn eval_string(vm: &mut VM, self_val: Value, class: Module, args: &Args) -> VMResult {
+    args.check_args_range(1, 3)?;
+    let mut arg0 = args[0];
+    let program = arg0.expect_string("1st arg")?.to_string();
+    let path = if args.len() > 1 {
+        let mut arg1 = args[1];
+        PathBuf::from(arg1.expect_string("2nd arg")?)
+    } else {
+        PathBuf::from("(eval)")
+    };
+    let line = if args.len() > 2 {
+        args[2].expect_integer("3rd arg")?
+    } else {
+        1
+    };
     let context = vm.context();
+    let method = vm.parse_program_eval(path, &program, line)?;
+    let mut iseq = vm.get_method_iseq();
+    iseq.class_defined.push(class);
+    let res = vm.invoke_func(method, self_val, Some(context), &Args::new0());
+    iseq.class_defined.pop().unwrap();
+    res?;
+    Ok(vm.stack_pop())
+}
+
+fn module_eval(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let module = self_val.into_module();
     match &args.block {
         Block::None => {
-            args.check_args_num(1)?;
-            let mut arg0 = args[0];
-            let program = arg0.expect_string("1st arg")?;
-            let method = vm.parse_program_eval(PathBuf::from("(eval)"), program)?;
-            // The scopes of constants and class variables are same as module definition of `self_val`.
-            vm.class_push(self_val);
-            let mut iseq = vm.get_method_iseq();
-            iseq.class_defined.push(self_val);
-            let res = vm.invoke_func(method, self_val, Some(context), &Args::new0());
-            iseq.class_defined.pop().unwrap();
+            vm.class_push(module);
+            let res = eval_string(vm, self_val, module, args);
             vm.class_pop();
-            res?;
-            Ok(vm.stack_pop())
+            res
         }
         block => {
             args.check_args_num(0)?;
             // The scopes of constants and class variables are outer of the block.
-            vm.class_push(self_val);
-            let res = vm.eval_block_self(block, self_val, &Args::new0());
+            vm.class_push(module);
+            let res = vm.eval_block_self(block, self_val, &Args::new1(self_val));
             vm.class_pop();
             res
         }
     }
 }
 
+fn module_exec(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let block = args.expect_block()?;
+    vm.class_push(self_val.into_module());
+    let res = vm.eval_block_self(block, self_val, args);
+    vm.class_pop();
+    res
+}
+
 fn module_alias_method(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(2)?;
     let new = args[0].expect_string_or_symbol("1st arg")?;
     let org = args[1].expect_string_or_symbol("2nd arg")?;
     let method = vm.get_method(Module::new(self_val), org)?;
     self_val.into_module().add_method(new, method);
+    Ok(Value::symbol(new))
+}
+
+fn module_undef_method(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let module = self_val.into_module();
+    for arg in args.iter() {
+        let name = arg.expect_string_or_symbol("Args")?;
+        module.undef_method(name)?;
+    }
     Ok(self_val)
 }
 
+fn define_method(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let id = args[0].expect_string_or_symbol("1st arg")?;
+    let method = if args.len() == 2 {
+        if args[1].as_proc().is_some() {
+            MethodRepo::add(MethodInfo::ProcFunc { proc: args[1] })
+        } else if let Some(info) = args[1].as_method() {
+            info.method
+        } else {
+            return Err(RubyError::typeerr(format!(
+                "Wrong argument type {} (expected Proc/Method).",
+                args[1].get_class_name()
+            )));
+        }
+    } else {
+        let proc = vm.create_proc(args.expect_block()?)?;
+        MethodRepo::add(MethodInfo::ProcFunc { proc })
+    };
+    vm.define_method(self_val, id, method);
+    Ok(Value::symbol(id))
+}
+
 fn public(_vm: &mut VM, self_val: Value, _args: &Args) -> VMResult {
     Ok(self_val)
 }
@@ -386,6 +551,62 @@ fn include_(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::bool(val.include_module(module)))
 }
 
+/// https://docs.ruby-lang.org/ja/latest/class/Module.html#I_REFINE
+fn refine(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let block = args.expect_block()?;
+    let module = self_val.into_module();
+    let class = args[0].expect_mod_class()?;
+    let refinement = match module.get_refinement(class) {
+        Some(refinement) => refinement,
+        None => {
+            let mut refinement = Module::module();
+            refinement.set_name(format!("#<refinement:{}@{}>", class.name(), module.name()));
+            module.add_refinement(class, refinement);
+            refinement
+        }
+    };
+    vm.class_push(refinement);
+    let res = vm.eval_block_self(block, refinement, &Args::new0());
+    vm.class_pop();
+    res?;
+    Ok(refinement.into())
+}
+
+/// Activate refinements in the module definition of `self`.
+/// https://docs.ruby-lang.org/ja/latest/class/Module.html#I_USING
+fn using(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let module = args[0].expect_module("1st arg")?;
+    let caller = vm.get_method_iseq();
+    MethodRepo::activate_refinements(caller, module);
+    Ok(self_val)
+}
+
+/// Activate refinements in the current file.
+/// This method is defined as a singleton method of the main object.
+/// https://docs.ruby-lang.org/ja/latest/class/main.html#I_USING
+pub fn main_using(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let module = args[0].expect_module("1st arg")?;
+    let caller = vm.get_method_iseq();
+    MethodRepo::activate_refinements(caller, module);
+    Ok(self_val)
+}
+
+/// Default implementation of hook methods (included, extended, prepended, method_added).
+fn hook_noop(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    Ok(Value::nil())
+}
+
+/// https://docs.ruby-lang.org/ja/latest/class/Module.html#I_CONST_MISSING
+fn const_missing(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let name = args[0].expect_symbol_or_string("1st arg")?;
+    Err(RubyError::uninitialized_constant(name))
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::*;
@@ -671,6 +892,94 @@ mod test {
         assert_script(program);
     }
 
+    #[test]
+    fn module_eval_string() {
+        let program = r#"
+        class C; end
+        assert C, C.class_eval("self")
+        assert_error { C.class_eval("1 +", "foo.rb", 10) }
+        assert 5, C.module_eval("x = 5", "foo.rb", 3)
+        assert C, C.class_eval { |c| c }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn define_method() {
+        let program = r#"
+        class Foo
+          def initialize(x)
+            @x = x
+          end
+          n = 10
+          define_method(:add) { |y| @x + y + n }
+          assert :twice, define_method(:twice, proc { |y| y * 2 })
+          define_method(:add2, instance_method(:add))
+        end
+        foo = Foo.new(1)
+        assert 13, foo.add(2)
+        assert 13, foo.add2(2)
+        assert 8, foo.twice(4)
+        assert 1, Foo.instance_method(:add).arity
+        assert_error { foo.add }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn module_exec() {
+        let program = r#"
+        class C; end
+        C.class_exec(:foo) do |name|
+          attr_accessor name
+        end
+        c = C.new
+        c.foo = 42
+        assert 42, c.foo
+        assert [C, 3], C.module_exec(3) { |x| [self, x] }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn class_variable_reflection() {
+        let program = r#"
+        class One
+          @@var1 = 1
+        end
+        class Two < One
+        end
+        assert 1, One.class_variable_get(:@@var1)
+        assert 1, Two.class_variable_get("@@var1")
+        assert true, Two.class_variable_defined?(:@@var1)
+        assert false, One.class_variable_defined?(:@@var2)
+        assert_error { One.class_variable_get(:@@var2) }
+        assert_error { One.class_variable_get(:var1) }
+        assert 10, Two.class_variable_set(:@@var1, 10)
+        assert 10, One.class_variable_get(:@@var1)
+        Two.class_variable_set(:@@var2, 2)
+        assert 2, Two.class_variable_get(:@@var2)
+        assert false, One.class_variable_defined?(:@@var2)
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn const_set_remove() {
+        let program = r#"
+        class C; end
+        assert 100, C.const_set(:FOO, 100)
+        assert 100, C::FOO
+        assert 200, C.const_set("BAR", 200)
+        assert true, C.const_defined?(:BAR)
+        assert_error { C.const_set(:foo, 1) }
+        assert 100, C.remove_const(:FOO)
+        assert false, C.const_defined?(:FOO)
+        assert_error { C.remove_const(:FOO) }
+        "#;
+        assert_script(program);
+    }
+
     #[test]
     fn alias_method() {
         let program = r##"
@@ -678,8 +987,8 @@ mod test {
           def foo
             55
           end
-          alias_method :bar1, :foo
-          alias_method "bar2", :foo
+          assert :bar1, alias_method(:bar1, :foo)
+          assert :bar2, alias_method("bar2", :foo)
           alias_method :bar3, "foo"
           alias_method "bar4", "foo"
           assert_error { alias_method 124, :foo }
@@ -784,6 +1093,118 @@ mod test {
         assert_script(program);
     }
 
+    #[test]
+    fn module_hooks() {
+        let program = r#"
+        $log = []
+        module M
+          def self.included(base)
+            $log << [:included, base]
+          end
+        end
+        module N
+          def self.prepended(base)
+            $log << [:prepended, base]
+          end
+        end
+        class C
+          def self.method_added(name)
+            $log << [:method_added, name]
+          end
+          def self.singleton_method_added(name)
+            $log << [:singleton_method_added, name]
+          end
+          include M
+          prepend N
+          def foo; end
+          def self.bar; end
+        end
+        assert [[:singleton_method_added, :singleton_method_added], [:included, C], [:prepended, C], [:method_added, :foo], [:singleton_method_added, :bar]], $log
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn const_missing() {
+        let program = r#"
+        class C
+          def self.const_missing(name)
+            "missing #{name}"
+          end
+          FOO = Foo
+        end
+        assert "missing Foo", C::FOO
+        assert "missing Bar", C::Bar
+        assert_error { Baz }
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn refinements() {
+        let program = r#"
+        module Shout
+          refine String do
+            def shout
+              upcase + "!"
+            end
+            def length
+              100
+            end
+          end
+        end
+        def before
+          "ruby".length
+        end
+        assert 4, before
+        assert_error { "ruby".shout }
+        assert false, "ruby".respond_to?(:shout)
+        using Shout
+        assert "RUBY!", "ruby".shout
+        assert 100, "ruby".length
+        assert "RUBY!", "ruby".send(:shout)
+        assert "RUBY!", "ruby".__send__(:shout)
+        assert true, "ruby".respond_to?(:shout)
+        # The refinements are active in the whole file, including methods defined before `using`.
+        assert 100, before
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn refinements_module_scope() {
+        let program = r#"
+        module Twice
+          refine Integer do
+            def twice
+              self * 2
+            end
+          end
+        end
+        module Foo
+          using Twice
+          def self.foo(x)
+            x.twice
+          end
+          def self.bar(x)
+            [x.send(:twice), x.respond_to?(:twice)]
+          end
+        end
+        module Bar
+          def self.bar(x)
+            x.respond_to?(:twice)
+          end
+        end
+        assert 6, Foo.foo(3)
+        assert [6, true], Foo.bar(3)
+        assert false, Bar.bar(3)
+        assert_error { 3.twice }
+        assert_error { 3.send(:twice) }
+        assert false, 3.respond_to?(:twice)
+        "#;
+        assert_script(program);
+    }
+
     #[test]
     fn include() {
         let program = "
@@ -793,4 +1214,25 @@ mod test {
         ";
         assert_script(program);
     }
+
+    #[test]
+    fn undef_method() {
+        let program = r#"
+        class Foo
+          def foo; 1; end
+          def bar; 2; end
+        end
+        class Bar < Foo
+          undef_method :foo
+        end
+        assert Bar, Bar.undef_method("bar")
+        assert 1, Foo.new.foo
+        assert 2, Foo.new.bar
+        assert false, Bar.new.respond_to?(:foo)
+        assert_error { Bar.new.foo }
+        assert_error { Bar.new.bar }
+        assert_error { Bar.undef_method(:baz) }
+        "#;
+        assert_script(program);
+    }
 }
@@ -1,4 +1,5 @@
 use crate::*;
+use std::convert::TryFrom;
 
 pub fn init() -> Module {
     let mut class = Module::class_under_object();
@@ -6,3 +7,349 @@ pub fn init() -> Module {
     class.append_include_without_increment_version(BuiltinClass::comparable());
     class
 }
+
+/// Check whether `base` is a valid radix for conversion between strings and integers.
+pub fn expect_radix(base: i64) -> Result<u32, RubyError> {
+    match base {
+        2..=36 => Ok(base as u32),
+        _ => Err(RubyError::argument(format!("Invalid radix {}.", base))),
+    }
+}
+
+/// Scan digits in `base` with optional underscores between them from `s[*pos..]`,
+/// and accumulate them to `val`. Return the number of digits.
+fn scan_digits(s: &[u8], pos: &mut usize, base: u32, val: &mut u64) -> Result<usize, RubyError> {
+    let mut count = 0;
+    while *pos < s.len() {
+        let ch = s[*pos] as char;
+        if ch == '_' && count != 0 {
+            match s.get(*pos + 1).and_then(|ch| (*ch as char).to_digit(base)) {
+                Some(_) => *pos += 1,
+                None => break,
+            }
+            continue;
+        }
+        let digit = match ch.to_digit(base) {
+            Some(digit) => digit as u64,
+            None => break,
+        };
+        *val = match val
+            .checked_mul(base as u64)
+            .and_then(|v| v.checked_add(digit))
+        {
+            Some(v) => v,
+            None => return Err(RubyError::range("Integer is too big to convert into i64.")),
+        };
+        count += 1;
+        *pos += 1;
+    }
+    Ok(count)
+}
+
+/// Parse `s` as an integer in `base`.
+///
+/// If `base` is 0, the base is determined by the prefix of `s` ("0b", "0o", "0", "0d" or "0x"),
+/// otherwise 10. A prefix which matches `base` is also allowed.
+///
+/// If `strict` is true, `s` must be a valid integer literal as a whole (except surrounding
+/// whitespaces), or None is returned. Otherwise, the longest valid part from the beginning
+/// is converted, and 0 is returned if there is no valid part.
+pub fn parse_integer(s: &str, base: i64, strict: bool) -> Result<Option<i64>, RubyError> {
+    let mut base = if base == 0 { 0 } else { expect_radix(base)? };
+    let s = if strict { s.trim() } else { s.trim_start() }.as_bytes();
+    let mut pos = 0;
+    let negative = s.first() == Some(&b'-');
+    if let Some(b'-') | Some(b'+') = s.first() {
+        pos += 1;
+    }
+    if s.get(pos) == Some(&b'0') {
+        let prefix_base = match s.get(pos + 1) {
+            Some(b'b') | Some(b'B') => 2,
+            Some(b'o') | Some(b'O') => 8,
+            Some(b'd') | Some(b'D') => 10,
+            Some(b'x') | Some(b'X') => 16,
+            _ => 0,
+        };
+        if prefix_base != 0 && (base == 0 || base == prefix_base) {
+            base = prefix_base;
+            pos += 2;
+        } else if base == 0 {
+            base = 8;
+        }
+    }
+    if base == 0 {
+        base = 10;
+    }
+    let mut val = 0;
+    let count = scan_digits(s, &mut pos, base, &mut val)?;
+    if strict && (count == 0 || pos != s.len()) {
+        return Ok(None);
+    }
+    // Bignum is not supported, so integers out of the range of i64 raise RangeError.
+    let val = if negative {
+        -(val as i128)
+    } else {
+        val as i128
+    };
+    match i64::try_from(val) {
+        Ok(i) => Ok(Some(i)),
+        Err(_) => Err(RubyError::range("Integer is too big to convert into i64.")),
+    }
+}
+
+/// A decimal number literal scanned from a string, like "-1_000.25e3".
+pub struct Decimal {
+    pub negative: bool,
+    /// Digits of the integer part followed by the fractional part.
+    pub digits: String,
+    /// The number of digits in the fractional part.
+    pub frac_len: usize,
+    pub exp: i64,
+    /// True if the literal has a fractional part or an exponent.
+    pub is_float: bool,
+    /// The length of the literal in bytes.
+    pub len: usize,
+}
+
+impl Decimal {
+    /// Scan a decimal number literal at the beginning of `s`.
+    pub fn scan(s: &str) -> Option<Decimal> {
+        let s = s.as_bytes();
+        let mut pos = 0;
+        let negative = s.first() == Some(&b'-');
+        if let Some(b'-') | Some(b'+') = s.first() {
+            pos += 1;
+        }
+        let mut digits = String::new();
+        // Collect decimal digits with optional underscores between them, and return
+        // the number of digits.
+        let mut scan = |pos: &mut usize| {
+            let start = digits.len();
+            while *pos < s.len() {
+                match s[*pos] {
+                    ch if ch.is_ascii_digit() => digits.push(ch as char),
+                    b'_' if digits.len() != start
+                        && s.get(*pos + 1).map_or(false, |ch| ch.is_ascii_digit()) => {}
+                    _ => break,
+                }
+                *pos += 1;
+            }
+            digits.len() - start
+        };
+        let int_len = scan(&mut pos);
+        let mut frac_len = 0;
+        let mut is_float = false;
+        if s.get(pos) == Some(&b'.') && s.get(pos + 1).map_or(false, |ch| ch.is_ascii_digit()) {
+            pos += 1;
+            frac_len = scan(&mut pos);
+            is_float = true;
+        }
+        if int_len == 0 && frac_len == 0 {
+            return None;
+        }
+        let mut exp = 0;
+        if let Some(b'e') | Some(b'E') = s.get(pos) {
+            let mut p = pos + 1;
+            let exp_negative = s.get(p) == Some(&b'-');
+            if let Some(b'-') | Some(b'+') = s.get(p) {
+                p += 1;
+            }
+            let start = p;
+            while p < s.len() && s[p].is_ascii_digit() {
+                exp = (exp * 10 + (s[p] - b'0') as i64).min(100000);
+                p += 1;
+            }
+            if p != start {
+                if exp_negative {
+                    exp = -exp;
+                }
+                pos = p;
+                is_float = true;
+            }
+        }
+        Some(Decimal {
+            negative,
+            digits,
+            frac_len,
+            exp,
+            is_float,
+            len: pos,
+        })
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let s = format!(
+            "{}{}e{}",
+            if self.negative { "-" } else { "" },
+            self.digits,
+            self.exp - self.frac_len as i64
+        );
+        s.parse().unwrap()
+    }
+
+    /// Convert to Integer if `self` has neither a fractional part nor an exponent,
+    /// otherwise to Float.
+    pub fn to_value(&self) -> Value {
+        if !self.is_float {
+            if let Ok(i) = self.digits.parse::<i64>() {
+                return Value::integer(if self.negative { -i } else { i });
+            }
+        }
+        Value::float(self.to_f64())
+    }
+
+    /// Convert to a pair of numerator and denominator.
+    pub fn to_rational(&self) -> Result<(i64, i64), RubyError> {
+        let overflow = || RubyError::range("Rational is too big to convert into i64.");
+        let mut num: i64 = self.digits.parse().map_err(|_| overflow())?;
+        let mut den: i64 = 1;
+        let exp = self.exp - self.frac_len as i64;
+        let pow = 10i64.checked_pow(exp.abs() as u32).ok_or_else(overflow)?;
+        if exp >= 0 {
+            num = num.checked_mul(pow).ok_or_else(overflow)?;
+        } else {
+            den = pow;
+        }
+        Ok((if self.negative { -num } else { num }, den))
+    }
+}
+
+/// Parse `s` as a floating point number.
+///
+/// If `strict` is true, `s` must be a valid number as a whole (except surrounding whitespaces),
+/// or None is returned. Otherwise, the longest valid part from the beginning is converted,
+/// and 0.0 is returned if there is no valid part.
+pub fn parse_float(s: &str, strict: bool) -> Option<f64> {
+    if strict {
+        let s = s.trim();
+        let hex = s.trim_start_matches(|ch| ch == '+' || ch == '-');
+        if hex.starts_with("0x") || hex.starts_with("0X") {
+            return parse_integer(s, 16, true).ok()?.map(|i| i as f64);
+        }
+        match Decimal::scan(s) {
+            Some(d) if d.len == s.len() => Some(d.to_f64()),
+            _ => None,
+        }
+    } else {
+        match Decimal::scan(s.trim_start()) {
+            Some(d) => Some(d.to_f64()),
+            None => Some(0.0),
+        }
+    }
+}
+
+/// Parse `s` as a rational number like "-3/4" or "1.25", and return a pair of
+/// numerator and denominator.
+///
+/// See `parse_float` for `strict`.
+pub fn parse_rational(s: &str, strict: bool) -> Result<Option<(i64, i64)>, RubyError> {
+    let s = if strict { s.trim() } else { s.trim_start() };
+    let d = match Decimal::scan(s) {
+        Some(d) => d,
+        None if strict => return Ok(None),
+        None => return Ok(Some((0, 1))),
+    };
+    let (num, mut den) = d.to_rational()?;
+    let mut pos = d.len;
+    let rest = s[pos..].as_bytes();
+    if rest.first() == Some(&b'/') && rest.get(1).map_or(false, |ch| ch.is_ascii_digit()) {
+        let mut divisor = 0;
+        let mut p = 1;
+        scan_digits(rest, &mut p, 10, &mut divisor)?;
+        if divisor == 0 {
+            return Err(RubyError::zero_div("Divided by 0."));
+        }
+        den = i64::try_from(divisor)
+            .ok()
+            .and_then(|divisor| den.checked_mul(divisor))
+            .ok_or_else(|| RubyError::range("Rational is too big to convert into i64."))?;
+        pos += p;
+    }
+    if strict && pos != s.len() {
+        return Ok(None);
+    }
+    Ok(Some((num, den)))
+}
+
+/// Convert `f` to a pair of numerator and denominator exactly.
+pub fn float_to_rational(f: f64) -> Result<(i64, i64), RubyError> {
+    if f.is_nan() || f.is_infinite() {
+        return Err(float_domain_error(f));
+    }
+    if f == 0.0 {
+        return Ok((0, 1));
+    }
+    let bits = f.to_bits();
+    let biased_exp = ((bits >> 52) & 0x7ff) as i64;
+    let mut mantissa = (bits & 0xf_ffff_ffff_ffff) as i64;
+    let mut exp = if biased_exp == 0 {
+        -1074
+    } else {
+        mantissa |= 1 << 52;
+        biased_exp - 1075
+    };
+    // f == mantissa * 2 ** exp
+    while mantissa & 1 == 0 && exp < 0 {
+        mantissa >>= 1;
+        exp += 1;
+    }
+    let overflow = || RubyError::range("Rational is too big to convert into i64.");
+    let (num, den) = if exp >= 0 {
+        if exp > mantissa.leading_zeros() as i64 - 1 {
+            return Err(overflow());
+        }
+        (mantissa << exp, 1)
+    } else {
+        if exp < -62 {
+            return Err(overflow());
+        }
+        (mantissa, 1i64 << -exp)
+    };
+    Ok((if f < 0.0 { -num } else { num }, den))
+}
+
+/// Generate a Rational object from `num` / `den`.
+pub fn rational(num: i64, den: i64) -> VMResult {
+    if den == 0 {
+        return Err(RubyError::zero_div("Divided by 0."));
+    }
+    let gcd = {
+        let (mut a, mut b) = (num.unsigned_abs(), den.unsigned_abs());
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+        a as i128
+    };
+    // Calculate in i128, since negating i64::MIN overflows.
+    let (num, den) = if den < 0 {
+        (-(num as i128) / gcd, -(den as i128) / gcd)
+    } else {
+        (num as i128 / gcd, den as i128 / gcd)
+    };
+    let (num, den) = match (i64::try_from(num), i64::try_from(den)) {
+        (Ok(num), Ok(den)) => (num, den),
+        _ => return Err(RubyError::range("Rational is too big to convert into i64.")),
+    };
+    let class = match BuiltinClass::get_toplevel_constant("Rational") {
+        Some(class) => class.into_module(),
+        None => return Err(RubyError::internal("Rational is not defined.")),
+    };
+    let val = Value::ordinary_object(class);
+    val.set_var_by_str("@numerator", Value::integer(num));
+    val.set_var_by_str("@denominator", Value::integer(den));
+    Ok(val)
+}
+
+/// Generate FloatDomainError for `f`.
+pub fn float_domain_error(f: f64) -> RubyError {
+    let msg = if f.is_nan() {
+        "NaN"
+    } else if f < 0.0 {
+        "-Infinity"
+    } else {
+        "Infinity"
+    };
+    RubyError::float_domain(msg)
+}
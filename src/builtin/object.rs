@@ -17,6 +17,7 @@ pub fn init() {
     object.add_builtin_method_by_str("<=>", cmp);
     object.add_builtin_method_by_str("eql?", eql);
     object.add_builtin_method_by_str("singleton_class", singleton_class);
+    object.add_builtin_method_by_str("extend", extend);
     object.add_builtin_method_by_str("clone", dup);
     object.add_builtin_method_by_str("dup", dup);
     object.add_builtin_method_by_str("nil?", nil_);
@@ -24,6 +25,8 @@ pub fn init() {
     object.add_builtin_method_by_str("instance_variable_set", instance_variable_set);
     object.add_builtin_method_by_str("instance_variable_get", instance_variable_get);
     object.add_builtin_method_by_str("instance_variables", instance_variables);
+    object.add_builtin_method_by_str("instance_variable_defined?", instance_variable_defined);
+    object.add_builtin_method_by_str("remove_instance_variable", remove_instance_variable);
     object.add_builtin_method_by_str("instance_of?", instance_of);
     object.add_builtin_method_by_str("freeze", freeze);
     object.add_builtin_method_by_str("super", super_);
@@ -35,6 +38,7 @@ pub fn init() {
     object.add_builtin_method_by_str("singleton_methods", singleton_methods);
     object.add_builtin_method_by_str("respond_to?", respond_to);
     object.add_builtin_method_by_str("instance_exec", instance_exec);
+    object.add_builtin_method_by_str("instance_eval", instance_eval);
 }
 
 fn initialize(_vm: &mut VM, self_val: Value, _: &Args) -> VMResult {
@@ -78,6 +82,19 @@ fn singleton_class(_: &mut VM, self_val: Value, _: &Args) -> VMResult {
     self_val.get_singleton_class().map(|c| c.into())
 }
 
+/// Include `modules` into the singleton class of `self`.
+/// https://docs.ruby-lang.org/ja/latest/class/Object.html#I_EXTEND
+fn extend(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_min(1)?;
+    let mut singleton = self_val.get_singleton_class()?;
+    for arg in args.iter().rev() {
+        let module = arg.expect_module("Args")?;
+        singleton.append_include(module);
+        vm.invoke_hook("extended", module.into(), self_val)?;
+    }
+    Ok(self_val)
+}
+
 fn dup(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     let val = self_val.dup();
@@ -100,8 +117,12 @@ fn method(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
         Some(id) => id,
         None => return Err(RubyError::wrong_type("1st arg", "Symbol", args[0])),
     };
-    let method = vm.get_method_from_receiver(self_val, name)?;
-    let val = Value::method(name, self_val, method);
+    let rec_class = self_val.get_class_for_method();
+    let (method, owner) = match rec_class.get_method_with_owner(name) {
+        Some(res) => res,
+        None => return Err(RubyError::undefined_method(name, self_val)),
+    };
+    let val = Value::method(name, self_val, method, owner);
     Ok(val)
 }
 
@@ -140,6 +161,45 @@ fn instance_variables(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::array_from(res))
 }
 
+/// Check whether `val` is a valid name of instance variables.
+fn expect_ivar_name(val: Value) -> Result<IdentId, RubyError> {
+    let id = val.expect_symbol_or_string("1st arg")?;
+    if IdentId::starts_with(id, "@") && !IdentId::starts_with(id, "@@") {
+        Ok(id)
+    } else {
+        Err(RubyError::name(format!(
+            "`{:?}' is not allowed as an instance variable name.",
+            id
+        )))
+    }
+}
+
+fn instance_variable_defined(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_ivar_name(args[0])?;
+    let b = match self_val.as_rvalue() {
+        Some(rval) => rval.get_var(id).is_some(),
+        None => false,
+    };
+    Ok(Value::bool(b))
+}
+
+fn remove_instance_variable(_: &mut VM, mut self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = expect_ivar_name(args[0])?;
+    let val = match self_val.as_mut_rvalue() {
+        Some(rval) => rval.var_table_mut().remove(&id),
+        None => None,
+    };
+    match val {
+        Some(val) => Ok(val),
+        None => Err(RubyError::name(format!(
+            "Instance variable {:?} not defined.",
+            id
+        ))),
+    }
+}
+
 fn instance_of(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     Ok(Value::bool(args[0].id() == self_val.get_class().id()))
@@ -173,21 +233,62 @@ fn super_(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
                 )));
             }
         };
-        if args.len() == 0 {
-            let param_num = iseq.params.param_ident.len();
-            let mut args = Args::new0();
-            for i in 0..param_num {
-                args.push(context[i]);
-            }
-            vm.eval_method(method, context.self_value, &args)
+        let mut args = if args.len() == 0 && args.kw_arg.is_nil() {
+            // Zsuper passes the current values of the parameters.
+            let mut zsuper_args = zsuper_args(context);
+            zsuper_args.block = args.block.clone();
+            zsuper_args
         } else {
-            vm.eval_method(method, context.self_value, &args)
+            args.clone()
+        };
+        if args.block.is_none() {
+            args.block = context.block.clone();
         }
+        vm.eval_method(method, context.self_value, &args)
     } else {
         return Err(RubyError::nomethod("super called outside of method"));
     }
 }
 
+/// Build arguments for zsuper from the parameters of the method `context`.
+fn zsuper_args(context: ContextRef) -> Args {
+    let iseq = context.iseq_ref.unwrap();
+    let params = &iseq.params;
+    let mut args = Args::new0();
+    let mut i = 0;
+    for _ in 0..params.req + params.opt {
+        args.push(context[i]);
+        i += 1;
+    }
+    if params.rest == Some(true) {
+        if let Some(ary) = context[i].as_array() {
+            ary.elements.iter().for_each(|v| args.push(*v));
+        }
+        i += 1;
+    }
+    for _ in 0..params.post {
+        args.push(context[i]);
+        i += 1;
+    }
+    let mut kw = FxIndexMap::default();
+    for id in &params.param_ident {
+        if let Some(lvar) = params.keyword.get(id) {
+            kw.insert(HashKey(Value::symbol(*id)), context[*lvar]);
+        }
+    }
+    if let Some(lvar) = iseq.lvar.kwrest_param() {
+        if let Some(hash) = context[lvar].as_hash() {
+            for (k, v) in hash.iter() {
+                kw.insert(HashKey(k), v);
+            }
+        }
+    }
+    if !kw.is_empty() {
+        args.kw_arg = Value::hash_from_map(kw);
+    }
+    args
+}
+
 fn equal(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     Ok(Value::bool(self_val.id() == args[0].id()))
@@ -207,6 +308,7 @@ fn send(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
         new_args[i] = args[i + 1];
     }
     new_args.block = args.block.clone();
+    new_args.kw_arg = args.kw_arg;
     let res = vm.eval_method(method, self_val, &new_args)?;
     Ok(res)
 }
@@ -280,13 +382,13 @@ fn singleton_methods(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::array_from(v.iter().cloned().collect()))
 }
 
-fn respond_to(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+fn respond_to(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_range(1, 2)?;
     if args.len() == 2 {
         eprintln!("Warining: 2nd arg will not used. respont_to?()")
     };
     let method = args[0].expect_string_or_symbol("1st arg")?;
-    let b = MethodRepo::find_method_from_receiver(self_val, method).is_some();
+    let b = vm.find_method_from_receiver(self_val, method).is_some();
     Ok(Value::bool(b))
 }
 
@@ -299,6 +401,28 @@ fn instance_exec(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     res
 }
 
+fn instance_eval(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let class = self_val.get_class_for_method();
+    // Methods defined in instance_eval go to the singleton class of the receiver.
+    // For receivers without a singleton class (e.g. Integer), `def` raises TypeError.
+    let definee = self_val.get_singleton_class().unwrap_or(class);
+    match &args.block {
+        Block::None => {
+            vm.singleton_class_push(definee);
+            let res = builtin::module::eval_string(vm, self_val, class, args);
+            vm.class_pop();
+            res
+        }
+        block => {
+            args.check_args_num(0)?;
+            vm.singleton_class_push(definee);
+            let res = vm.eval_block_self(block, self_val, &Args::new1(self_val));
+            vm.class_pop();
+            res
+        }
+    }
+}
+
 fn match_(_: &mut VM, _: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     Ok(Value::nil())
@@ -560,14 +684,14 @@ mod test {
         c = B.new
         def c.baz
         end
-        assert [:foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], a.methods
-        assert [:foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], a.methods(true)
+        assert [:foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, a.methods.sort
+        assert [:foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, a.methods(true).sort
         assert [], a.methods(false)
-        assert [:bar, :foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], b.methods
-        assert [:bar, :foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], b.methods(true)
+        assert [:bar, :foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, b.methods.sort
+        assert [:bar, :foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, b.methods(true).sort
         assert [], b.methods(false)
-        assert [:baz, :bar, :foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], c.methods
-        assert [:baz, :bar, :foo, :instance_exec, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :sleep, :loop, :__FILE__, :is_a?, :require_relative, :assert, :puts, :eval, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :method_missing, :__id__], c.methods(true)
+        assert [:baz, :bar, :foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, c.methods.sort
+        assert [:baz, :bar, :foo, :instance_exec, :instance_eval, :instance_variable_defined?, :remove_instance_variable, :methods, :__send__, :freeze, :instance_variable_get, :nil?, :singleton_class, :equal?, :===, :inspect, :initialize, :singleton_methods, :to_enum, :super, :instance_variables, :method, :clone, :extend, :=~, :class, :to_s, :==, :respond_to?, :enum_for, :send, :instance_of?, :instance_variable_set, :dup, :eql?, :object_id, :<=>, :Integer, :Float, :String, :Hash, :Rational, :sleep, :loop, :__FILE__, :__LINE__, :__method__, :caller, :caller_locations, :is_a?, :require_relative, :assert, :puts, :eval, :catch, :throw, :binding, :Array, :lambda, :abort, :rand, :__dir__, :block_given?, :require, :print, :"`", :Complex, :proc, :exit, :raise, :kind_of?, :load, :assert_error, :p, :at_exit, :"/alias_method", :"/undef_method", :method_missing, :__id__, :to_json].sort, c.methods(true).sort
         assert [:baz], c.methods(false)
         "#;
         assert_script(program);
@@ -646,4 +770,120 @@ mod test {
         "#;
         assert_script(program);
     }
+
+    #[test]
+    fn object_extend() {
+        let program = r#"
+        module Greet
+          def greet
+            "Hello, #{name}"
+          end
+          def self.extended(obj)
+            $extended = obj
+          end
+        end
+        class Person
+          attr_accessor :name
+        end
+        a = Person.new
+        a.name = "Alice"
+        b = Person.new
+        assert a, a.extend(Greet)
+        assert a, $extended
+        assert "Hello, Alice", a.greet
+        assert true, a.singleton_class.include?(Greet)
+        assert false, b.singleton_class.include?(Greet)
+        assert_error { b.greet }
+        assert_error { a.extend(3) }
+
+        module Util
+          extend self
+          def twice(x)
+            x * 2
+          end
+        end
+        assert 6, Util.twice(3)
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn object_instance_eval() {
+        let program = r#"
+        class Foo
+          def initialize
+            @a = 1
+          end
+        end
+        foo = Foo.new
+        assert 1, foo.instance_eval { @a }
+        assert 1, foo.instance_eval("@a")
+        assert foo, foo.instance_eval { |x| x }
+        foo.instance_eval do
+          def bar
+            @a + 10
+          end
+        end
+        assert 11, foo.bar
+        assert_error { Foo.new.bar }
+        foo.instance_eval "def baz; 100; end"
+        assert 100, foo.baz
+        assert_error { foo.instance_eval("raise 'x'", "foo.rb", 5) }
+        Foo.instance_eval do
+          def create
+            new
+          end
+        end
+        assert Foo, Foo.create.class
+        assert 7, 7.instance_eval { self }
+        assert_error { 7.instance_eval { def seven; end } }
+        assert_error { :sym.instance_eval "def sym; end" }
+        assert false, 8.respond_to?(:seven)
+        assert false, :foo.respond_to?(:sym)
+
+        class Bar
+          def make
+            def made; end
+          end
+        end
+        bar = Bar.new
+        def bar.singleton_method_added(id)
+          $added << id
+        end
+        $added = []
+        bar.instance_eval do
+          def qux; end
+          make
+        end
+        bar.instance_eval "def quux; end"
+        assert [:qux, :quux], $added
+        assert [:made, :make], Bar.instance_methods(false).sort
+        assert true, bar.singleton_methods.include?(:qux)
+        assert true, bar.singleton_methods.include?(:quux)
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn object_instance_variable() {
+        let program = r#"
+        class Foo
+          def initialize
+            @a = 1
+            @b = nil
+          end
+        end
+        foo = Foo.new
+        assert true, foo.instance_variable_defined?(:@a)
+        assert true, foo.instance_variable_defined?("@b")
+        assert false, foo.instance_variable_defined?(:@c)
+        assert false, 3.instance_variable_defined?(:@a)
+        assert_error { foo.instance_variable_defined?(:a) }
+        assert 1, foo.remove_instance_variable(:@a)
+        assert false, foo.instance_variable_defined?(:@a)
+        assert [:@b], foo.instance_variables
+        assert_error { foo.remove_instance_variable(:@a) }
+        "#;
+        assert_script(program);
+    }
 }
@@ -0,0 +1,351 @@
+use crate::*;
+
+pub fn init() -> Value {
+    let mut module = Module::module();
+    BuiltinClass::set_toplevel_constant("ObjectSpace", module);
+    module.add_builtin_class_method("each_object", each_object);
+    module.add_builtin_class_method("count_objects", count_objects);
+    module.add_builtin_class_method("_id2ref", id2ref);
+    module.add_builtin_class_method("define_finalizer", define_finalizer);
+    module.add_builtin_class_method("undefine_finalizer", undefine_finalizer);
+    module.add_builtin_class_method("garbage_collect", garbage_collect);
+
+    let weak_map = Module::class_under_object();
+    module.set_const_by_str("WeakMap", weak_map.into());
+    weak_map.add_builtin_method_by_str("[]=", weakmap_set);
+    weak_map.add_builtin_method_by_str("[]", weakmap_get);
+    weak_map.add_builtin_method_by_str("key?", weakmap_has_key);
+    weak_map.add_builtin_method_by_str("include?", weakmap_has_key);
+    weak_map.add_builtin_method_by_str("member?", weakmap_has_key);
+    weak_map.add_builtin_method_by_str("keys", weakmap_keys);
+    weak_map.add_builtin_method_by_str("values", weakmap_values);
+    weak_map.add_builtin_method_by_str("size", weakmap_size);
+    weak_map.add_builtin_method_by_str("length", weakmap_size);
+    weak_map.add_builtin_method_by_str("each", weakmap_each);
+    weak_map.add_builtin_method_by_str("each_pair", weakmap_each);
+    weak_map.add_builtin_method_by_str("inspect", weakmap_inspect);
+    module.into()
+}
+
+/// Check whether `obj` is visible from Ruby programs.
+fn is_visible(obj: Value) -> bool {
+    match &obj.rvalue().kind {
+        ObjKind::Splat(_) => false,
+        ObjKind::Module(cinfo) => !cinfo.is_included(),
+        _ => true,
+    }
+}
+
+// Class methods
+
+fn each_object(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let module = if args.len() == 1 {
+        Some(args[0].expect_mod_class()?)
+    } else {
+        None
+    };
+    let block = match &args.block {
+        Block::None => {
+            let id = IdentId::get_id("each_object");
+            return vm.create_enumerator(id, self_val, args.clone());
+        }
+        block => block,
+    };
+    let mut objects = vec![];
+    ALLOC.with(|m| {
+        m.borrow().each_object(|obj| {
+            if is_visible(obj) {
+                match module {
+                    Some(module) if !obj.get_class().include_module(module) => {}
+                    _ => objects.push(obj),
+                }
+            }
+        })
+    });
+    // Objects must be kept alive while the block is evaluated.
+    let len = vm.temp_len();
+    vm.temp_push_vec(&objects);
+    for obj in &objects {
+        vm.eval_block(block, &Args::new1(*obj))?;
+    }
+    vm.temp_pop_vec(len);
+    Ok(Value::integer(objects.len() as i64))
+}
+
+fn count_objects(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let mut total = 0;
+    let mut free = 0;
+    let mut count = FxIndexMap::default();
+    ALLOC.with(|m| {
+        m.borrow().each_slot(|slot| {
+            total += 1;
+            let kind = match &slot.inner().kind {
+                ObjKind::Invalid => {
+                    free += 1;
+                    return;
+                }
+                ObjKind::Ordinary | ObjKind::Exception(_) => "T_OBJECT",
+                ObjKind::Integer(_) => "T_BIGNUM",
+                ObjKind::Float(_) => "T_FLOAT",
+                ObjKind::Complex { .. } => "T_COMPLEX",
+                ObjKind::Module(cinfo) if cinfo.is_included() => "T_ICLASS",
+                ObjKind::Module(cinfo) if cinfo.is_module() => "T_MODULE",
+                ObjKind::Module(_) => "T_CLASS",
+                ObjKind::String(_) => "T_STRING",
+                ObjKind::Array(_) | ObjKind::Splat(_) => "T_ARRAY",
+                ObjKind::Range(_) => "T_STRUCT",
+                ObjKind::Hash(_) => "T_HASH",
+                ObjKind::Regexp(_) => "T_REGEXP",
+                _ => "T_DATA",
+            };
+            *count.entry(kind).or_insert(0) += 1;
+        })
+    });
+    let mut hash = FxIndexMap::default();
+    let mut insert = |key: &str, num: usize| {
+        let key = Value::symbol_from_str(key);
+        hash.insert(HashKey(key), Value::integer(num as i64));
+    };
+    insert("TOTAL", total);
+    insert("FREE", free);
+    count.sort_keys();
+    for (kind, num) in count {
+        insert(kind, num);
+    }
+    Ok(Value::hash_from_map(hash))
+}
+
+fn id2ref(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let id = args[0].expect_integer("1st arg")? as u64;
+    if id != 0 {
+        let val = Value::from(id);
+        if val.is_packed_value() {
+            if is_valid_packed_value(val) {
+                return Ok(val);
+            }
+        } else if ALLOC.with(|m| m.borrow().is_live_object(id)) && is_visible(val) {
+            return Ok(val);
+        }
+    }
+    Err(RubyError::range(format!("0x{:016x} is not id value.", id)))
+}
+
+/// Examine if `val` is a packed value which can be an object id.
+/// (Fixnum, Flonum, nil, true, false, or an interned Symbol)
+fn is_valid_packed_value(val: Value) -> bool {
+    if val.is_packed_num() || val.is_nil() || val.is_true_val() || val.is_false_val() {
+        true
+    } else if val.is_packed_symbol() {
+        let id = val.as_packed_symbol();
+        Value::symbol(id).id() == val.id() && IdentId::is_interned(id)
+    } else {
+        false
+    }
+}
+
+fn define_finalizer(vm: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_range(1, 2)?;
+    let obj = args[0];
+    let proc = if args.len() == 2 {
+        args[1]
+    } else {
+        vm.create_proc(args.expect_block()?)?
+    };
+    if obj.is_packed_value() {
+        return Err(RubyError::argument(format!(
+            "Cannot define finalizer for {}.",
+            obj.get_class_name()
+        )));
+    }
+    if MethodRepo::find_method_from_receiver(proc, IdentId::get_id("call")).is_none() {
+        return Err(RubyError::argument(format!(
+            "Wrong type argument {} (should be callable).",
+            proc.get_class_name()
+        )));
+    }
+    ALLOC.with(|m| m.borrow_mut().add_finalizer(obj, proc));
+    Ok(Value::array_from(vec![Value::integer(0), proc]))
+}
+
+fn undefine_finalizer(_: &mut VM, _: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    ALLOC.with(|m| m.borrow_mut().remove_finalizers(args[0]));
+    Ok(args[0])
+}
+
+fn garbage_collect(vm: &mut VM, _: Value, _: &Args) -> VMResult {
+    vm.globals.gc();
+    vm.run_finalizers();
+    Ok(Value::nil())
+}
+
+// Instance methods of ObjectSpace::WeakMap
+
+fn weakmap_set(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(2)?;
+    ALLOC.with(|m| m.borrow_mut().weak_map_set(self_val, args[0], args[1]));
+    Ok(args[1])
+}
+
+fn weakmap_get(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let val = ALLOC.with(|m| m.borrow().weak_map_get(self_val, args[0]));
+    Ok(val.unwrap_or_default())
+}
+
+fn weakmap_has_key(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let val = ALLOC.with(|m| m.borrow().weak_map_get(self_val, args[0]));
+    Ok(Value::bool(val.is_some()))
+}
+
+fn weakmap_keys(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let entries = ALLOC.with(|m| m.borrow().weak_map_entries(self_val));
+    Ok(Value::array_from(entries.iter().map(|(k, _)| *k).collect()))
+}
+
+fn weakmap_values(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let entries = ALLOC.with(|m| m.borrow().weak_map_entries(self_val));
+    Ok(Value::array_from(entries.iter().map(|(_, v)| *v).collect()))
+}
+
+fn weakmap_size(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let entries = ALLOC.with(|m| m.borrow().weak_map_entries(self_val));
+    Ok(Value::integer(entries.len() as i64))
+}
+
+fn weakmap_each(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let block = args.expect_block()?;
+    let entries = ALLOC.with(|m| m.borrow().weak_map_entries(self_val));
+    let len = vm.temp_len();
+    for (k, v) in &entries {
+        vm.temp_push(*k);
+        vm.temp_push(*v);
+    }
+    for (k, v) in entries {
+        vm.eval_block(block, &Args::new2(k, v))?;
+    }
+    vm.temp_pop_vec(len);
+    Ok(self_val)
+}
+
+fn weakmap_inspect(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let entries = ALLOC.with(|m| m.borrow().weak_map_entries(self_val));
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|(k, v)| format!("{:?} => {:?}", k, v))
+        .collect();
+    let s = format!(
+        "#<ObjectSpace::WeakMap:0x{:016x} {}>",
+        self_val.id(),
+        entries.join(", ")
+    );
+    Ok(Value::string(s))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::*;
+
+    #[test]
+    fn objectspace_each_object() {
+        let program = r#"
+        class Foo; end
+        a = Foo.new
+        b = Foo.new
+        objs = []
+        assert 2, ObjectSpace.each_object(Foo) { |x| objs << x }
+        assert true, objs.include?(a)
+        assert true, objs.include?(b)
+        assert true, ObjectSpace.each_object(Class).to_a.include?(Foo)
+        assert a, ObjectSpace._id2ref(a.object_id)
+        assert :foo, ObjectSpace._id2ref(:foo.object_id)
+        assert_error { ObjectSpace._id2ref(8) }
+        assert 3, ObjectSpace._id2ref(3.object_id)
+        assert nil, ObjectSpace._id2ref(nil.object_id)
+        assert true, ObjectSpace._id2ref(true.object_id)
+        assert_error { ObjectSpace._id2ref(4) }
+        assert_error { ObjectSpace._id2ref(0x7fff_ffff_0000_000c) }
+        assert_error { ObjectSpace._id2ref(0x0000_0000_0000_100c) }
+        h = ObjectSpace.count_objects
+        assert true, h[:TOTAL] >= h[:FREE] + h[:T_STRING]
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn objectspace_finalizer() {
+        let program = r#"
+        $res = []
+        def finalizer(sym)
+          proc { |id| $res << sym }
+        end
+        def make
+          a = "a"
+          ObjectSpace.define_finalizer(a, finalizer(:a))
+          b = "b"
+          ObjectSpace.define_finalizer(b, finalizer(:b))
+          ObjectSpace.undefine_finalizer(b)
+          nil
+        end
+        make
+        ObjectSpace.garbage_collect
+        assert [:a], $res
+        assert_error { ObjectSpace.define_finalizer(1) { } }
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn objectspace_weakmap() {
+        let program = r#"
+        map = ObjectSpace::WeakMap.new
+        k = "key"
+        v = "value"
+        map[k] = v
+        map[:sym] = 100
+        assert v, map[k]
+        assert true, map.key?(k)
+        assert nil, map["other"]
+        assert 2, map.size
+        def add_garbage(map)
+          map["garbage_key"] = "garbage"
+          nil
+        end
+        add_garbage(map)
+        assert 3, map.size
+        GC.start
+        assert 2, map.size
+        assert [k, :sym], map.keys
+        assert [v, 100], map.values
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn weakref() {
+        let program = r#"
+        obj = "object"
+        ref = WeakRef.new(obj)
+        assert true, ref.weakref_alive?
+        assert 6, ref.size
+        assert obj, ref.__getobj__
+        def make_ref
+          WeakRef.new("garbage")
+        end
+        ref2 = make_ref
+        GC.start
+        assert false, ref2.weakref_alive?
+        assert_error { ref2.__getobj__ }
+    "#;
+        assert_script(program);
+    }
+}
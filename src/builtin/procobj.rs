@@ -18,6 +18,13 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("inspect", inspect);
     class.add_builtin_method_by_str("call", proc_call);
     class.add_builtin_method_by_str("[]", proc_call);
+    class.add_builtin_method_by_str("yield", proc_call);
+    class.add_builtin_method_by_str("===", proc_call);
+    class.add_builtin_method_by_str("arity", arity);
+    class.add_builtin_method_by_str("lambda?", lambda);
+    class.add_builtin_method_by_str("parameters", parameters);
+    class.add_builtin_method_by_str("source_location", source_location);
+    class.add_builtin_method_by_str("binding", binding);
 
     class.add_builtin_class_method("new", proc_new);
     class.into()
@@ -48,6 +55,50 @@ fn proc_call(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     vm.eval_proc(self_val, args)
 }
 
+fn arity(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let pref = self_val.as_proc().unwrap();
+    let params = &pref.context.iseq_ref.unwrap().params;
+    let arity = if pref.context.kind != ISeqKind::Block {
+        params.arity()
+    } else {
+        // Optional parameters of a proc are not taken into account unless it has a rest parameter.
+        let required = (params.req + params.post) as i64;
+        if params.rest.is_some() {
+            -required - 1
+        } else {
+            required
+        }
+    };
+    Ok(Value::integer(arity))
+}
+
+fn lambda(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let pref = self_val.as_proc().unwrap();
+    Ok(Value::bool(pref.context.kind != ISeqKind::Block))
+}
+
+fn parameters(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let pref = self_val.as_proc().unwrap();
+    let is_proc = pref.context.kind == ISeqKind::Block;
+    Ok(pref.context.iseq_ref.unwrap().params.parameters(is_proc))
+}
+
+fn source_location(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let pref = self_val.as_proc().unwrap();
+    Ok(pref.context.iseq_ref.unwrap().source_location())
+}
+
+fn binding(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let pref = self_val.as_proc().unwrap();
+    let context = pref.context.outer.unwrap_or(pref.context);
+    Ok(Value::binding(context))
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::*;
@@ -65,4 +116,97 @@ mod test {
         ";
         assert_script(program);
     }
+
+    #[test]
+    fn proc_introspection() {
+        let program = r#"
+        pr = proc { |a, b = 1, *c, d, &e| }
+        la = lambda { |a, b = 1, *c, d, &e| }
+        assert false, pr.lambda?
+        assert true, la.lambda?
+        assert true, ->(x){}.lambda?
+        assert(-3, pr.arity)
+        assert(-3, la.arity)
+        assert 1, proc { |x, y = 0| }.arity
+        assert(-2, lambda { |x, y = 0| }.arity)
+        assert 0, proc {}.arity
+        assert 2, proc { |x, y| }.arity
+        assert [[:opt, :a], [:opt, :b], [:rest, :c], [:opt, :d], [:block, :e]], pr.parameters
+        assert [[:req, :a], [:opt, :b], [:rest, :c], [:req, :d], [:block, :e]], la.parameters
+        assert 2, pr.source_location[1]
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn proc_call_aliases() {
+        let program = r#"
+        pr = proc { |x| x * 2 }
+        assert 6, pr.call(3)
+        assert 6, pr[3]
+        assert 6, pr.yield(3)
+        assert 6, pr === 3
+        assert 6, pr.(3)
+        case 5
+        when ->(x) { x > 3 }
+          res = :big
+        else
+          res = :small
+        end
+        assert :big, res
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn proc_lambda_strictness() {
+        let program = r#"
+        pr = proc { |a, b| [a, b] }
+        assert [1, nil], pr.call(1)
+        assert [1, 2], pr.call(1, 2, 3)
+        assert [1, 2], pr.call([1, 2])
+        la = lambda { |a, b| [a, b] }
+        assert [1, 2], la.call(1, 2)
+        assert_error { la.call(1) }
+        assert_error { la.call(1, 2, 3) }
+        assert_error { la.call([1, 2]) }
+        la2 = lambda { |a| a }
+        assert [1, 2], la2.call([1, 2])
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn proc_curry() {
+        let program = r#"
+        add = lambda { |a, b, c| a + b + c }
+        assert 6, add.curry[1][2][3]
+        assert 6, add.curry[1, 2][3]
+        assert 6, add.curry.(1).(2, 3)
+        assert_error { add.curry(2) }
+        pr = proc { |a, b, c| (a || 0) + (b || 0) + (c || 0) }
+        assert 6, pr.curry[1][2][3]
+        assert 3, pr.curry(2)[1][2]
+        sum = proc { |*a| a.inject(0) { |x, y| x + y } }
+        assert 10, sum.curry(4)[1][2][3][4]
+    "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn proc_compose() {
+        let program = r#"
+        f = proc { |x| x * 2 }
+        g = proc { |x| x + 1 }
+        assert 7, (f >> g).call(3)
+        assert 8, (f << g).call(3)
+        assert false, (f >> g).lambda?
+        l = lambda { |x| x * 3 }
+        assert true, (l >> g).lambda?
+        assert 10, (l >> g).call(3)
+        h = f >> g >> l
+        assert 21, h.call(3)
+    "#;
+        assert_script(program);
+    }
 }
@@ -47,6 +47,7 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("to_a", to_a);
     class.add_builtin_method_by_str("exclude_end?", exclude_end);
     class.add_builtin_method_by_str("include?", include);
+    class.add_builtin_method_by_str("===", include);
 
     class.add_builtin_class_method("new", range_new);
     class.into()
@@ -233,10 +234,15 @@ fn to_a(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
                 .collect();
             Ok(Value::array_from(v))
         } else {
-            unimplemented!()
+            Err(RubyError::not_implemented(
+                "Range#to_a for multi-character strings is not implemented.",
+            ))
         }
     } else {
-        unimplemented!()
+        Err(RubyError::typeerr(format!(
+            "Can't iterate from {}.",
+            start.get_class_name()
+        )))
     }
 }
 
@@ -249,6 +255,23 @@ fn exclude_end(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
 fn include(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let range = self_val.as_range().unwrap();
+    if range.start.is_nil() || range.end.is_nil() {
+        // Beginless or endless range.
+        let val = args[0];
+        if !range.start.is_nil() {
+            match compare(vm, range.start, val)? {
+                Some(i) if i <= 0 => {}
+                _ => return Ok(Value::false_val()),
+            }
+        }
+        if !range.end.is_nil() {
+            match compare(vm, val, range.end)? {
+                Some(i) if i < 0 || (i == 0 && !range.exclude) => {}
+                _ => return Ok(Value::false_val()),
+            }
+        }
+        return Ok(Value::true_val());
+    }
     match range.start.unpack() {
         RV::Integer(start) => {
             let start = Real::Integer(start);
@@ -284,6 +307,12 @@ fn include(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     }
 }
 
+/// Compare `lhs` and `rhs` by `<=>`. Return None if they are not comparable.
+fn compare(vm: &mut VM, lhs: Value, rhs: Value) -> Result<Option<i64>, RubyError> {
+    let res = vm.send(IdentId::_CMP, lhs, &Args::new1(rhs))?;
+    Ok(res.as_integer())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
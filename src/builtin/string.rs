@@ -31,6 +31,7 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("tr", tr);
     class.add_builtin_method_by_str("size", size);
     class.add_builtin_method_by_str("length", size);
+    class.add_builtin_method_by_str("bytesize", bytesize);
     class.add_builtin_method_by_str("bytes", bytes);
     class.add_builtin_method_by_str("each_byte", each_byte);
     class.add_builtin_method_by_str("chars", chars);
@@ -39,6 +40,11 @@ pub fn init() -> Value {
     class.add_builtin_method_by_str("upcase", upcase);
     class.add_builtin_method_by_str("chomp", chomp);
     class.add_builtin_method_by_str("to_i", toi);
+    class.add_builtin_method_by_str("hex", hex);
+    class.add_builtin_method_by_str("oct", oct);
+    class.add_builtin_method_by_str("to_f", tof);
+    class.add_builtin_method_by_str("to_r", tor);
+    class.add_builtin_method_by_str("to_c", toc);
     class.add_builtin_method_by_str("<", lt);
     class.add_builtin_method_by_str(">", gt);
     class.add_builtin_method_by_str("center", center);
@@ -665,6 +671,12 @@ fn size(_: &mut VM, mut self_val: Value, args: &Args) -> VMResult {
     Ok(Value::integer(rec.chars().count() as i64))
 }
 
+fn bytesize(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let bytes = self_val.expect_bytes("Receiver")?;
+    Ok(Value::integer(bytes.len() as i64))
+}
+
 fn bytes(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(0)?;
     match &args.block {
@@ -749,19 +761,86 @@ fn chomp(_: &mut VM, mut self_val: Value, args: &Args) -> VMResult {
     Ok(Value::string(res))
 }
 
-fn toi(_: &mut VM, mut self_val: Value, args: &Args) -> VMResult {
-    args.check_args_num(0)?;
-    let self_ = match self_val.expect_string("Receiver") {
-        Ok(s) => s,
-        Err(_) => return Ok(Value::integer(0)),
+/// to_i(base = 10) -> Integer
+///
+/// `base` can be 0, which means the base is determined by the prefix.
+fn toi(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_range(0, 1)?;
+    let base = match args.len() {
+        0 => 10,
+        _ => args[0].expect_integer("Base")?,
     };
-    let i: i64 = match self_.parse() {
-        Ok(i) => i,
-        Err(_) => 0,
+    let s = self_val.as_rstring().unwrap().to_s();
+    let i = numeric::parse_integer(&s, base, false)?.unwrap_or(0);
+    Ok(Value::integer(i))
+}
+
+fn hex(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let s = self_val.as_rstring().unwrap().to_s();
+    let i = numeric::parse_integer(&s, 16, false)?.unwrap_or(0);
+    Ok(Value::integer(i))
+}
+
+/// Convert as an octal number, or according to the prefix ("0b", "0o", "0d" or "0x").
+fn oct(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let s = self_val.as_rstring().unwrap().to_s();
+    let unsigned = s
+        .trim_start()
+        .trim_start_matches(|ch| ch == '+' || ch == '-');
+    let base = match unsigned.get(0..2) {
+        Some(prefix) if prefix.len() == 2 && prefix.starts_with('0') => match &prefix[1..] {
+            "b" | "B" | "o" | "O" | "d" | "D" | "x" | "X" => 0,
+            _ => 8,
+        },
+        _ => 8,
     };
+    let i = numeric::parse_integer(&s, base, false)?.unwrap_or(0);
     Ok(Value::integer(i))
 }
 
+fn tof(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let s = self_val.as_rstring().unwrap().to_s();
+    let f = numeric::parse_float(&s, false).unwrap_or(0.0);
+    Ok(Value::float(f))
+}
+
+fn tor(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let s = self_val.as_rstring().unwrap().to_s();
+    let (num, den) = numeric::parse_rational(&s, false)?.unwrap_or((0, 1));
+    numeric::rational(num, den)
+}
+
+/// Convert a string like "1.5+2i" to Complex.
+fn toc(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let s = self_val.as_rstring().unwrap().to_s();
+    let s = s.trim_start();
+    let zero = Value::integer(0);
+    let real = match numeric::Decimal::scan(s) {
+        Some(real) => real,
+        None => return Ok(Value::complex(zero, zero)),
+    };
+    let rest = &s[real.len..];
+    if rest.starts_with('i') {
+        return Ok(Value::complex(zero, real.to_value()));
+    }
+    let imag = match rest.get(0..1) {
+        Some("+") | Some("-") if rest[1..].starts_with('i') => {
+            Value::integer(if rest.starts_with('-') { -1 } else { 1 })
+        }
+        Some("+") | Some("-") => match numeric::Decimal::scan(rest) {
+            Some(imag) if rest[imag.len..].starts_with('i') => imag.to_value(),
+            _ => zero,
+        },
+        _ => zero,
+    };
+    Ok(Value::complex(real.to_value(), imag))
+}
+
 fn lt(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     args.check_args_num(1)?;
     let lhs = self_val.as_rstring().unwrap();
@@ -1303,6 +1382,43 @@ mod test {
         [49, 53, 55, 56].map{ |elem| a += elem.chr }
         assert 1578, a.to_i
         assert 0, "k".to_i
+        assert 1_000, " 1_000xyz".to_i
+        assert -255, "-ff".to_i(16)
+        assert 5, "101".to_i(2)
+        assert 255, "0xff".to_i(16)
+        assert 255, "0xff".to_i(0)
+        assert 8, "010".to_i(0)
+        assert 35, "z".to_i(36)
+        assert -9223372036854775807 - 1, "-9223372036854775808".to_i
+        assert 9223372036854775807, "9223372036854775807".to_i
+        assert_error { "9223372036854775808".to_i }
+        assert_error { "1".to_i(1) }
+        assert_error { "1".to_i(37) }
+        assert 255, "0xff".hex
+        assert -10, "-a".hex
+        assert 8, "10".oct
+        assert 5, "0b101".oct
+        assert 16, "0x10".oct
+        assert 0, "z".oct
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn string_tof() {
+        let program = r#"
+        assert 1.5, "1.5".to_f
+        assert -0.25, " -.25xyz".to_f
+        assert 1200.0, "1_2e2".to_f
+        assert 3.0, "3.".to_f
+        assert 0.0, "abc".to_f
+        assert Rational(3, 4), "0.75".to_r
+        assert Rational(-1, 3), "-2/6".to_r
+        assert Rational(0, 1), "x".to_r
+        assert Complex(1, 2), "1+2i".to_c
+        assert Complex(0, 2.5), "2.5i".to_c
+        assert Complex(3, -1), "3-i".to_c
+        assert Complex(7, 0), "7".to_c
         "#;
         assert_script(program);
     }
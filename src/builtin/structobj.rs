@@ -8,7 +8,7 @@ pub fn init() -> Value {
 }
 
 fn struct_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
-    let self_val = self_val.into_module();
+    let mut self_val = self_val.into_module();
     args.check_args_min(1)?;
     let mut i = 0;
 
@@ -26,11 +26,15 @@ fn struct_new(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
                 }
             };
             i = 1;
-            class.set_name(format!("Struct::{}", s))
+            class.set_name(format!("Struct::{}", s));
+            self_val.set_const_by_str(s, class.into());
         }
     };
     class.add_builtin_method_by_str("initialize", initialize);
     class.add_builtin_method_by_str("inspect", inspect);
+    class.add_builtin_method_by_str("to_a", to_a);
+    class.add_builtin_method_by_str("deconstruct", to_a);
+    class.add_builtin_method_by_str("deconstruct_keys", deconstruct_keys);
     class.add_builtin_class_method("[]", builtin::class::new);
     class.add_builtin_class_method("new", builtin::class::new);
 
@@ -113,6 +117,63 @@ fn inspect(vm: &mut VM, self_val: Value, _args: &Args) -> VMResult {
     Ok(Value::string(inspect))
 }
 
+/// Get the names of the members of the struct.
+fn members(self_val: Value) -> Result<Vec<IdentId>, RubyError> {
+    match self_val.get_class().get_var(IdentId::get_id("/members")) {
+        Some(members) => Ok(members
+            .into_array()
+            .elements
+            .iter()
+            .map(|x| x.as_symbol().unwrap())
+            .collect()),
+        None => Err(RubyError::internal("No /members.")),
+    }
+}
+
+fn member_value(self_val: Value, id: IdentId) -> Value {
+    self_val
+        .get_var(IdentId::add_prefix(id, "@"))
+        .unwrap_or_default()
+}
+
+fn to_a(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let ary = members(self_val)?
+        .into_iter()
+        .map(|id| member_value(self_val, id))
+        .collect();
+    Ok(Value::array_from(ary))
+}
+
+/// Struct#deconstruct_keys(keys)
+///
+/// Return a hash of the members in `keys`, or of all members if `keys` is nil.
+fn deconstruct_keys(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(1)?;
+    let members = members(self_val)?;
+    let keys = if args[0].is_nil() {
+        members
+    } else {
+        let keys = match args[0].as_array() {
+            Some(ary) => ary.elements.clone(),
+            None => return Err(RubyError::no_implicit_conv(args[0], "Array")),
+        };
+        let mut ids = vec![];
+        for key in keys {
+            match key.as_symbol() {
+                Some(id) if members.contains(&id) => ids.push(id),
+                _ => break,
+            }
+        }
+        ids
+    };
+    let mut hash = FxIndexMap::default();
+    for id in keys {
+        hash.insert(HashKey(Value::symbol(id)), member_value(self_val, id));
+    }
+    Ok(Value::hash_from_map(hash))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::*;
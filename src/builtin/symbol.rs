@@ -10,6 +10,12 @@ pub fn init() -> Value {
     symbol_class.add_builtin_method_by_str("inspect", inspect);
     symbol_class.add_builtin_method_by_str("<=>", cmp);
     symbol_class.add_builtin_method_by_str("==", eq);
+    symbol_class.add_builtin_method_by_str("length", length);
+    symbol_class.add_builtin_method_by_str("size", length);
+    symbol_class.add_builtin_method_by_str("upcase", upcase);
+    symbol_class.add_builtin_method_by_str("succ", succ);
+    symbol_class.add_builtin_method_by_str("start_with?", start_with);
+    symbol_class.add_builtin_method_by_str("[]", index);
     symbol_class.into()
 }
 
@@ -51,6 +57,44 @@ fn eq(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
     Ok(Value::bool(lhs == rhs))
 }
 
+/// Call String method `method` on the name of `self_val`.
+fn send_to_name(vm: &mut VM, self_val: Value, method: &str, args: &Args) -> VMResult {
+    let name = IdentId::get_name(self_val.as_symbol().unwrap());
+    vm.send(IdentId::get_id(method), Value::string(name), args)
+}
+
+/// Convert the String `val` to a Symbol.
+fn string_to_symbol(val: Value) -> VMResult {
+    match val.as_string() {
+        Some(s) => Ok(Value::symbol(IdentId::get_id(s))),
+        None => Err(RubyError::internal("Expected String.")),
+    }
+}
+
+fn length(_: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    args.check_args_num(0)?;
+    let name = IdentId::get_name(self_val.as_symbol().unwrap());
+    Ok(Value::integer(name.chars().count() as i64))
+}
+
+fn upcase(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let res = send_to_name(vm, self_val, "upcase", args)?;
+    string_to_symbol(res)
+}
+
+fn succ(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    let res = send_to_name(vm, self_val, "succ", args)?;
+    string_to_symbol(res)
+}
+
+fn start_with(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    send_to_name(vm, self_val, "start_with?", args)
+}
+
+fn index(vm: &mut VM, self_val: Value, args: &Args) -> VMResult {
+    send_to_name(vm, self_val, "[]", args)
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::*;
@@ -89,4 +133,42 @@ mod test {
     "##;
         assert_script(program);
     }
+
+    #[test]
+    fn symbol_string_methods() {
+        let program = r##"
+        assert 4, :ruby.length
+        assert 4, :ruby.size
+        assert :RUBY, :ruby.upcase
+        assert :rubz, :ruby.succ
+        assert true, :ruby.start_with?("ru")
+        assert false, :ruby.start_with?("by")
+        assert "u", :ruby[1]
+        assert "ub", :ruby[1, 2]
+    "##;
+        assert_script(program);
+    }
+
+    #[test]
+    fn symbol_to_proc() {
+        let program = r##"
+        assert ["1", "2", "3"], [1, 2, 3].map(&:to_s)
+        assert 6, [1, 2, 3].inject(0, &:+)
+        pr = :upcase.to_proc
+        assert true, pr.lambda?
+        assert "FOO", pr.call("foo")
+        assert [:A, :B], [:a, :b].map(&:upcase)
+        a = []
+        [1, 2].each(&a.method(:push))
+        assert [1, 2], a
+        class Foo
+          def to_proc
+            proc { |x| x * 10 }
+          end
+        end
+        assert [10, 20], [1, 2].map(&Foo.new)
+        assert_error { [1, 2].map(&1) }
+    "##;
+        assert_script(program);
+    }
 }
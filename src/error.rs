@@ -27,6 +27,32 @@ pub struct ErrorInfo {
     pub kind: RubyErrorKind,
     pub info: Vec<(SourceInfoRef, Loc)>,
     level: usize,
+    /// Frames of the call stack where the error was raised.
+    pub backtrace: Vec<Frame>,
+}
+
+/// A frame of the call stack.
+#[derive(Clone, PartialEq)]
+pub struct Frame {
+    pub source_info: SourceInfoRef,
+    pub loc: Loc,
+    /// Label of the method, block or class body, like "foo", "block in foo" or "<main>".
+    pub label: String,
+}
+
+impl Frame {
+    pub fn path(&self) -> String {
+        self.source_info.get_file_name()
+    }
+
+    pub fn line(&self) -> i64 {
+        self.source_info.get_line(&self.loc)
+    }
+
+    /// Return a string like "main.rb:10:in `foo'".
+    pub fn to_s(&self) -> String {
+        format!("{}:{}:in `{}'", self.path(), self.line(), self.label)
+    }
 }
 
 impl std::fmt::Debug for ErrorInfo {
@@ -38,6 +64,7 @@ impl std::fmt::Debug for ErrorInfo {
             RubyErrorKind::ParseErr(kind) => write!(f, "ParseErr: {:?}", kind),
             RubyErrorKind::MethodReturn => write!(f, "MethodReturn"),
             RubyErrorKind::BlockReturn => write!(f, "BlockReturn"),
+            RubyErrorKind::Throw(tag, val) => write!(f, "Throw {:?} {:?}", tag, val),
             RubyErrorKind::Value(val) => write!(f, "{:?}", val),
             RubyErrorKind::Internal(msg) => write!(f, "InternalError {}", msg),
             RubyErrorKind::None(msg) => write!(f, "{}", msg),
@@ -55,6 +82,8 @@ pub enum RubyErrorKind {
     Value(Value),
     MethodReturn,
     BlockReturn,
+    /// Non-local exit by `throw`. (tag, value)
+    Throw(Value, Value),
     Internal(String),
     None(String),
 }
@@ -81,7 +110,14 @@ pub enum RuntimeErrKind {
     Runtime,
     LoadError,
     Range,
+    FloatDomain,
     ZeroDivision,
+    Key,
+    Frozen,
+    IO,
+    NotImplemented,
+    /// SystemCallError with errno.
+    SystemCall(i32),
 }
 
 impl RubyError {
@@ -90,6 +126,7 @@ impl RubyError {
             kind,
             info: vec![],
             level,
+            backtrace: vec![],
         }))
     }
 
@@ -103,6 +140,7 @@ impl RubyError {
             kind,
             info: vec![(source_info, loc)],
             level,
+            backtrace: vec![],
         }))
     }
 
@@ -178,6 +216,7 @@ impl RubyError {
             RubyErrorKind::RuntimeErr { message, .. } => message.to_owned(),
             RubyErrorKind::MethodReturn => "LocalJumpError".to_string(),
             RubyErrorKind::BlockReturn => "LocalJumpError".to_string(),
+            RubyErrorKind::Throw(tag, _) => format!("uncaught throw {:?}", tag),
             RubyErrorKind::Value(val) => val.if_exception().unwrap().message(),
             RubyErrorKind::None(msg) => msg.to_owned(),
             RubyErrorKind::Internal(msg) => {
@@ -187,55 +226,53 @@ impl RubyError {
     }
 
     pub fn to_exception_val(&self) -> Value {
-        match &self.kind {
-            RubyErrorKind::Value(val) => *val,
-            RubyErrorKind::RuntimeErr { kind, .. } => match &kind {
-                RuntimeErrKind::Type => {
-                    let err_class = BuiltinClass::get_toplevel_constant("TypeError")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                RuntimeErrKind::Argument => {
-                    let err_class = BuiltinClass::get_toplevel_constant("ArgumentError")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                RuntimeErrKind::NoMethod => {
-                    let err_class = BuiltinClass::get_toplevel_constant("NoMethodError")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                RuntimeErrKind::Runtime => {
-                    let err_class = BuiltinClass::get_toplevel_constant("RuntimeError")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                RuntimeErrKind::LoadError => {
-                    let err_class = BuiltinClass::get_toplevel_constant("LoadError")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                RuntimeErrKind::StopIteration => {
-                    let err_class = BuiltinClass::get_toplevel_constant("StopIteration")
-                        .unwrap()
-                        .into_module();
-                    Value::exception(err_class, self.clone())
-                }
-                _ => {
-                    let standard = BuiltinClass::standard();
-                    Value::exception(standard, self.clone())
+        let class_name = match &self.kind {
+            RubyErrorKind::Value(val) => return *val,
+            RubyErrorKind::RuntimeErr { kind, .. } => match kind {
+                RuntimeErrKind::Name => "NameError",
+                RuntimeErrKind::NoMethod => "NoMethodError",
+                RuntimeErrKind::Argument => "ArgumentError",
+                RuntimeErrKind::Index => "IndexError",
+                RuntimeErrKind::Type => "TypeError",
+                RuntimeErrKind::Regexp => "RegexpError",
+                RuntimeErrKind::Fiber => "FiberError",
+                RuntimeErrKind::LocalJump => "LocalJumpError",
+                RuntimeErrKind::StopIteration => "StopIteration",
+                RuntimeErrKind::Runtime => "RuntimeError",
+                RuntimeErrKind::LoadError => "LoadError",
+                RuntimeErrKind::Range => "RangeError",
+                RuntimeErrKind::FloatDomain => "FloatDomainError",
+                RuntimeErrKind::ZeroDivision => "ZeroDivisionError",
+                RuntimeErrKind::Key => "KeyError",
+                RuntimeErrKind::Frozen => "FrozenError",
+                RuntimeErrKind::IO => "IOError",
+                RuntimeErrKind::NotImplemented => "NotImplementedError",
+                RuntimeErrKind::SystemCall(errno) => {
+                    let class = builtin::exception::errno_class(*errno);
+                    let val = Value::exception(class, self.clone());
+                    val.set_var_by_str("@errno", Value::integer(*errno as i64));
+                    return val;
                 }
             },
-            _ => {
-                let standard = BuiltinClass::standard();
-                Value::exception(standard, self.clone())
-            }
+            RubyErrorKind::ParseErr(_) => "SyntaxError",
+            RubyErrorKind::MethodReturn | RubyErrorKind::BlockReturn => "LocalJumpError",
+            RubyErrorKind::Throw(..) => "UncaughtThrowError",
+            RubyErrorKind::None(_) => "RuntimeError",
+            RubyErrorKind::Internal(_) => "StandardError",
+        };
+        let err_class = BuiltinClass::get_toplevel_constant(class_name)
+            .unwrap()
+            .into_module();
+        Value::exception(err_class, self.clone())
+    }
+
+    /// Convert `self` to an exception object with instance variables `vars`.
+    fn with_vars(self, vars: &[(&str, Value)]) -> Self {
+        let val = self.to_exception_val();
+        for (name, var) in vars {
+            val.set_var_by_str(name, *var);
         }
+        RubyError::value(val)
     }
 }
 
@@ -274,13 +311,18 @@ impl RubyError {
     }
 
     pub fn undefined_op(method_name: impl Into<String>, rhs: Value, lhs: Value) -> RubyError {
+        let method_name = method_name.into();
         Self::nomethod(format!(
             "no method `{}' {} for {:?}:{}",
-            method_name.into(),
+            method_name,
             rhs.get_class_name(),
             lhs,
             lhs.get_class_name()
         ))
+        .with_vars(&[
+            ("@name", Value::symbol_from_str(&method_name)),
+            ("@receiver", lhs),
+        ])
     }
 
     pub fn undefined_method(method: IdentId, receiver: Value) -> RubyError {
@@ -290,10 +332,14 @@ impl RubyError {
             receiver,
             receiver.get_class_name()
         ))
+        .with_vars(&[("@name", Value::symbol(method)), ("@receiver", receiver)])
     }
 
     pub fn undefined_method_for_class(method: IdentId, class: Module) -> RubyError {
-        Self::nomethod(format!("no method `{:?}' for {}", method, class.name()))
+        Self::nomethod(format!("no method `{:?}' for {}", method, class.name())).with_vars(&[
+            ("@name", Value::symbol(method)),
+            ("@receiver", class.into()),
+        ])
     }
 
     pub fn internal(msg: impl Into<String>) -> RubyError {
@@ -308,6 +354,16 @@ impl RubyError {
         RubyError::new_runtime_err(RuntimeErrKind::Name, msg.into())
     }
 
+    pub fn uninitialized_constant(id: IdentId) -> RubyError {
+        Self::name(format!("Uninitialized constant {:?}.", id))
+            .with_vars(&[("@name", Value::symbol(id))])
+    }
+
+    pub fn uninitialized_class_var(id: IdentId) -> RubyError {
+        Self::name(format!("Uninitialized class variable {:?}.", id))
+            .with_vars(&[("@name", Value::symbol(id))])
+    }
+
     pub fn typeerr(msg: impl Into<String>) -> RubyError {
         RubyError::new_runtime_err(RuntimeErrKind::Type, msg.into())
     }
@@ -366,6 +422,41 @@ impl RubyError {
     pub fn zero_div(msg: impl Into<String>) -> RubyError {
         RubyError::new_runtime_err(RuntimeErrKind::ZeroDivision, msg.into())
     }
+
+    pub fn float_domain(msg: impl Into<String>) -> RubyError {
+        RubyError::new_runtime_err(RuntimeErrKind::FloatDomain, msg.into())
+    }
+
+    /// KeyError for `key` which was not found in `receiver`.
+    pub fn key(msg: impl Into<String>, receiver: Value, key: Value) -> RubyError {
+        RubyError::new_runtime_err(RuntimeErrKind::Key, msg.into())
+            .with_vars(&[("@receiver", receiver), ("@key", key)])
+    }
+
+    pub fn frozen(msg: impl Into<String>) -> RubyError {
+        RubyError::new_runtime_err(RuntimeErrKind::Frozen, msg.into())
+    }
+
+    pub fn io(msg: impl Into<String>) -> RubyError {
+        RubyError::new_runtime_err(RuntimeErrKind::IO, msg.into())
+    }
+
+    pub fn not_implemented(msg: impl Into<String>) -> RubyError {
+        RubyError::new_runtime_err(RuntimeErrKind::NotImplemented, msg.into())
+    }
+
+    /// SystemCallError (Errno::*) for an I/O error of the OS.
+    pub fn errno(err: &std::io::Error, detail: impl std::fmt::Display) -> RubyError {
+        let errno = err.raw_os_error().unwrap_or(0);
+        let description = match builtin::exception::errno_entry(errno) {
+            Some((_, _, description)) => description.to_string(),
+            None => format!("{}", err),
+        };
+        RubyError::new_runtime_err(
+            RuntimeErrKind::SystemCall(errno),
+            format!("{} - {}", description, detail),
+        )
+    }
 }
 
 impl RubyError {
@@ -377,6 +468,10 @@ impl RubyError {
         RubyError::new(RubyErrorKind::BlockReturn, 0)
     }
 
+    pub fn throw(tag: Value, val: Value) -> RubyError {
+        RubyError::new(RubyErrorKind::Throw(tag, val), 0)
+    }
+
     pub fn value(val: Value) -> RubyError {
         RubyError::new(RubyErrorKind::Value(val), 0)
     }
@@ -8,6 +8,8 @@ pub struct Globals {
     // Global info
     pub const_values: ConstantValues,
     global_var: ValueTable,
+    /// Global variables aliased by `alias $new $old` (new name => original name).
+    global_alias: FxHashMap<IdentId, IdentId>,
     //method_cache: MethodCache,
     const_cache: ConstCache,
     pub case_dispatch: CaseDispatchMap,
@@ -20,6 +22,10 @@ pub struct Globals {
     pub const_version: u32,
     pub main_object: Value,
     pub regexp_cache: FxHashMap<String, Rc<Regex>>,
+    /// Procs generated from Symbols given as a block argument (e.g. `map(&:to_s)`).
+    pub sym_procs: FxHashMap<IdentId, Value>,
+    /// Procs registered by `at_exit` or `END { }`.
+    pub at_exit_procs: Vec<Value>,
     source_files: Vec<PathBuf>,
     #[cfg(feature = "perf")]
     pub perf: Perf,
@@ -33,6 +39,8 @@ impl GC for Globals {
         self.const_values.mark(alloc);
         self.main_object.mark(alloc);
         self.global_var.values().for_each(|v| v.mark(alloc));
+        self.sym_procs.values().for_each(|v| v.mark(alloc));
+        self.at_exit_procs.iter().for_each(|v| v.mark(alloc));
         /*self.method_cache
         .cache
         .keys()
@@ -66,6 +74,7 @@ impl Globals {
         let mut globals = Globals {
             const_values: ConstantValues::new(),
             global_var: FxHashMap::default(),
+            global_alias: FxHashMap::default(),
             //method_cache: MethodCache::new(),
             const_cache: ConstCache::new(),
             main_fiber: None,
@@ -76,6 +85,8 @@ impl Globals {
             case_dispatch: CaseDispatchMap::new(),
             case_dispatch2: CaseDispatchMap2::new(),
             regexp_cache: FxHashMap::default(),
+            sym_procs: FxHashMap::default(),
+            at_exit_procs: vec![],
             source_files: vec![],
             #[cfg(feature = "perf")]
             perf: Perf::new(),
@@ -86,8 +97,14 @@ impl Globals {
 
         BUILTINS.with(|m| m.borrow_mut().exception = exception::init());
 
+        main_object
+            .get_singleton_class()
+            .unwrap()
+            .add_builtin_method_by_str("using", module::main_using);
+
         io::init(&mut globals);
         file::init();
+        json::init();
 
         let mut env_map = HashInfo::new(FxIndexMap::default());
         std::env::vars()
@@ -125,16 +142,39 @@ impl Globals {
 
 impl Globals {
     pub fn get_global_var(&self, id: IdentId) -> Option<Value> {
+        let id = self.global_var_origin(id);
         self.global_var.get(&id).cloned()
     }
 
     pub fn set_global_var(&mut self, id: IdentId, val: Value) {
+        let id = self.global_var_origin(id);
         self.global_var.insert(id, val);
     }
 
     pub fn set_global_var_by_str(&mut self, name: &str, val: Value) {
         let id = IdentId::get_id(name);
-        self.global_var.insert(id, val);
+        self.set_global_var(id, val);
+    }
+
+    /// Make the global variable `new` refer to the same storage as `org`.
+    pub fn alias_global_var(&mut self, new: IdentId, org: IdentId) {
+        let org = self.global_var_origin(org);
+        if new == org {
+            return;
+        }
+        self.global_var.remove(&new);
+        self.global_alias
+            .values_mut()
+            .filter(|id| **id == new)
+            .for_each(|id| *id = org);
+        self.global_alias.insert(new, org);
+    }
+
+    fn global_var_origin(&self, id: IdentId) -> IdentId {
+        match self.global_alias.get(&id) {
+            Some(org) => *org,
+            None => id,
+        }
     }
 
     /// Bind `class_object` to the constant `class_name` of the root object.
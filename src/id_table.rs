@@ -77,6 +77,7 @@ impl IdentId {
     pub const _METHOD_MISSING: IdentId = id!(26);
     pub const EACH: IdentId = id!(27);
     pub const MAP: IdentId = id!(28);
+    pub const _UNDEF_METHOD: IdentId = id!(29);
 }
 
 impl IdentId {
@@ -95,6 +96,11 @@ impl IdentId {
         }
     }
 
+    /// Examine if `id` was registered in the identifier table.
+    pub fn is_interned(id: IdentId) -> bool {
+        ID.lock().unwrap().is_interned(id)
+    }
+
     pub fn starts_with(id: IdentId, pat: &str) -> bool {
         ID.lock().unwrap().starts_with(id, pat)
     }
@@ -151,6 +157,7 @@ impl IdentifierTable {
         table.set_ident_id("method_missing", IdentId::_METHOD_MISSING);
         table.set_ident_id("each", IdentId::EACH);
         table.set_ident_id("map", IdentId::MAP);
+        table.set_ident_id("/undef_method", IdentId::_UNDEF_METHOD);
         table
     }
 
@@ -175,6 +182,11 @@ impl IdentifierTable {
         self.table.iter().find(|(_, v)| **v == id.into()).unwrap().0
     }
 
+    fn is_interned(&self, id: IdentId) -> bool {
+        let id: u32 = id.into();
+        self.table.values().any(|v| *v == id)
+    }
+
     fn starts_with<'a>(&'a self, id: IdentId, pat: impl std::str::pattern::Pattern<'a>) -> bool {
         self.get_name(id).starts_with(pat)
     }
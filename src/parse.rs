@@ -2,7 +2,7 @@ mod lexer;
 pub mod node;
 pub mod parser;
 pub mod token;
-pub use lexer::Lexer;
+pub use lexer::{Lexer, WordState};
 pub use node::*;
 pub use token::*;
 pub mod codegen;
@@ -1,5 +1,7 @@
 use crate::error::{ParseErrKind, RubyError};
-use crate::parse::node::{BinOp, FormalParam, Node, NodeKind, ParamKind, UnOp};
+use crate::parse::node::{
+    BinOp, FormalParam, HashPatternRest, Node, NodeKind, ParamKind, Pattern, UnOp,
+};
 use crate::parse::parser::RescueEntry;
 use crate::vm::vm_inst::*;
 use crate::*;
@@ -9,6 +11,8 @@ pub struct Codegen {
     // Codegen State
     method_stack: Vec<MethodId>,
     loop_stack: Vec<LoopInfo>,
+    /// Ensure clauses which enclose the current position.
+    ensure_stack: Vec<Node>,
     context_stack: Vec<Context>,
     extern_context: Option<ContextRef>,
     pub loc: Loc,
@@ -20,6 +24,10 @@ pub struct Codegen {
 struct LoopInfo {
     state: LoopState,
     escape: Vec<EscapeInfo>,
+    /// Destination of `redo` (the start of the loop body or the block body).
+    redo_dest: Option<ISeqPos>,
+    /// Number of ensure clauses which enclose the loop.
+    ensure_depth: usize,
 }
 
 impl LoopInfo {
@@ -27,6 +35,8 @@ impl LoopInfo {
         LoopInfo {
             state: LoopState::Top,
             escape: vec![],
+            redo_dest: None,
+            ensure_depth: 0,
         }
     }
 
@@ -34,6 +44,8 @@ impl LoopInfo {
         LoopInfo {
             state: LoopState::Loop,
             escape: vec![],
+            redo_dest: None,
+            ensure_depth: 0,
         }
     }
 }
@@ -70,6 +82,9 @@ pub struct Context {
     pub iseq_sourcemap: Vec<(ISeqPos, Loc)>,
     /// Unsolved destinations of local jumps.
     jump_dest: Vec<LocalJumpDest>,
+    /// Destinations of `retry` (the start of begin-end bodies) and the numbers of ensure clauses
+    /// which enclose the begin-end.
+    retry_dest: Vec<(ISeqPos, usize)>,
     exception_table: Vec<ExceptionEntry>,
     kind: ContextKind,
 }
@@ -101,7 +116,10 @@ pub struct ExceptionEntry {
     pub start: ISeqPos,
     /// end position in ISeq.
     pub end: ISeqPos,
+    /// start position of the handler (rescue or ensure clauses) in ISeq.
     pub dest: ISeqPos,
+    /// end position of the handler in ISeq.
+    pub dest_end: ISeqPos,
 }
 
 use std::fmt;
@@ -109,24 +127,30 @@ use std::fmt;
 impl fmt::Debug for ExceptionEntry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_fmt(format_args!(
-            "ExceptionEntry ({:?}, {:?}) => {:?}",
-            self.start, self.end, self.dest,
+            "ExceptionEntry ({:?}, {:?}) => ({:?}, {:?})",
+            self.start, self.end, self.dest, self.dest_end,
         ))
     }
 }
 
 impl ExceptionEntry {
-    fn new(start: usize, end: usize, dest: ISeqPos) -> Self {
+    fn new(start: usize, end: usize, dest: ISeqPos, dest_end: ISeqPos) -> Self {
         Self {
             start: ISeqPos::from(start),
             end: ISeqPos::from(end),
             dest,
+            dest_end,
         }
     }
 
     pub fn include(&self, pc: usize) -> bool {
         self.start.into_usize() <= pc && pc < self.end.into_usize()
     }
+
+    /// Check whether `pc` is in the handler.
+    pub fn handler_include(&self, pc: usize) -> bool {
+        self.dest.into_usize() <= pc && pc < self.dest_end.into_usize()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -153,6 +177,7 @@ impl Context {
             lvar_info: FxHashMap::default(),
             iseq_sourcemap: vec![],
             jump_dest: vec![],
+            retry_dest: vec![],
             exception_table: vec![],
             kind: ContextKind::Eval,
         }
@@ -163,6 +188,7 @@ impl Context {
             lvar_info,
             iseq_sourcemap: vec![],
             jump_dest: vec![],
+            retry_dest: vec![],
             exception_table: vec![],
             kind,
         }
@@ -176,6 +202,7 @@ impl Codegen {
             context_stack: vec![Context::new()],
             extern_context: None,
             loop_stack: vec![LoopInfo::new_top()],
+            ensure_stack: vec![],
             loc: Loc(0, 0),
             source_info,
         }
@@ -510,6 +537,12 @@ impl Codegen {
                     self.gen_pop(iseq);
                 };
             }
+            NodeKind::Splat(lhs) => match lhs.kind {
+                // `*` without LHS discards the rest.
+                NodeKind::Nil => self.gen_pop(iseq),
+                _ => self.gen_assign(globals, iseq, *lhs)?,
+            },
+            NodeKind::Mlhs(mlhs) => self.gen_mlhs(globals, iseq, mlhs)?,
             _ => {
                 return Err(
                     self.error_syntax(format!("Unimplemented LHS form. {:#?}", lhs), lhs.loc())
@@ -519,6 +552,23 @@ impl Codegen {
         Ok(())
     }
 
+    /// Expand the value on the stack top and assign each element to `mlhs`.
+    fn gen_mlhs(
+        &mut self,
+        globals: &mut Globals,
+        iseq: &mut ISeq,
+        mlhs: Vec<Node>,
+    ) -> Result<(), RubyError> {
+        let pre = mlhs.iter().take_while(|lhs| !lhs.is_splat()).count();
+        let rest = pre != mlhs.len();
+        let post = if rest { mlhs.len() - pre - 1 } else { 0 };
+        iseq.gen_expand_array(pre, post, rest);
+        for lhs in mlhs {
+            self.gen_assign(globals, iseq, lhs)?;
+        }
+        Ok(())
+    }
+
     fn gen_assign_val(
         &mut self,
         globals: &mut Globals,
@@ -649,11 +699,6 @@ impl Codegen {
         iseq.push32(len as u32);
     }
 
-    fn gen_take(&mut self, iseq: &mut ISeq, len: usize) {
-        iseq.push(Inst::TAKE);
-        iseq.push32(len as u32);
-    }
-
     fn gen_concat(&mut self, iseq: &mut ISeq, len: usize) {
         iseq.push(Inst::CONCAT_STRING);
         iseq.push32(len as u32);
@@ -703,13 +748,16 @@ impl Codegen {
         if !is_block {
             self.method_stack.push(id)
         }
+        if kind != ContextKind::Block {
+            self.loop_stack.push(LoopInfo::new_top());
+        }
         let save_loc = self.loc;
         let mut params = ISeqParams::default();
         let mut iseq = ISeq::new();
 
         self.context_stack
             .push(Context::from(lvar_collector.clone_table(), kind));
-        for (lvar_id, param) in param_list.into_iter().enumerate() {
+        for param in param_list {
             match param.kind {
                 ParamKind::Param(id) => {
                     params.param_ident.push(id);
@@ -733,23 +781,45 @@ impl Codegen {
                 }
                 ParamKind::Keyword(id, default) => {
                     params.param_ident.push(id);
-                    params.keyword.insert(id, lvar_id.into());
-                    if let Some(default) = default {
-                        self.gen_default_expr(globals, &mut iseq, id, *default)?
+                    params.keyword.insert(id, *lvar_collector.get(&id).unwrap());
+                    match default {
+                        Some(default) => self.gen_default_expr(globals, &mut iseq, id, *default)?,
+                        None => params.req_keyword.push(id),
                     }
                 }
                 ParamKind::KWRest(id) => {
                     params.param_ident.push(id);
                     params.kwrest = true;
                 }
+                ParamKind::NoKey => {
+                    params.no_keyword = true;
+                }
                 ParamKind::Block(id) => {
                     params.param_ident.push(id);
                     params.block = true;
                 }
+                ParamKind::Forward => {
+                    // `...` is a set of anonymous rest, keyword rest and block parameters.
+                    params.param_ident.push(ParamKind::anon_rest());
+                    params.rest = Some(true);
+                    params.param_ident.push(ParamKind::anon_kwrest());
+                    params.kwrest = true;
+                    params.param_ident.push(ParamKind::anon_block());
+                    params.block = true;
+                }
             }
         }
 
+        if kind == ContextKind::Block {
+            let ensure_depth = self.ensure_stack.len();
+            let info = self.loop_stack.last_mut().unwrap();
+            info.redo_dest = Some(iseq.current());
+            info.ensure_depth = ensure_depth;
+        }
         self.gen(globals, &mut iseq, node, use_value)?;
+        if kind != ContextKind::Block {
+            self.loop_stack.pop().unwrap();
+        }
         let forvars = match forvars {
             None => vec![],
             Some(forvars) => forvars
@@ -776,6 +846,7 @@ impl Codegen {
                 exception_table,
                 iseq_sourcemap,
                 self.source_info,
+                save_loc,
                 match kind {
                     ContextKind::Block => ISeqKind::Block,
                     ContextKind::Eval => ISeqKind::Other,
@@ -817,6 +888,355 @@ impl Codegen {
         Ok(id)
     }
 
+    /// Generate ensure clauses which enclose the current position from the innermost one,
+    /// until the number of enclosing ensure clauses becomes `depth`.
+    fn gen_ensure_clauses(
+        &mut self,
+        globals: &mut Globals,
+        iseq: &mut ISeq,
+        depth: usize,
+    ) -> Result<(), RubyError> {
+        let ensures = self.ensure_stack.split_off(depth);
+        for ensure in ensures.iter().rev() {
+            self.gen(globals, iseq, ensure.clone(), false)?;
+        }
+        self.ensure_stack.extend(ensures);
+        Ok(())
+    }
+
+    /// Generate code for matching the value on the stack top with `pattern`.
+    ///
+    /// `depth` is the number of stack items (including the value) which belong to the pattern matching.
+    /// The value is consumed if the match succeeded, and the jumps on failure are pushed to `fails`
+    /// with the stack depth at each jump.
+    /// If `single` is true, a missing key in hash patterns raises NoMatchingPatternKeyError.
+    /// Alternatives and find patterns can retry after a missing key, so they are generated
+    /// with `single` = false.
+    fn gen_pattern(
+        &mut self,
+        globals: &mut Globals,
+        iseq: &mut ISeq,
+        pattern: Pattern,
+        fails: &mut Vec<(ISeqPos, usize)>,
+        depth: usize,
+        single: bool,
+    ) -> Result<(), RubyError> {
+        match pattern {
+            Pattern::Value(node) => {
+                // value === obj
+                let loc = node.loc;
+                self.gen(globals, iseq, *node, true)?;
+                self.gen_topn(iseq, 1);
+                self.save_loc(iseq, loc);
+                iseq.push(Inst::TEQ);
+                fails.push((iseq.gen_jmp_if_f(), depth - 1));
+            }
+            Pattern::Var(id) => self.gen_set_local(iseq, id),
+            Pattern::Bind(pattern, id) => {
+                self.gen_dup(iseq, 1);
+                self.gen_pattern(globals, iseq, *pattern, fails, depth + 1, single)?;
+                self.gen_set_local(iseq, id);
+            }
+            Pattern::Alt(mut alts) => {
+                let last = alts.pop().unwrap();
+                let mut success = vec![];
+                for alt in alts {
+                    let mut alt_fails = vec![];
+                    self.gen_dup(iseq, 1);
+                    self.gen_pattern(globals, iseq, alt, &mut alt_fails, depth + 1, false)?;
+                    self.gen_pop(iseq);
+                    success.push(iseq.gen_jmp());
+                    self.gen_pattern_fail(iseq, alt_fails, depth);
+                }
+                self.gen_pattern(globals, iseq, last, fails, depth, false)?;
+                for src in success {
+                    iseq.write_disp_from_cur(src);
+                }
+            }
+            Pattern::Array {
+                const_,
+                pre,
+                rest,
+                post,
+            } => {
+                self.gen_deconstruct(globals, iseq, const_, "deconstruct", fails, depth)?;
+                // Check the size of the array.
+                let len = pre.len() + post.len();
+                self.gen_dup(iseq, 1);
+                self.gen_opt_send(iseq, IdentId::get_id("size"), 0, None, true);
+                iseq.gen_fixnum(len as i64);
+                iseq.push(if rest.is_some() { Inst::GE } else { Inst::EQ });
+                fails.push((iseq.gen_jmp_if_f(), depth));
+                let pre_len = pre.len();
+                let post_len = post.len();
+                for (i, pattern) in pre.into_iter().enumerate() {
+                    self.gen_array_elem(iseq, i as i64);
+                    self.gen_pattern(globals, iseq, pattern, fails, depth + 1, single)?;
+                }
+                if let Some(Some(id)) = rest {
+                    // ary[pre_len..-post_len-1]
+                    self.gen_dup(iseq, 1);
+                    iseq.push(Inst::PUSH_FALSE);
+                    iseq.gen_fixnum(-(post_len as i64) - 1);
+                    iseq.gen_fixnum(pre_len as i64);
+                    iseq.push(Inst::CREATE_RANGE);
+                    self.gen_topn(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::_INDEX, 1, None, true);
+                    self.gen_set_local(iseq, id);
+                }
+                for (i, pattern) in post.into_iter().enumerate() {
+                    self.gen_array_elem(iseq, i as i64 - post_len as i64);
+                    self.gen_pattern(globals, iseq, pattern, fails, depth + 1, single)?;
+                }
+                self.gen_pop(iseq);
+            }
+            Pattern::Find {
+                const_,
+                pre,
+                mid,
+                post,
+            } => {
+                self.gen_deconstruct(globals, iseq, const_, "deconstruct", fails, depth)?;
+                let len = mid.len() as i64;
+                // stack: [ary, i]
+                iseq.gen_fixnum(0);
+                let loop_start = iseq.current();
+                // Check i <= ary.size - len.
+                self.gen_dup(iseq, 2);
+                self.gen_topn(iseq, 1);
+                self.gen_opt_send(iseq, IdentId::get_id("size"), 0, None, true);
+                iseq.gen_fixnum(len);
+                iseq.push(Inst::SUB);
+                iseq.push(Inst::LE);
+                fails.push((iseq.gen_jmp_if_f(), depth + 1));
+                let mut mid_fails = vec![];
+                for (k, pattern) in mid.into_iter().enumerate() {
+                    // ary[i + k]
+                    self.gen_dup(iseq, 2);
+                    iseq.gen_fixnum(k as i64);
+                    iseq.push(Inst::ADD);
+                    self.gen_topn(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::_INDEX, 1, None, true);
+                    self.gen_pattern(globals, iseq, pattern, &mut mid_fails, depth + 2, false)?;
+                }
+                if let Some(id) = pre {
+                    // ary[0, i]
+                    self.gen_dup(iseq, 2);
+                    iseq.gen_fixnum(0);
+                    self.gen_sinkn(iseq, 1);
+                    self.gen_topn(iseq, 2);
+                    self.gen_opt_send(iseq, IdentId::_INDEX, 2, None, true);
+                    self.gen_set_local(iseq, id);
+                }
+                if let Some(id) = post {
+                    // ary.drop(i + len)
+                    self.gen_dup(iseq, 2);
+                    iseq.gen_fixnum(len);
+                    iseq.push(Inst::ADD);
+                    self.gen_topn(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::get_id("drop"), 1, None, true);
+                    self.gen_set_local(iseq, id);
+                }
+                self.gen_pop(iseq);
+                self.gen_pop(iseq);
+                let success = iseq.gen_jmp();
+                // Retry from the next index.
+                self.gen_pattern_fail(iseq, mid_fails, depth + 1);
+                iseq.gen_fixnum(1);
+                iseq.push(Inst::ADD);
+                iseq.gen_jmp_back(loop_start);
+                iseq.write_disp_from_cur(success);
+            }
+            Pattern::Hash {
+                const_,
+                pairs,
+                rest,
+            } => {
+                // Check the class and deconstruct_keys. The argument is an array of the keys,
+                // or nil if all keys are needed for the rest pattern.
+                if let Some(const_) = const_ {
+                    self.gen_dup(iseq, 1);
+                    self.gen_pattern(
+                        globals,
+                        iseq,
+                        Pattern::Value(const_),
+                        fails,
+                        depth + 1,
+                        single,
+                    )?;
+                }
+                self.gen_respond_to(iseq, "deconstruct_keys", fails, depth);
+                match rest {
+                    HashPatternRest::Var(_) => iseq.gen_push_nil(),
+                    _ => {
+                        for (key, _) in &pairs {
+                            iseq.gen_symbol(*key);
+                        }
+                        iseq.gen_create_array(pairs.len());
+                    }
+                }
+                self.gen_topn(iseq, 1);
+                self.gen_opt_send(iseq, IdentId::get_id("deconstruct_keys"), 1, None, true);
+                if pairs.is_empty() && rest == HashPatternRest::Omitted {
+                    // `{}` matches only with an empty hash.
+                    self.gen_dup(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::get_id("empty?"), 0, None, true);
+                    fails.push((iseq.gen_jmp_if_f(), depth));
+                }
+                let keys: Vec<_> = pairs.iter().map(|(key, _)| *key).collect();
+                for (key, pattern) in pairs {
+                    // hash.key?(key)
+                    self.gen_dup(iseq, 1);
+                    iseq.gen_symbol(key);
+                    self.gen_topn(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::get_id("key?"), 1, None, true);
+                    if single {
+                        let src = iseq.gen_jmp_if_t();
+                        self.gen_key_error(globals, iseq, key);
+                        iseq.write_disp_from_cur(src);
+                    } else {
+                        fails.push((iseq.gen_jmp_if_f(), depth));
+                    }
+                    // hash[key]
+                    self.gen_dup(iseq, 1);
+                    iseq.gen_symbol(key);
+                    self.gen_topn(iseq, 1);
+                    self.gen_opt_send(iseq, IdentId::_INDEX, 1, None, true);
+                    match pattern {
+                        Some(pattern) => {
+                            self.gen_pattern(globals, iseq, pattern, fails, depth + 1, single)?
+                        }
+                        None => self.gen_set_local(iseq, key),
+                    }
+                }
+                match rest {
+                    HashPatternRest::Omitted => {}
+                    HashPatternRest::Nil => {
+                        self.gen_dup(iseq, 1);
+                        self.gen_opt_send(iseq, IdentId::get_id("size"), 0, None, true);
+                        iseq.gen_fixnum(keys.len() as i64);
+                        iseq.push(Inst::EQ);
+                        fails.push((iseq.gen_jmp_if_f(), depth));
+                    }
+                    HashPatternRest::Var(id) => {
+                        // rest = hash.dup; rest.delete(key)..
+                        self.gen_dup(iseq, 1);
+                        self.gen_opt_send(iseq, IdentId::get_id("dup"), 0, None, true);
+                        for key in keys {
+                            self.gen_dup(iseq, 1);
+                            iseq.gen_symbol(key);
+                            self.gen_topn(iseq, 1);
+                            self.gen_opt_send(iseq, IdentId::get_id("delete"), 1, None, false);
+                        }
+                        self.gen_set_local(iseq, id);
+                    }
+                }
+                self.gen_pop(iseq);
+            }
+        };
+        Ok(())
+    }
+
+    /// Generate code for pushing `ary[index]`. `ary` is on the stack top.
+    fn gen_array_elem(&mut self, iseq: &mut ISeq, index: i64) {
+        self.gen_dup(iseq, 1);
+        iseq.gen_fixnum(index);
+        self.gen_topn(iseq, 1);
+        self.gen_opt_send(iseq, IdentId::_INDEX, 1, None, true);
+    }
+
+    /// Generate code for checking the class of the value on the stack top with `const_`,
+    /// and converting the value by `method`. (`deconstruct`)
+    fn gen_deconstruct(
+        &mut self,
+        globals: &mut Globals,
+        iseq: &mut ISeq,
+        const_: Option<Box<Node>>,
+        method: &str,
+        fails: &mut Vec<(ISeqPos, usize)>,
+        depth: usize,
+    ) -> Result<(), RubyError> {
+        if let Some(const_) = const_ {
+            self.gen_dup(iseq, 1);
+            self.gen_pattern(
+                globals,
+                iseq,
+                Pattern::Value(const_),
+                fails,
+                depth + 1,
+                false,
+            )?;
+        }
+        self.gen_respond_to(iseq, method, fails, depth);
+        self.gen_opt_send(iseq, IdentId::get_id(method), 0, None, true);
+        Ok(())
+    }
+
+    /// Generate code for checking whether the value on the stack top responds to `method`.
+    fn gen_respond_to(
+        &mut self,
+        iseq: &mut ISeq,
+        method: &str,
+        fails: &mut Vec<(ISeqPos, usize)>,
+        depth: usize,
+    ) {
+        self.gen_dup(iseq, 1);
+        iseq.gen_symbol(IdentId::get_id(method));
+        self.gen_topn(iseq, 1);
+        self.gen_opt_send(iseq, IdentId::get_id("respond_to?"), 1, None, true);
+        fails.push((iseq.gen_jmp_if_f(), depth));
+    }
+
+    /// Generate the destination of the jumps in `fails`,
+    /// where the stack is popped until the stack depth becomes `depth`.
+    fn gen_pattern_fail(
+        &mut self,
+        iseq: &mut ISeq,
+        mut fails: Vec<(ISeqPos, usize)>,
+        depth: usize,
+    ) {
+        fails.sort_by(|a, b| b.1.cmp(&a.1));
+        let mut current = match fails.first() {
+            Some((_, d)) => *d,
+            None => return,
+        };
+        for (src, d) in fails {
+            while current > d {
+                self.gen_pop(iseq);
+                current -= 1;
+            }
+            iseq.write_disp_from_cur(src);
+        }
+        while current > depth {
+            self.gen_pop(iseq);
+            current -= 1;
+        }
+    }
+
+    /// Generate code for raising NoMatchingPatternKeyError for the hash on the stack top.
+    fn gen_key_error(&mut self, globals: &mut Globals, iseq: &mut ISeq, key: IdentId) {
+        // NoMatchingPatternKeyError.new(msg, matchee: hash, key: key)
+        let msg = format!("key not found: :{}", IdentId::get_name(key));
+        iseq.gen_string(globals, &msg);
+        iseq.gen_symbol(IdentId::get_id("matchee"));
+        self.gen_topn(iseq, 2);
+        iseq.gen_symbol(IdentId::get_id("key"));
+        iseq.gen_symbol(key);
+        iseq.gen_create_hash(2);
+        self.gen_get_const(globals, iseq, IdentId::get_id("NoMatchingPatternKeyError"));
+        self.gen_send(iseq, IdentId::NEW, 1, 0, 1, None);
+        self.gen_send_self(iseq, IdentId::get_id("raise"), 1, 0, 0, None);
+    }
+
+    /// Generate code for raising NoMatchingPatternError for the value on the stack top.
+    fn gen_no_matching_pattern(&mut self, globals: &mut Globals, iseq: &mut ISeq) {
+        // raise NoMatchingPatternError, value.inspect
+        self.gen_get_const(globals, iseq, IdentId::get_id("NoMatchingPatternError"));
+        self.gen_topn(iseq, 1);
+        self.gen_opt_send(iseq, IdentId::get_id("inspect"), 0, None, true);
+        self.gen_opt_send_self(iseq, IdentId::get_id("raise"), 2, None, true);
+    }
+
     fn gen_default_expr(
         &mut self,
         globals: &mut Globals,
@@ -928,7 +1348,7 @@ impl Codegen {
                 iseq.gen_complex(globals, r);
             }
             NodeKind::String(s) => {
-                iseq.gen_string(globals, &s);
+                iseq.gen_bytes(globals, &s);
             }
             NodeKind::Symbol(id) => {
                 iseq.gen_symbol(id);
@@ -939,7 +1359,7 @@ impl Codegen {
                     match node.kind {
                         NodeKind::String(s) => {
                             if s.len() != 0 {
-                                iseq.gen_string(globals, &s);
+                                iseq.gen_bytes(globals, &s);
                                 c += 1;
                             }
                         }
@@ -973,7 +1393,7 @@ impl Codegen {
                     for node in nodes {
                         match node.kind {
                             NodeKind::String(s) => {
-                                iseq.gen_string(globals, &s);
+                                iseq.gen_bytes(globals, &s);
                             }
                             NodeKind::CompStmt(nodes) => {
                                 self.gen_comp_stmt(globals, iseq, nodes, true)?;
@@ -1335,6 +1755,10 @@ impl Codegen {
                     self.gen(globals, iseq, *cond, true)?;
                     iseq.gen_jmp_if_t()
                 };
+                let ensure_depth = self.ensure_stack.len();
+                let info = self.loop_stack.last_mut().unwrap();
+                info.redo_dest = Some(iseq.current());
+                info.ensure_depth = ensure_depth;
                 self.gen(globals, iseq, *body, false)?;
                 iseq.gen_jmp_back(loop_start);
                 iseq.write_disp_from_cur(src);
@@ -1365,6 +1789,11 @@ impl Codegen {
             } => {
                 let mut ensure_dest = vec![];
                 let body_start = iseq.len();
+                if let Some(ensure) = &ensure {
+                    self.ensure_stack.push((**ensure).clone());
+                }
+                // `retry` runs only the ensure clauses nested in the rescue clause.
+                let retry_dest = (iseq.current(), self.ensure_stack.len());
                 self.context_mut()
                     .jump_dest
                     .push(LocalJumpDest::new(ensure.is_some()));
@@ -1372,6 +1801,7 @@ impl Codegen {
                 let jump_dest = self.context_mut().jump_dest.pop().unwrap();
                 let body_end = iseq.len();
                 let mut dest = None;
+                let mut dest_end = None;
                 let mut prev = None;
 
                 if !rescue.is_empty() {
@@ -1409,31 +1839,53 @@ impl Codegen {
                             Some(assign) => self.gen_assign(globals, iseq, *assign)?,
                             None => self.gen_pop(iseq),
                         }
+                        self.context_mut().retry_dest.push(retry_dest);
                         self.gen(globals, iseq, *body, use_value)?;
+                        self.context_mut().retry_dest.pop().unwrap();
                         ensure_dest.push(iseq.gen_jmp());
                     }
                     // When no rescue clause were matched
                     if let Some(prev) = prev {
                         iseq.write_disp_from_cur(prev);
                     }
+                    if ensure.is_some() {
+                        self.ensure_stack.pop().unwrap();
+                    }
                     if let Some(box ensure) = ensure.clone() {
                         self.gen(globals, iseq, ensure, false)?;
                     }
                     self.save_loc(iseq, node.loc);
                     iseq.push(Inst::THROW);
+                    dest_end = Some(iseq.current());
                     //self.gen_pop(iseq);
                     //if use_value {
                     //    iseq.gen_push_nil()
                     //};
                     //ensure_dest.push(iseq.gen_jmp());
                     iseq.write_disp_from_cur(else_dest);
+                } else if let Some(box ensure) = ensure.clone() {
+                    self.ensure_stack.pop().unwrap();
+                    // Ensure clause for exception path.
+                    let else_dest = iseq.gen_jmp();
+                    dest = Some(iseq.current());
+                    self.gen(globals, iseq, ensure, false)?;
+                    self.save_loc(iseq, node.loc);
+                    iseq.push(Inst::THROW);
+                    dest_end = Some(iseq.current());
+                    iseq.write_disp_from_cur(else_dest);
                 }
                 // If no exception occured, execute else clause.
                 if let Some(else_) = else_ {
                     if use_value {
                         self.gen_pop(iseq)
                     };
-                    self.gen(globals, iseq, *else_, use_value)?
+                    if let Some(ensure) = &ensure {
+                        self.ensure_stack.push((**ensure).clone());
+                    }
+                    self.gen(globals, iseq, *else_, use_value)?;
+                    if ensure.is_some() {
+                        self.ensure_stack.pop().unwrap();
+                    }
                 };
                 if !jump_dest.is_empty() {
                     // Ensure clause for exception return path.
@@ -1464,10 +1916,10 @@ impl Codegen {
                 for src in ensure_dest {
                     iseq.write_disp_from_cur(src);
                 }
-                if let Some(dest) = dest {
+                if let (Some(dest), Some(dest_end)) = (dest, dest_end) {
                     self.context_mut()
                         .exception_table
-                        .push(ExceptionEntry::new(body_start, body_end, dest));
+                        .push(ExceptionEntry::new(body_start, body_end, dest, dest_end));
                 }
                 // Ensure clause does not return value.
                 if let Some(ensure) = ensure {
@@ -1542,7 +1994,7 @@ impl Codegen {
                                     let k = match &elem.kind {
                                         NodeKind::Integer(i) => Value::integer(*i),
                                         NodeKind::Symbol(sym) => Value::symbol(*sym),
-                                        NodeKind::String(str) => Value::string(str),
+                                        NodeKind::String(str) => Value::bytes(str.clone()),
                                         _ => unreachable!(),
                                     };
                                     map.insert(k, disp);
@@ -1614,9 +2066,84 @@ impl Codegen {
                     }
                 }
             }
+            NodeKind::CaseIn {
+                cond,
+                branches,
+                else_,
+            } => {
+                // A missing key raises NoMatchingPatternKeyError if there is only one pattern.
+                let single = branches.len() == 1 && else_.is_none();
+                let mut end = vec![];
+                self.gen(globals, iseq, *cond, true)?;
+                for branch in branches {
+                    let mut fails = vec![];
+                    self.gen_dup(iseq, 1);
+                    self.gen_pattern(globals, iseq, branch.pattern, &mut fails, 2, single)?;
+                    if let Some(guard) = branch.guard {
+                        let src = self.gen_jmp_if_false(globals, iseq, *guard)?;
+                        fails.push((src, 1));
+                    }
+                    self.gen_pop(iseq);
+                    self.gen(globals, iseq, *branch.body, use_value)?;
+                    end.push(iseq.gen_jmp());
+                    self.gen_pattern_fail(iseq, fails, 1);
+                }
+                match else_ {
+                    Some(else_) => {
+                        self.gen_pop(iseq);
+                        self.gen(globals, iseq, *else_, use_value)?;
+                    }
+                    None => {
+                        self.gen_no_matching_pattern(globals, iseq);
+                        if !use_value {
+                            self.gen_pop(iseq);
+                        }
+                    }
+                }
+                for src in end {
+                    iseq.write_disp_from_cur(src);
+                }
+            }
+            NodeKind::PatternMatch {
+                value,
+                pattern,
+                raise_error,
+            } => {
+                self.gen(globals, iseq, *value, true)?;
+                let mut fails = vec![];
+                if raise_error {
+                    // expr => pattern
+                    self.gen_dup(iseq, 1);
+                    self.gen_pattern(globals, iseq, pattern, &mut fails, 2, true)?;
+                    self.gen_pop(iseq);
+                    if use_value {
+                        iseq.gen_push_nil();
+                    }
+                    let src = iseq.gen_jmp();
+                    self.gen_pattern_fail(iseq, fails, 1);
+                    self.gen_no_matching_pattern(globals, iseq);
+                    if !use_value {
+                        self.gen_pop(iseq);
+                    }
+                    iseq.write_disp_from_cur(src);
+                } else {
+                    // expr in pattern
+                    self.gen_pattern(globals, iseq, pattern, &mut fails, 1, false)?;
+                    iseq.push(Inst::PUSH_TRUE);
+                    let src = iseq.gen_jmp();
+                    self.gen_pattern_fail(iseq, fails, 0);
+                    iseq.push(Inst::PUSH_FALSE);
+                    iseq.write_disp_from_cur(src);
+                    if !use_value {
+                        self.gen_pop(iseq);
+                    }
+                }
+            }
             NodeKind::MulAssign(mut mlhs, mut mrhs) => {
                 let lhs_len = mlhs.len();
-                if lhs_len == 1 && mrhs.len() == 1 {
+                let nested =
+                    |lhs: &Node| matches!(lhs.kind, NodeKind::Splat(_) | NodeKind::Mlhs(_));
+                if lhs_len == 1 && mrhs.len() == 1 && !nested(&mlhs[0]) {
                     match (&mlhs[0].kind, &mrhs[0].kind) {
                         (
                             NodeKind::InstanceVar(id1),
@@ -1669,10 +2196,12 @@ impl Codegen {
                     if use_value {
                         self.gen_dup(iseq, 1);
                     };
-                    self.gen_take(iseq, lhs_len);
-
-                    for lhs in mlhs.into_iter().rev() {
-                        self.gen_assign(globals, iseq, lhs)?;
+                    match mlhs[0].kind {
+                        // A single nested MLHS destructures the value itself. cf: |(a)|
+                        NodeKind::Mlhs(_) if lhs_len == 1 => {
+                            self.gen_assign(globals, iseq, mlhs.remove(0))?
+                        }
+                        _ => self.gen_mlhs(globals, iseq, mlhs)?,
                     }
                 } else {
                     // no splat. mlhs.len != 1
@@ -2011,6 +2540,27 @@ impl Codegen {
                     x.escape.push(EscapeInfo::new(src, EscapeKind::Next));
                 }
             }
+            NodeKind::Redo => {
+                let info = self.loop_stack.last().unwrap();
+                let (dest, ensure_depth) = match info.redo_dest {
+                    Some(dest) => (dest, info.ensure_depth),
+                    None => {
+                        return Err(self.error_syntax("Invalid redo.", node_loc.merge(self.loc)))
+                    }
+                };
+                self.gen_ensure_clauses(globals, iseq, ensure_depth)?;
+                iseq.gen_jmp_back(dest);
+            }
+            NodeKind::Retry => {
+                let (dest, ensure_depth) = match self.context().retry_dest.last() {
+                    Some(dest) => *dest,
+                    None => {
+                        return Err(self.error_syntax("Invalid retry.", node_loc.merge(self.loc)))
+                    }
+                };
+                self.gen_ensure_clauses(globals, iseq, ensure_depth)?;
+                iseq.gen_jmp_back(dest);
+            }
             NodeKind::Proc { params, body, lvar } => {
                 self.loop_stack.push(LoopInfo::new_top());
                 let method = self.gen_iseq(
@@ -2047,6 +2597,16 @@ impl Codegen {
                 self.gen(globals, iseq, *old, true)?;
                 self.gen_opt_send_self(iseq, IdentId::_ALIAS_METHOD, 2, None, use_value);
             }
+            NodeKind::UndefMethod(names) => {
+                let len = names.len();
+                for name in names {
+                    self.gen(globals, iseq, name, true)?;
+                }
+                self.gen_opt_send_self(iseq, IdentId::_UNDEF_METHOD, len, None, use_value);
+            }
+            NodeKind::Mlhs(_) => {
+                return Err(self.error_syntax("Unexpected multiple assignment targets.", node_loc))
+            }
             _ => unreachable!("Codegen: Unimplemented syntax. {:?}", node.kind),
         };
         Ok(())
@@ -2138,7 +2698,7 @@ impl Codegen {
             NodeKind::Float(f) => Ok(Value::float(f)),
             NodeKind::Nil => Ok(Value::nil()),
             NodeKind::Symbol(s) => Ok(Value::symbol(s)),
-            NodeKind::String(s) => Ok(Value::string(s)),
+            NodeKind::String(s) => Ok(Value::bytes(s)),
             NodeKind::Hash(key_value, true) => self.const_hash(globals, key_value),
             NodeKind::Array(nodes, true) => self.const_array(globals, nodes),
             NodeKind::RegExp(nodes, true) => self.const_regexp(globals, nodes, loc),
@@ -2169,7 +2729,7 @@ impl Codegen {
         let mut string = String::new();
         for node in nodes {
             match &node.kind {
-                NodeKind::String(s) => string += s,
+                NodeKind::String(s) => string += &String::from_utf8_lossy(s),
                 _ => unreachable!(),
             }
         }
@@ -2238,6 +2798,105 @@ mod tests {
         assert_error("def foo; next; end");
     }
 
+    #[test]
+    fn codegen_invalid_redo() {
+        assert_error("redo");
+        assert_error("def foo; redo; end");
+        assert_error("while true; def foo; redo; end; end");
+    }
+
+    #[test]
+    fn codegen_invalid_retry() {
+        assert_error("retry");
+        assert_error("begin; retry; rescue; end");
+        assert_error("begin; rescue; [1].each { retry }; end");
+    }
+
+    #[test]
+    fn codegen_retry() {
+        let program = r#"
+        $ensure = 0
+        def foo
+          n = 0
+          begin
+            n += 1
+            raise "error" if n < 3
+            n
+          rescue
+            retry
+          ensure
+            $ensure += 1
+          end
+        end
+        assert 3, foo
+        assert 1, $ensure
+
+        def bar
+          n = 0
+          begin
+            n += 1
+            raise "error" if n < 3
+            n
+          rescue
+            begin
+              retry
+            ensure
+              $ensure += 10
+            end
+          end
+        end
+        $ensure = 0
+        assert 3, bar
+        assert 20, $ensure
+        "#;
+        assert_script(program);
+    }
+
+    #[test]
+    fn codegen_redo() {
+        let program = r#"
+        res = []
+        i = 0
+        redone = false
+        while i < 3
+          res << i
+          i += 1
+          if i == 3 && !redone
+            redone = true
+            redo
+          end
+        end
+        assert [0, 1, 2, 3], res
+
+        res = []
+        redone = false
+        [1, 2, 3].each do |x|
+          res << x
+          if x == 2 && !redone
+            redone = true
+            redo
+          end
+        end
+        assert [1, 2, 2, 3], res
+
+        res = []
+        redone = false
+        [1].each do |x|
+          begin
+            res << :body
+            unless redone
+              redone = true
+              redo
+            end
+          ensure
+            res << :ensure
+          end
+        end
+        assert [:body, :ensure, :body, :ensure], res
+        "#;
+        assert_script(program);
+    }
+
     #[test]
     fn codegen_error1() {
         assert_error(r#"a"#);
@@ -14,7 +14,35 @@ pub struct Lexer {
     reserved: FxHashMap<String, Reserved>,
     reserved_rev: FxHashMap<Reserved, String>,
     pub source_info: SourceInfoRef,
-    state_save: Vec<(u32, u32)>, // (token_start_pos, pos)
+    state_save: Vec<LexerState>,
+    /// The heredocs whose bodies are being read. The last one is the innermost.
+    heredoc: Vec<Heredoc>,
+    /// Stack of (the end of the line which contains the starts of heredocs, the start of the line next to their bodies).
+    /// The last one is the innermost, and is popped when the bodies are skipped.
+    heredoc_skip: Vec<(u32, u32)>,
+    /// The text after `__END__` line.
+    pub data: Option<String>,
+}
+
+/// Saved state of the lexer for lookahead.
+#[derive(Debug, Clone, PartialEq)]
+struct LexerState {
+    token_start_pos: u32,
+    pos: u32,
+    heredoc_len: usize,
+    heredoc_skip: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Heredoc {
+    /// No escapes and interpolations are processed. (<<'ID')
+    raw: bool,
+    /// Width of the indentation removed from each line. (<<~ID)
+    indent: usize,
+    /// Start of the terminator line.
+    end: u32,
+    /// Position where the lexer resumes after the body, which is just after the start of the heredoc.
+    resume: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -32,8 +60,19 @@ pub enum VarKind {
 }
 #[derive(Debug, Clone, PartialEq)]
 enum InterpolateState {
-    Finished(String),
-    NewInterpolation(String, usize), // (string, paren_level)
+    Finished(Vec<u8>),
+    NewInterpolation(Vec<u8>, usize), // (string, paren_level)
+}
+
+/// The state after reading a word of word list literals. (%w, %W, %i, %I)
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordState {
+    /// The word is followed by a whitespace.
+    Separated(Vec<u8>),
+    /// The word is followed by the terminator of the literal.
+    Finished(Vec<u8>),
+    /// The word is followed by an interpolation.
+    NewInterpolation(Vec<u8>),
 }
 
 impl Lexer {
@@ -72,11 +111,14 @@ impl Lexer {
             "next" => Reserved::Next,
             "nil" => Reserved::Nil,
             "or" => Reserved::Or,
+            "redo" => Reserved::Redo,
+            "retry" => Reserved::Retry,
             "return" => Reserved::Return,
             "rescue" => Reserved::Rescue,
             "self" => Reserved::Self_,
             "then" => Reserved::Then,
             "true" => Reserved::True,
+            "undef" => Reserved::Undef,
             "until" => Reserved::Until,
             "unless" => Reserved::Unless,
             "when" => Reserved::When,
@@ -93,6 +135,9 @@ impl Lexer {
             reserved_rev,
             source_info: SourceInfoRef::new(SourceInfo::new(std::path::PathBuf::default())),
             state_save: vec![],
+            heredoc: vec![],
+            heredoc_skip: vec![],
+            data: None,
         }
     }
 
@@ -178,7 +223,7 @@ impl Lexer {
         if let Some(tok) = &self.buf_skip_lt {
             return Ok(tok.clone());
         };
-        let state_save = (self.pos, self.token_start_pos);
+        let state = self.get_state();
         let mut tok;
         loop {
             tok = self.read_token()?;
@@ -186,8 +231,7 @@ impl Lexer {
                 break;
             }
         }
-        self.pos = state_save.0;
-        self.token_start_pos = state_save.1;
+        self.set_state(state);
         self.buf_skip_lt = Some(tok.clone());
         Ok(tok)
     }
@@ -225,14 +269,32 @@ impl Lexer {
         }
     }
 
+    fn get_state(&self) -> LexerState {
+        LexerState {
+            token_start_pos: self.token_start_pos,
+            pos: self.pos,
+            heredoc_len: self.heredoc.len(),
+            heredoc_skip: self.heredoc_skip.clone(),
+        }
+    }
+
+    fn set_state(&mut self, state: LexerState) {
+        self.token_start_pos = state.token_start_pos;
+        self.pos = state.pos;
+        self.heredoc.truncate(state.heredoc_len);
+        self.heredoc_skip = state.heredoc_skip;
+    }
+
     pub fn save_state(&mut self) {
-        self.state_save.push((self.token_start_pos, self.pos));
+        let state = self.get_state();
+        self.state_save.push(state);
     }
 
     pub fn restore_state(&mut self) {
         let state = self.state_save.pop().unwrap();
-        self.token_start_pos = state.0;
-        self.pos = state.1;
+        self.set_state(state);
+        self.buf = None;
+        self.buf_skip_lt = None;
     }
 
     pub fn discard_state(&mut self) {
@@ -255,7 +317,9 @@ impl Lexer {
                 Err(_) => return Ok(self.new_eof(self.pos)),
             };
 
-            if ch.is_ascii_alphabetic() || ch == '_' {
+            if ch == '_' && self.is_end_of_script(pos) {
+                return Ok(self.new_eof(pos));
+            } else if ch.is_ascii_alphabetic() || ch == '_' {
                 return self.read_identifier(ch, VarKind::Identifier);
             } else if ch.is_numeric() {
                 return self.read_number_literal(ch);
@@ -687,30 +751,70 @@ impl Lexer {
         term: char,
         mut level: usize,
     ) -> Result<InterpolateState, RubyError> {
-        let mut s = "".to_string();
+        let mut s = vec![];
         loop {
             match self.get()? {
                 c if open == Some(c) => {
-                    s.push(c);
+                    push_char(&mut s, c);
                     level += 1;
                 }
                 c if c == term => {
                     if level == 0 {
                         return Ok(InterpolateState::Finished(s));
                     } else {
-                        s.push(c);
+                        push_char(&mut s, c);
                         level -= 1;
                     }
                 }
-                '\\' => s.push(self.read_escaped_char()?),
+                '\\' => self.read_escape_sequence(&mut s)?,
+                '#' if self.peek_interpolation() => {
+                    return Ok(InterpolateState::NewInterpolation(s, level))
+                }
+                c => push_char(&mut s, c),
+            }
+        }
+    }
 
-                '#' => match self.peek() {
-                    Some(ch) if ch == '{' || ch == '$' || ch == '@' => {
-                        return Ok(InterpolateState::NewInterpolation(s, level))
+    /// Read a word of word list literals (%w, %W, %i, %I) with `term` as a terminator.
+    ///
+    /// Escape sequences and interpolations are processed only if `interpolate` is true.
+    pub fn read_word(
+        &mut self,
+        term: char,
+        level: &mut usize,
+        interpolate: bool,
+    ) -> Result<WordState, RubyError> {
+        let open = Lexer::open_bracket(term);
+        let mut s = vec![];
+        loop {
+            match self.get()? {
+                c if open == Some(c) => {
+                    push_char(&mut s, c);
+                    *level += 1;
+                }
+                c if c == term => {
+                    if *level == 0 {
+                        return Ok(WordState::Finished(s));
+                    } else {
+                        push_char(&mut s, c);
+                        *level -= 1;
                     }
-                    _ => s.push('#'),
+                }
+                ' ' | '\t' | '\n' | '\r' => return Ok(WordState::Separated(s)),
+                '\\' => match self.peek() {
+                    // Backslash-space does not separate words.
+                    Some(c)
+                        if c.is_ascii_whitespace() || c == '\\' || c == term || open == Some(c) =>
+                    {
+                        push_char(&mut s, self.get()?)
+                    }
+                    _ if interpolate => self.read_escape_sequence(&mut s)?,
+                    _ => s.push(b'\\'),
                 },
-                c => s.push(c),
+                '#' if interpolate && self.peek_interpolation() => {
+                    return Ok(WordState::NewInterpolation(s))
+                }
+                c => push_char(&mut s, c),
             }
         }
     }
@@ -747,6 +851,8 @@ impl Lexer {
                         if escape_backslash {
                             s.push('\\');
                         }
+                    } else if !escape_backslash && (c == term || open == Some(c)) {
+                        s.push(c);
                     } else {
                         s.push('\\');
                         s.push(c);
@@ -762,10 +868,11 @@ impl Lexer {
         let c = self.get()?;
         self.buf = None;
         if c == '\\' {
-            let ch = self.read_escaped_char()?;
-            Ok(self.new_stringlit(ch))
+            let mut s = vec![];
+            self.read_escape_sequence(&mut s)?;
+            Ok(self.new_stringlit(s))
         } else {
-            Ok(self.new_stringlit(c))
+            Ok(self.new_stringlit(c.to_string()))
         }
     }
 
@@ -791,7 +898,7 @@ impl Lexer {
             match self.get()? {
                 '/' => {
                     self.check_postfix(&mut s);
-                    return Ok(InterpolateState::Finished(s));
+                    return Ok(InterpolateState::Finished(s.into_bytes()));
                 }
                 '\\' => {
                     s.push('\\');
@@ -808,12 +915,9 @@ impl Lexer {
                         s.push(ch);
                     }
                 }
-                '#' => match self.peek() {
-                    Some(ch) if ch == '{' || ch == '$' || ch == '@' => {
-                        return Ok(InterpolateState::NewInterpolation(s, 0))
-                    }
-                    _ => s.push('#'),
-                },
+                '#' if self.peek_interpolation() => {
+                    return Ok(InterpolateState::NewInterpolation(s.into_bytes(), 0))
+                }
                 c => s.push(c),
             }
         }
@@ -853,6 +957,10 @@ impl Lexer {
                 Ok(self.new_stringlit(s))
             }
             Some('Q') | None => Ok(self.read_string_literal_double(open, term, 0)?),
+            Some('x') => Ok(self.read_command_literal(open, term, 0)?),
+            Some(kind @ 'w') | Some(kind @ 'W') | Some(kind @ 'i') | Some(kind @ 'I') => {
+                Ok(self.new_open_words(kind, term))
+            }
             Some('r') => {
                 let s = self.read_string_literal_single(open, term, true)?;
                 Ok(self.new_percent('r', s))
@@ -864,6 +972,17 @@ impl Lexer {
         }
     }
 
+    /// Get the opening bracket paired with `term`, if `term` is a closing bracket.
+    pub fn open_bracket(term: char) -> Option<char> {
+        match term {
+            ')' => Some('('),
+            '}' => Some('{'),
+            ']' => Some('['),
+            '>' => Some('<'),
+            _ => None,
+        }
+    }
+
     fn char_to_hex(&self, c: char) -> Result<u32, RubyError> {
         match c {
             ch @ '0'..='9' => Ok(ch as u32 - '0' as u32),
@@ -873,100 +992,179 @@ impl Lexer {
         }
     }
 
-    fn read_escaped_char(&mut self) -> Result<char, RubyError> {
+    /// Read an escape sequence after `\` and push the resulting bytes to `s`.
+    fn read_escape_sequence(&mut self, s: &mut Vec<u8>) -> Result<(), RubyError> {
+        // Backslash-newline joins lines.
+        if self.consume('\n') {
+            return Ok(());
+        }
+        if !self.consume('u') {
+            match self.peek() {
+                Some(c) if !c.is_ascii() => {
+                    self.get()?;
+                    push_char(s, c);
+                }
+                _ => s.push(self.read_escaped_char()?),
+            }
+            return Ok(());
+        }
+        if !self.consume('{') {
+            // \uXXXX
+            let mut code = 0;
+            for _ in 0..4 {
+                let c = self.get()?;
+                code = code * 16 + self.char_to_hex(c)?;
+            }
+            match std::char::from_u32(code) {
+                Some(ch) => push_char(s, ch),
+                None => return Err(self.error_parse("Invalid UTF-8 character.", self.pos - 1)),
+            }
+            return Ok(());
+        }
+        // \u{XXXX XXXX ..}
+        let mut empty = true;
+        loop {
+            while self.consume(' ') || self.consume('\t') {}
+            if self.consume('}') {
+                if empty {
+                    return Err(self.error_parse("Invalid Unicode escape.", self.pos - 1));
+                }
+                return Ok(());
+            }
+            let mut code = 0;
+            let mut len = 0;
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_hexdigit() {
+                    break;
+                }
+                self.get()?;
+                code = code * 16 + self.char_to_hex(c)?;
+                len += 1;
+            }
+            if len == 0 || len > 6 {
+                return Err(self.error_parse("Invalid Unicode escape.", self.pos));
+            }
+            match std::char::from_u32(code) {
+                Some(ch) => push_char(s, ch),
+                None => return Err(self.error_parse("Invalid UTF-8 character.", self.pos - 1)),
+            }
+            empty = false;
+        }
+    }
+
+    /// Read a char after `\M-`, `\C-` or `\c`, which may be an escape sequence.
+    fn read_escape_operand(&mut self) -> Result<u8, RubyError> {
+        match self.get()? {
+            '\\' => self.read_escaped_char(),
+            c if c.is_ascii() => Ok(c as u8),
+            _ => Err(self.error_parse("Invalid escape character syntax.", self.pos - 1)),
+        }
+    }
+
+    /// Read an escape sequence after `\` which gives a single byte.
+    fn read_escaped_char(&mut self) -> Result<u8, RubyError> {
         let ch = match self.get()? {
-            c @ '\'' | c @ '"' | c @ '?' | c @ '\\' => c,
-            'a' => '\x07',
-            'b' => '\x08',
-            'e' => '\x1b',
-            'f' => '\x0c',
-            'n' => '\x0a',
-            'r' => '\x0d',
-            's' => '\x20',
-            't' => '\x09',
-            'v' => '\x0b',
+            c @ '\'' | c @ '"' | c @ '?' | c @ '\\' => c as u8,
+            'a' => 0x07,
+            'b' => 0x08,
+            'e' => 0x1b,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            's' => 0x20,
+            't' => 0x09,
+            'v' => 0x0b,
             'x' => {
-                let c1 = self.get()?;
-                let c1 = self.char_to_hex(c1)?;
-                let c2 = self.get()?;
-                let c2 = self.char_to_hex(c2)?;
-                match std::char::from_u32(c1 * 16 + c2) {
-                    Some(c) => c,
-                    None => return Err(self.error_unexpected(self.pos)),
+                let c = self.get()?;
+                let mut code = self.char_to_hex(c)?;
+                match self.peek() {
+                    Some(c) if c.is_ascii_hexdigit() => {
+                        self.get()?;
+                        code = code * 16 + self.char_to_hex(c)?;
+                    }
+                    _ => {}
                 }
+                code as u8
             }
-            'u' => {
-                let mut code = 0;
-                for _ in 0..4 {
-                    let c = self.get()?;
-                    code = code * 16 + self.char_to_hex(c)?;
+            'M' => {
+                if !self.consume('-') {
+                    return Err(self.error_parse("Invalid escape character syntax.", self.pos));
+                }
+                self.read_escape_operand()? | 0x80
+            }
+            c @ 'C' | c @ 'c' => {
+                if c == 'C' && !self.consume('-') {
+                    return Err(self.error_parse("Invalid escape character syntax.", self.pos));
                 }
-                match std::char::from_u32(code) {
-                    Some(ch) => ch,
-                    None => return Err(self.error_parse("Invalid UTF-8 character.", self.pos - 1)),
+                match self.read_escape_operand()? {
+                    b'?' => 0x7f,
+                    c => c & 0x9f,
                 }
             }
             c if '0' <= c && c <= '7' => {
                 if let Some(num) = self.consume_tri_octal(c) {
-                    num as char
+                    num
                 } else {
-                    c
+                    c as u8
                 }
             }
-            c => c,
+            c if c.is_ascii() => c as u8,
+            _ => return Err(self.error_parse("Invalid escape character syntax.", self.pos - 1)),
         };
         Ok(ch)
     }
 
-    pub fn read_heredocument(&mut self) -> Result<String, RubyError> {
-        #[derive(Clone, PartialEq)]
-        enum Mode {
-            Normal,
-            AllowIndent,
-        }
-        let mut mode = Mode::Normal;
-        let mut delimiter = vec![];
-        match self.peek() {
-            Some(ch) if ch == '-' => {
-                mode = Mode::AllowIndent;
+    /// Read the start of a heredoc after `<<`. (<<ID, <<-ID, <<~ID, <<"ID", <<'ID' or <<`ID`)
+    ///
+    /// The lexer moves to the start of the body, which is read by `read_heredoc_body()`.
+    /// Returns true if the heredoc is a command (<<`ID`).
+    pub fn read_heredocument(&mut self) -> Result<bool, RubyError> {
+        let squiggly = self.consume('~');
+        let allow_indent = squiggly || self.consume('-');
+        let quote = match self.peek() {
+            Some(ch) if ch == '\'' || ch == '"' || ch == '`' => {
                 self.get()?;
+                Some(ch)
             }
-            _ => {}
+            _ => None,
         };
-        loop {
-            match self.peek() {
-                Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => {
-                    self.get()?;
-                    delimiter.push(ch);
+        let mut delimiter = String::new();
+        match quote {
+            Some(quote) => loop {
+                match self.get()? {
+                    ch if ch == quote => break,
+                    '\n' => return Err(self.error_unexpected(self.pos - 1)),
+                    ch => delimiter.push(ch),
                 }
-                _ => break,
-            };
+            },
+            None => loop {
+                match self.peek() {
+                    Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => {
+                        self.get()?;
+                        delimiter.push(ch);
+                    }
+                    _ => break,
+                };
+            },
         }
-        if delimiter.len() == 0 {
+        if delimiter.is_empty() {
             return Err(self.error_unexpected(self.pos));
         }
-        let delimiter: String = delimiter.iter().collect();
-        //eprintln!("delimiter:{}", delimiter);
-        //self.save_state();
-        self.goto_eol();
-        self.get()?;
-        let mut res = String::new();
-        loop {
-            let start = self.pos as usize;
-            self.goto_eol();
-            let end = self.pos as usize;
-            let line: String = self.source_info.code[start..end].iter().collect();
-            //eprintln!("line:[{}]", line);
-            if mode == Mode::AllowIndent {
-                if line.trim_start() == delimiter {
-                    break;
-                }
-            } else {
-                if line == delimiter {
-                    break;
-                }
+        let resume = self.pos;
+        // The body starts at the next line, or just after the bodies of the preceding heredocs in the same line.
+        let eol = self.find_eol(self.pos);
+        let body_start = match self.heredoc_skip.last() {
+            Some((skip_eol, skip_to)) if *skip_eol == eol => {
+                let skip_to = *skip_to;
+                self.heredoc_skip.pop();
+                skip_to
             }
-            if self.get().is_err() {
+            _ => eol + 1,
+        };
+        let mut pos = body_start;
+        let mut indent = None;
+        let (end, next) = loop {
+            if pos as usize >= self.len {
                 return Err(self.error_parse(
                     &format!(
                         r#"Can not find string "{}" anywhere before EOF."#,
@@ -974,14 +1172,101 @@ impl Lexer {
                     ),
                     self.pos,
                 ));
+            }
+            let line_end = self.find_eol(pos);
+            let chars = &self.source_info.code[pos as usize..line_end as usize];
+            let line: String = chars.iter().collect();
+            let found = if allow_indent {
+                line.trim_start() == delimiter
+            } else {
+                line == delimiter
             };
-            res = format!("{}{}\n", res, line);
+            if found {
+                break (pos, std::cmp::min(line_end + 1, self.len as u32));
+            }
+            // Lines which consist of whitespaces are ignored for the indentation.
+            if squiggly && !line.trim().is_empty() {
+                let width = indent_width(chars, usize::max_value()).0;
+                indent = Some(std::cmp::min(indent.unwrap_or(width), width));
+            }
+            pos = line_end + 1;
+        };
+        self.heredoc_skip.push((eol, next));
+        self.heredoc.push(Heredoc {
+            raw: quote == Some('\''),
+            indent: indent.unwrap_or(0),
+            end,
+            resume,
+        });
+        self.pos = body_start;
+        Ok(quote == Some('`'))
+    }
+
+    /// Read the body of the heredoc.
+    ///
+    /// Returns OpenString if an interpolation was found, or StringLit at the end of the body.
+    /// At the end of the body, the lexer moves back to the line which contains the start of the heredoc.
+    pub fn read_heredoc_body(&mut self) -> Result<Token, RubyError> {
+        let heredoc = match self.heredoc.last() {
+            Some(heredoc) => heredoc.clone(),
+            None => return Err(self.error_unexpected(self.pos)),
+        };
+        self.token_start_pos = self.pos;
+        let mut s = vec![];
+        while self.pos < heredoc.end {
+            let pos = self.pos as usize;
+            if heredoc.indent != 0 && self.source_info.code[pos - 1] == '\n' {
+                // Remove the indentation at the start of each line.
+                let len = indent_width(&self.source_info.code[pos..], heredoc.indent).1;
+                self.pos += len as u32;
+            }
+            match self.get()? {
+                '\\' if !heredoc.raw => self.read_escape_sequence(&mut s)?,
+                '#' if !heredoc.raw && self.peek_interpolation() => {
+                    return Ok(self.new_open_string(s, '\n', 0));
+                }
+                '\n' => {
+                    s.push(b'\n');
+                    // Skip the bodies of heredocs which started in an interpolation of this body.
+                    self.skip_heredoc_bodies();
+                }
+                ch => push_char(&mut s, ch),
+            }
         }
-        //self.restore_state();
-        Ok(res)
+        let tok = self.new_stringlit(s);
+        self.pos = heredoc.resume;
+        self.heredoc.pop();
+        Ok(tok)
     }
 }
 
+/// Push `ch` to `s` as UTF-8 bytes.
+fn push_char(s: &mut Vec<u8>, ch: char) {
+    let mut buf = [0; 4];
+    s.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+}
+
+/// Measure the leading whitespaces of `line` up to `max` columns, where a tab advances to the next multiple of 8.
+///
+/// Returns (the width in columns, the number of chars).
+fn indent_width(line: &[char], max: usize) -> (usize, usize) {
+    let mut width = 0;
+    let mut chars = 0;
+    for ch in line {
+        let next = match ch {
+            ' ' => width + 1,
+            '\t' => (width / 8 + 1) * 8,
+            _ => break,
+        };
+        if next > max {
+            break;
+        }
+        width = next;
+        chars += 1;
+    }
+    (width, chars)
+}
+
 // Low level API
 impl Lexer {
     /// Get one char and move to the next.
@@ -996,6 +1281,33 @@ impl Lexer {
         }
     }
 
+    /// Examine if `__END__` line starts at `pos`.
+    /// If so, stop lexing there and keep the rest of the source in `data`.
+    fn is_end_of_script(&mut self, pos: u32) -> bool {
+        let start = pos as usize;
+        let end = start + 7;
+        let code = &self.source_info.code;
+        if start != 0 && code[start - 1] != '\n' || end > self.len {
+            return false;
+        }
+        if code[start..end].iter().collect::<String>() != "__END__" {
+            return false;
+        }
+        let data_start = if end == self.len {
+            end
+        } else if code[end] == '\n' {
+            end + 1
+        } else if code[end] == '\r' && end + 1 < self.len && code[end + 1] == '\n' {
+            end + 2
+        } else {
+            return false;
+        };
+        self.data = Some(code[data_start..self.len].iter().collect());
+        self.len = start;
+        self.pos = pos;
+        true
+    }
+
     /// Push back the last token.
     fn push_back(&mut self) {
         self.pos -= 1;
@@ -1095,6 +1407,19 @@ impl Lexer {
         }
     }
 
+    /// Examine if the chars next to `#` start an interpolation. (#{..}, #@a, #@@a, #$a)
+    fn peek_interpolation(&self) -> bool {
+        let is_var_start = |ch: char| ch.is_alphabetic() || ch == '_';
+        match (self.peek(), self.peek2()) {
+            (Some('{'), _) => true,
+            (Some('@'), Some(ch)) => ch == '@' || is_var_start(ch),
+            (Some('$'), Some(ch)) => {
+                is_var_start(ch) || ch.is_ascii_digit() || "~*$?!@/\\;,.=:<>&`'+".contains(ch)
+            }
+            _ => false,
+        }
+    }
+
     /// Peek the next char.
     /// Returns Some(char) or None if the cursor reached EOF.
     fn peek_digit(&self) -> bool {
@@ -1121,6 +1446,7 @@ impl Lexer {
         loop {
             if self.consume('\n') {
                 res = Some(self.new_line_term());
+                self.skip_heredoc_bodies();
             } else if !self.consume_cont_line() && !self.consume_whitespace() {
                 self.token_start_pos = self.pos;
                 return res;
@@ -1128,6 +1454,24 @@ impl Lexer {
         }
     }
 
+    /// Skip the bodies of heredocs which started in the line just terminated.
+    fn skip_heredoc_bodies(&mut self) {
+        if let Some((eol, skip_to)) = self.heredoc_skip.last() {
+            if self.pos == eol + 1 {
+                self.pos = *skip_to;
+                self.heredoc_skip.pop();
+            }
+        }
+    }
+
+    /// Find the end of the line (the position of '\n' or EOF) from `pos`.
+    fn find_eol(&self, mut pos: u32) -> u32 {
+        while (pos as usize) < self.len && self.source_info.code[pos as usize] != '\n' {
+            pos += 1;
+        }
+        pos
+    }
+
     fn goto_eol(&mut self) {
         loop {
             match self.peek() {
@@ -1180,11 +1524,11 @@ impl Lexer {
         Token::new_imaginarylit(num, self.cur_loc())
     }
 
-    fn new_stringlit(&self, string: impl Into<String>) -> Token {
+    fn new_stringlit(&self, string: impl Into<Vec<u8>>) -> Token {
         Annot::new(TokenKind::StringLit(string.into()), self.cur_loc())
     }
 
-    fn new_commandlit(&self, string: impl Into<String>) -> Token {
+    fn new_commandlit(&self, string: impl Into<Vec<u8>>) -> Token {
         Annot::new(TokenKind::CommandLit(string.into()), self.cur_loc())
     }
 
@@ -1192,18 +1536,22 @@ impl Lexer {
         Annot::new(TokenKind::Punct(punc), self.cur_loc())
     }
 
-    fn new_open_string(&self, s: String, delimiter: char, level: usize) -> Token {
+    fn new_open_string(&self, s: Vec<u8>, delimiter: char, level: usize) -> Token {
         Token::new_open_string(s, delimiter, level, self.cur_loc())
     }
 
-    fn new_open_reg(&self, s: String) -> Token {
+    fn new_open_reg(&self, s: Vec<u8>) -> Token {
         Token::new_open_reg(s, self.cur_loc())
     }
 
-    fn new_open_command(&self, s: String, delimiter: char, level: usize) -> Token {
+    fn new_open_command(&self, s: Vec<u8>, delimiter: char, level: usize) -> Token {
         Token::new_open_command(s, delimiter, level, self.cur_loc())
     }
 
+    fn new_open_words(&self, kind: char, delimiter: char) -> Token {
+        Token::new_open_words(kind, delimiter, self.cur_loc())
+    }
+
     fn new_percent(&self, kind: char, content: String) -> Token {
         Token::new_percent(kind, content, self.cur_loc())
     }
@@ -13,7 +13,7 @@ pub enum NodeKind {
     Float(f64),
     Imaginary(Real),
     Bool(bool),
-    String(String),
+    String(Vec<u8>),
     InterporatedString(Vec<Node>),
     Command(Box<Node>),
     Symbol(IdentId),
@@ -46,6 +46,7 @@ pub enum NodeKind {
     Splat(Box<Node>),
     AssignOp(BinOp, Box<Node>, Box<Node>),
     MulAssign(Vec<Node>, Vec<Node>), // mlhs, mrhs
+    Mlhs(Vec<Node>),                 // nested targets of multiple assignment. cf: a, (b, c) = ...
 
     CompStmt(Vec<Node>),
     If {
@@ -68,6 +69,17 @@ pub enum NodeKind {
         when_: Vec<CaseBranch>,
         else_: Box<Node>,
     },
+    CaseIn {
+        cond: Box<Node>,
+        branches: Vec<InBranch>,
+        else_: Option<Box<Node>>,
+    },
+    /// `expr => pattern` (raise_error: true) or `expr in pattern` (raise_error: false)
+    PatternMatch {
+        value: Box<Node>,
+        pattern: Pattern,
+        raise_error: bool,
+    },
     Begin {
         body: Box<Node>,
         rescue: Vec<RescueEntry>, // (ex_class_list, ex_param)
@@ -81,6 +93,8 @@ pub enum NodeKind {
     },
     Break(Box<Node>),
     Next(Box<Node>),
+    Redo,
+    Retry,
     Return(Box<Node>),
     Yield(ArgList),
     MethodDef(IdentId, Vec<FormalParam>, Box<Node>, LvarCollector), // id, params, body
@@ -113,6 +127,7 @@ pub enum NodeKind {
 
     Defined(Box<Node>),
     AliasMethod(Box<Node>, Box<Node>), // (new_method, old_method)
+    UndefMethod(Vec<Node>),
 }
 
 pub type FormalParam = Annot<ParamKind>;
@@ -126,7 +141,26 @@ pub enum ParamKind {
     RestDiscard,
     Keyword(IdentId, Option<Box<Node>>), // name, default expr
     KWRest(IdentId),
+    NoKey, // `**nil`
     Block(IdentId),
+    Forward, // `...`
+}
+
+impl ParamKind {
+    /// The local variable which holds an anonymous rest parameter (`*`).
+    pub fn anon_rest() -> IdentId {
+        IdentId::get_id("*")
+    }
+
+    /// The local variable which holds an anonymous keyword rest parameter (`**`).
+    pub fn anon_kwrest() -> IdentId {
+        IdentId::get_id("**")
+    }
+
+    /// The local variable which holds an anonymous block parameter (`&`).
+    pub fn anon_block() -> IdentId {
+        IdentId::get_id("&")
+    }
 }
 
 impl FormalParam {
@@ -161,9 +195,17 @@ impl FormalParam {
         FormalParam::new(ParamKind::KWRest(id), loc)
     }
 
+    pub fn no_key(loc: Loc) -> Self {
+        FormalParam::new(ParamKind::NoKey, loc)
+    }
+
     pub fn block(id: IdentId, loc: Loc) -> Self {
         FormalParam::new(ParamKind::Block(id), loc)
     }
+
+    pub fn forward(loc: Loc) -> Self {
+        FormalParam::new(ParamKind::Forward, loc)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -218,6 +260,68 @@ impl CaseBranch {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct InBranch {
+    pub pattern: Pattern,
+    pub guard: Option<Box<Node>>,
+    pub body: Box<Node>,
+}
+
+impl InBranch {
+    pub fn new(pattern: Pattern, guard: Option<Node>, body: Node) -> Self {
+        InBranch {
+            pattern,
+            guard: guard.map(Box::new),
+            body: Box::new(body),
+        }
+    }
+}
+
+/// Pattern for pattern matching.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// Value pattern matched by `value === obj`. (including pinned expressions like `^x`)
+    Value(Box<Node>),
+    /// Variable pattern which binds obj to the local variable.
+    Var(IdentId),
+    /// `pattern => var`
+    Bind(Box<Pattern>, IdentId),
+    /// `pattern | pattern | ..`
+    Alt(Vec<Pattern>),
+    /// `Const(pre.., *rest, post..)` or `[pre.., *rest, post..]`
+    Array {
+        const_: Option<Box<Node>>,
+        pre: Vec<Pattern>,
+        /// None: no rest pattern, Some(None): anonymous rest pattern
+        rest: Option<Option<IdentId>>,
+        post: Vec<Pattern>,
+    },
+    /// `Const(*pre, mid.., *post)` or `[*pre, mid.., *post]`
+    Find {
+        const_: Option<Box<Node>>,
+        pre: Option<IdentId>,
+        mid: Vec<Pattern>,
+        post: Option<IdentId>,
+    },
+    /// `Const(key: pattern, .., **rest)` or `{key: pattern, .., **rest}`
+    Hash {
+        const_: Option<Box<Node>>,
+        /// The pattern is None for `key:`, which binds the value to `key`.
+        pairs: Vec<(IdentId, Option<Pattern>)>,
+        rest: HashPatternRest,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashPatternRest {
+    /// No rest pattern.
+    Omitted,
+    /// `**nil`
+    Nil,
+    /// `**var`
+    Var(IdentId),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BinOp {
     Add,
@@ -324,8 +428,8 @@ impl Node {
         Node::new(NodeKind::Imaginary(num), loc)
     }
 
-    pub fn new_string(s: String, loc: Loc) -> Self {
-        Node::new(NodeKind::String(s), loc)
+    pub fn new_string(s: impl Into<Vec<u8>>, loc: Loc) -> Self {
+        Node::new(NodeKind::String(s.into()), loc)
     }
 
     pub fn new_array(nodes: Vec<Node>, loc: Loc) -> Self {
@@ -382,6 +486,10 @@ impl Node {
         Node::new(NodeKind::AliasMethod(Box::new(new), Box::new(old)), loc)
     }
 
+    pub fn new_undef(names: Vec<Node>, loc: Loc) -> Self {
+        Node::new(NodeKind::UndefMethod(names), loc)
+    }
+
     pub fn new_comp_stmt(nodes: Vec<Node>, mut loc: Loc) -> Self {
         if let Some(node) = nodes.first() {
             loc = node.loc();
@@ -461,7 +569,8 @@ impl Node {
 
     pub fn new_mul_assign(mlhs: Vec<Node>, mrhs: Vec<Node>) -> Self {
         let splat_flag = mrhs.iter().find(|n| n.is_splat()).is_some();
-        let mrhs = if splat_flag || mlhs.len() == 1 && mrhs.len() != 1 {
+        let lhs_splat_flag = mlhs.iter().find(|n| n.is_splat()).is_some();
+        let mrhs = if splat_flag || (mlhs.len() == 1 || lhs_splat_flag) && mrhs.len() != 1 {
             let loc = mrhs[0].loc();
             vec![Node::new_array(mrhs, loc)]
         } else {
@@ -471,6 +580,10 @@ impl Node {
         Node::new(NodeKind::MulAssign(mlhs, mrhs), loc)
     }
 
+    pub fn new_mlhs(mlhs: Vec<Node>, loc: Loc) -> Self {
+        Node::new(NodeKind::Mlhs(mlhs), loc)
+    }
+
     pub fn new_single_assign(lhs: Node, rhs: Node) -> Self {
         let loc = lhs.loc().merge(rhs.loc());
         Node::new(NodeKind::MulAssign(vec![lhs], vec![rhs]), loc)
@@ -593,6 +706,28 @@ impl Node {
         )
     }
 
+    pub fn new_case_in(cond: Node, branches: Vec<InBranch>, else_: Option<Node>, loc: Loc) -> Self {
+        Node::new(
+            NodeKind::CaseIn {
+                cond: Box::new(cond),
+                branches,
+                else_: else_.map(Box::new),
+            },
+            loc,
+        )
+    }
+
+    pub fn new_pattern_match(value: Node, pattern: Pattern, raise_error: bool, loc: Loc) -> Self {
+        Node::new(
+            NodeKind::PatternMatch {
+                value: Box::new(value),
+                pattern,
+                raise_error,
+            },
+            loc,
+        )
+    }
+
     pub fn new_case(cond: Option<Node>, when_: Vec<CaseBranch>, else_: Node, loc: Loc) -> Self {
         let loc = loc.merge(else_.loc());
         Node::new(
@@ -646,6 +781,14 @@ impl Node {
         Node::new(NodeKind::Next(Box::new(val)), loc)
     }
 
+    pub fn new_redo(loc: Loc) -> Self {
+        Node::new(NodeKind::Redo, loc)
+    }
+
+    pub fn new_retry(loc: Loc) -> Self {
+        Node::new(NodeKind::Retry, loc)
+    }
+
     pub fn new_return(val: Node, loc: Loc) -> Self {
         Node::new(NodeKind::Return(Box::new(val)), loc)
     }
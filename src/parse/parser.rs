@@ -16,6 +16,10 @@ pub struct Parser {
     supress_acc_assign: bool,
     /// this flag suppress accesory multiple assignment. e.g. x = 2,3
     supress_mul_assign: bool,
+    /// Bodies of BEGIN blocks, which are hoisted to the start of the program.
+    begin_blocks: Vec<Node>,
+    /// this flag is set when the next compound statement is the toplevel of the program.
+    toplevel: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +27,8 @@ pub struct ParseResult {
     pub node: Node,
     pub lvar_collector: LvarCollector,
     pub source_info: SourceInfoRef,
+    /// The text after `__END__` line.
+    pub data: Option<String>,
 }
 
 impl ParseResult {
@@ -31,6 +37,7 @@ impl ParseResult {
             node,
             lvar_collector,
             source_info,
+            data: None,
         }
     }
 }
@@ -79,6 +86,28 @@ pub struct LvarCollector {
     table: FxHashMap<IdentId, LvarId>,
     kwrest: Option<LvarId>,
     block: Option<LvarId>,
+    implicit: ImplicitParam,
+}
+
+/// Usage state of implicit block parameters (`_1`..`_9` and `it`) in a scope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImplicitParam {
+    /// Not a block. Implicit parameters are not available.
+    NotAllowed,
+    /// The block has explicit parameters.
+    Explicit,
+    /// The block has no explicit parameters, and no implicit parameter is used yet.
+    Unused,
+    /// Numbered parameters are used. Holds the highest number used.
+    Numbered(usize),
+    /// `it` is used.
+    It,
+}
+
+impl Default for ImplicitParam {
+    fn default() -> Self {
+        ImplicitParam::NotAllowed
+    }
 }
 
 impl LvarCollector {
@@ -89,12 +118,13 @@ impl LvarCollector {
             table: FxHashMap::default(),
             kwrest: None,
             block: None,
+            implicit: ImplicitParam::NotAllowed,
         }
     }
 
     /// Check whether `val` exists in `LvarCollector` or not, and return `LvarId` if exists.
     /// If not, add new variable `val` to the `LvarCollector`.
-    fn insert(&mut self, val: IdentId) -> LvarId {
+    pub fn insert(&mut self, val: IdentId) -> LvarId {
         match self.table.get(&val) {
             Some(id) => *id,
             None => {
@@ -135,6 +165,27 @@ impl LvarCollector {
         Some(lvar)
     }
 
+    /// Renumber local variables so that `params` come first in the given order.
+    /// Used to place implicit block parameters at the head of the table.
+    fn set_params_first(&mut self, params: &[IdentId]) {
+        let mut others: Vec<(IdentId, LvarId)> = self
+            .table
+            .iter()
+            .filter(|(id, _)| !params.contains(id))
+            .map(|(id, lvar)| (*id, *lvar))
+            .collect();
+        others.sort_by_key(|(_, lvar)| lvar.0);
+        self.table.clear();
+        let ids = params
+            .iter()
+            .cloned()
+            .chain(others.into_iter().map(|(id, _)| id));
+        for (i, id) in ids.enumerate() {
+            self.table.insert(id, LvarId(i));
+        }
+        self.id = self.table.len();
+    }
+
     pub fn get(&self, val: &IdentId) -> Option<&LvarId> {
         self.table.get(val)
     }
@@ -184,6 +235,8 @@ impl LvarCollector {
 struct ParseContext {
     lvar: LvarCollector,
     kind: ContextKind,
+    /// Destructuring assignments for parenthesized parameters. cf: |k, (x, y)|
+    param_mlhs: Vec<Node>,
 }
 
 impl ParseContext {
@@ -191,28 +244,65 @@ impl ParseContext {
         ParseContext {
             lvar: LvarCollector::new(),
             kind: ContextKind::Method,
+            param_mlhs: vec![],
         }
     }
     fn new_class(lvar_collector: Option<LvarCollector>) -> Self {
         ParseContext {
             lvar: lvar_collector.unwrap_or(LvarCollector::new()),
             kind: ContextKind::Class,
+            param_mlhs: vec![],
         }
     }
     fn new_block() -> Self {
         ParseContext {
             lvar: LvarCollector::new(),
             kind: ContextKind::Block,
+            param_mlhs: vec![],
         }
     }
     fn new_for() -> Self {
         ParseContext {
             lvar: LvarCollector::new(),
             kind: ContextKind::For,
+            param_mlhs: vec![],
         }
     }
 }
 
+/// Return `n` if `name` is a numbered block parameter `_n` (`_1`..`_9`).
+fn numbered_param(name: &str) -> Option<usize> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('_'), Some(c @ '1'..='9'), None) => Some(c as usize - '0' as usize),
+        _ => None,
+    }
+}
+
+/// Check whether `tok` can follow a pattern. (used for `key:` without a pattern and endless ranges)
+fn is_pattern_end(tok: &Token) -> bool {
+    tok.is_term()
+        || matches!(
+            tok.kind,
+            TokenKind::Punct(Punct::Comma)
+                | TokenKind::Punct(Punct::RParen)
+                | TokenKind::Punct(Punct::RBrace)
+                | TokenKind::Punct(Punct::RBracket)
+                | TokenKind::Punct(Punct::BitOr)
+                | TokenKind::Punct(Punct::FatArrow)
+                | TokenKind::Reserved(Reserved::Then)
+                | TokenKind::Reserved(Reserved::If)
+                | TokenKind::Reserved(Reserved::Unless)
+        )
+}
+
+/// An element of array patterns.
+enum PatternItem {
+    Pattern(Pattern),
+    /// `*var` or `*`
+    Rest(Option<IdentId>),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RescueEntry {
     /// The exception classes for this rescue clause.
@@ -254,6 +344,8 @@ impl Parser {
             extern_context: None,
             supress_acc_assign: false,
             supress_mul_assign: false,
+            begin_blocks: vec![],
+            toplevel: false,
         }
     }
 
@@ -353,6 +445,165 @@ impl Parser {
         }
     }
 
+    /// Examine whether the current scope is a method definition.
+    fn is_method_context(&self) -> bool {
+        self.context_stack.last().unwrap().kind == ContextKind::Method
+    }
+
+    /// Examine whether the next token ends an anonymous argument (`*`, `**` or `&`).
+    fn is_anonymous_arg(&mut self) -> bool {
+        self.peek_punct_no_term(Punct::Comma) || self.peek_punct_no_term(Punct::RParen)
+    }
+
+    /// Build a node which refers to the anonymous parameter `id` (`*`, `**` or `&`).
+    /// Return error if no such parameter exists.
+    fn anonymous_arg(&mut self, id: IdentId, loc: Loc) -> Result<Node, RubyError> {
+        if self.is_local_var(id) {
+            Ok(Node::new_lvar(id, loc))
+        } else {
+            Err(self.error_unexpected(loc, format!("No anonymous {:?} parameter.", id)))
+        }
+    }
+
+    /// Consume `...` in an argument list if it is an argument forwarding,
+    /// not a beginless range.
+    fn consume_forwarding_arg(&mut self) -> Result<bool, RubyError> {
+        if self.peek()?.kind != TokenKind::Punct(Punct::Range3) {
+            return Ok(false);
+        }
+        self.save_state();
+        self.get()?;
+        if self.peek_punct_no_term(Punct::RParen) {
+            self.discard_state();
+            Ok(true)
+        } else {
+            self.restore_state();
+            Ok(false)
+        }
+    }
+
+    /// Examine whether the value of `key:` is omitted. (e.g. `{x:, y:}` or `f(x:)`)
+    ///
+    /// `close` is the closing bracket of the hash or the argument list, or None for
+    /// arguments without parentheses. Inside brackets, the value may follow a newline.
+    fn is_shorthand_value_omitted(&mut self, close: Option<Punct>) -> bool {
+        let tok = match close {
+            Some(_) => self.peek(),
+            None => self.peek_no_term(),
+        };
+        match tok {
+            Ok(tok) => {
+                (close.is_none() && (tok.is_line_term() || tok.is_eof()))
+                    || tok.kind == TokenKind::Punct(Punct::Comma)
+                    || close.map_or(false, |close| tok.kind == TokenKind::Punct(close))
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Build the omitted value of `key:` in a hash literal from the key token.
+    fn shorthand_value(&mut self, token: &Token) -> Result<Node, RubyError> {
+        let loc = token.loc();
+        match &token.kind {
+            TokenKind::Ident(name) => {
+                let id = self.get_ident_id(name);
+                if self.is_local_var(id) {
+                    Ok(Node::new_lvar(id, loc))
+                } else {
+                    Ok(Node::new_identifier(name, loc))
+                }
+            }
+            TokenKind::Const(name) => Ok(Node::new_const(name, false, loc)),
+            _ => Err(self.error_unexpected(loc, "Identifier is expected for hash shorthand.")),
+        }
+    }
+
+    /// Examine whether `it` is an ordinary local variable, not an implicit parameter
+    /// of an outer block.
+    fn is_it_var(&self, id: IdentId) -> bool {
+        for c in self.context_stack.iter().rev() {
+            if c.lvar.table.contains_key(&id) && c.lvar.implicit != ImplicitParam::It {
+                return true;
+            }
+            match c.kind {
+                ContextKind::Block | ContextKind::For => {}
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Use the numbered parameter `_n` in the current block.
+    /// Return false if the current scope does not accept implicit parameters.
+    fn use_numbered_param(&mut self, n: usize, id: IdentId, loc: Loc) -> Result<bool, RubyError> {
+        let implicit = self.context_mut().lvar.implicit;
+        let max = match implicit {
+            ImplicitParam::NotAllowed => return Ok(false),
+            ImplicitParam::Explicit => {
+                return Err(self.error_unexpected(loc, "Ordinary parameter is defined."))
+            }
+            ImplicitParam::It => {
+                return Err(self.error_unexpected(
+                    loc,
+                    "Numbered parameter is not allowed when 'it' is already used.",
+                ))
+            }
+            ImplicitParam::Unused => n,
+            ImplicitParam::Numbered(max) => std::cmp::max(max, n),
+        };
+        for c in self.context_stack.iter().rev().skip(1) {
+            if c.kind != ContextKind::Block {
+                break;
+            }
+            if let ImplicitParam::Numbered(_) = c.lvar.implicit {
+                return Err(self
+                    .error_unexpected(loc, "Numbered parameter is already used in outer block."));
+            }
+        }
+        let lvar = &mut self.context_mut().lvar;
+        lvar.implicit = ImplicitParam::Numbered(max);
+        lvar.insert(id);
+        Ok(true)
+    }
+
+    /// Use `it` as the implicit parameter of the current block.
+    /// Return false if the current scope does not accept implicit parameters.
+    fn use_it_param(&mut self, id: IdentId, loc: Loc) -> Result<bool, RubyError> {
+        let implicit = self.context_mut().lvar.implicit;
+        match implicit {
+            ImplicitParam::Unused | ImplicitParam::It => {}
+            ImplicitParam::Numbered(_) => {
+                return Err(self.error_unexpected(
+                    loc,
+                    "'it' is not allowed when a numbered parameter is already used.",
+                ))
+            }
+            _ => return Ok(false),
+        }
+        let lvar = &mut self.context_mut().lvar;
+        lvar.implicit = ImplicitParam::It;
+        lvar.insert(id);
+        Ok(true)
+    }
+
+    /// Build formal parameters for the implicit parameters used in the current block,
+    /// and place them at the head of the local variable table.
+    /// The arity is given by the highest numbered parameter used.
+    fn implicit_params(&mut self, loc: Loc) -> Vec<FormalParam> {
+        let implicit = self.context_mut().lvar.implicit;
+        let ids: Vec<IdentId> = match implicit {
+            ImplicitParam::Numbered(max) => (1..=max)
+                .map(|i| self.get_ident_id(&format!("_{}", i)))
+                .collect(),
+            ImplicitParam::It => vec![self.get_ident_id("it")],
+            _ => return vec![],
+        };
+        self.context_mut().lvar.set_params_first(&ids);
+        ids.into_iter()
+            .map(|id| FormalParam::req_param(id, loc))
+            .collect()
+    }
+
     fn get_ident_id(&self, method: &str) -> IdentId {
         IdentId::get_id(method)
     }
@@ -535,7 +786,7 @@ impl Parser {
             TokenKind::Ident(ident) => ident,
             TokenKind::Const(ident) => ident,
             TokenKind::InstanceVar(ident) => ident,
-            TokenKind::StringLit(ident) => ident,
+            TokenKind::StringLit(ident) => String::from_utf8_lossy(&ident).into_owned(),
             TokenKind::Reserved(reserved) => {
                 self.lexer.get_string_from_reserved(reserved).to_string()
             }
@@ -563,7 +814,8 @@ impl Parser {
 
         let tok = self.peek()?;
         if tok.is_eof() {
-            let result = ParseResult::default(node, lvar, self.lexer.source_info);
+            let mut result = ParseResult::default(node, lvar, self.lexer.source_info);
+            result.data = self.lexer.data.take();
             Ok(result)
         } else {
             Err(self.error_unexpected(tok.loc(), "Expected end-of-input."))
@@ -608,18 +860,27 @@ impl Parser {
                 Some(ctx) => Some(ctx.iseq_ref.unwrap().lvar.clone()),
                 None => None,
             }));
-        let node = self.parse_comp_stmt()?;
+        self.toplevel = true;
+        let mut node = self.parse_comp_stmt()?;
+        if !self.begin_blocks.is_empty() {
+            let mut nodes = std::mem::take(&mut self.begin_blocks);
+            nodes.push(node);
+            node = Node::new_comp_stmt(nodes, self.prev_loc());
+        }
         let lvar = self.context_stack.pop().unwrap().lvar;
         Ok((node, lvar))
     }
 
+    /// Parse `program` for eval. `line` is the line number of the first line of `program`.
     pub fn parse_program_eval(
         mut self,
         path: PathBuf,
         program: &str,
+        line: i64,
         extern_context: Option<ContextRef>,
     ) -> Result<ParseResult, RubyError> {
         self.lexer.init(path, program);
+        self.lexer.source_info.line_offset = line - 1;
         self.extern_context = extern_context;
         self.context_stack.push(ParseContext::new_block());
         let node = self.parse_comp_stmt()?;
@@ -638,6 +899,8 @@ impl Parser {
         self.peek()?;
         let loc = self.loc();
         let mut nodes = vec![];
+        // BEGIN blocks are permitted only as statements of the toplevel.
+        let toplevel = std::mem::replace(&mut self.toplevel, false);
 
         loop {
             if self.peek()?.check_stmt_end() {
@@ -646,6 +909,18 @@ impl Parser {
                 return Ok(node);
             }
 
+            if toplevel && self.consume_reserved(Reserved::BEGIN)? {
+                // BEGIN { COMPSTMT }
+                self.expect_punct(Punct::LBrace)?;
+                let body = self.parse_comp_stmt()?;
+                self.expect_punct(Punct::RBrace)?;
+                self.begin_blocks.push(body);
+                if !self.consume_term()? {
+                    break;
+                }
+                continue;
+            }
+
             let node = self.parse_stmt()?;
             //println!("node {:?}", node);
             nodes.push(node);
@@ -723,6 +998,18 @@ impl Parser {
             } else if self.consume_reserved_no_skip_line_term(Reserved::Or)? {
                 let rhs = self.parse_not()?;
                 node = Node::new_binop(BinOp::LOr, node, rhs);
+            } else if self.consume_punct_no_term(Punct::FatArrow)? {
+                // EXPR => PATTERN
+                let loc = node.loc();
+                let pattern = self.parse_pattern_top()?;
+                let loc = loc.merge(self.prev_loc());
+                node = Node::new_pattern_match(node, pattern, true, loc);
+            } else if self.consume_reserved_no_skip_line_term(Reserved::In)? {
+                // EXPR in PATTERN
+                let loc = node.loc();
+                let pattern = self.parse_pattern_top()?;
+                let loc = loc.merge(self.prev_loc());
+                node = Node::new_pattern_match(node, pattern, false, loc);
             } else {
                 return Ok(node);
             }
@@ -734,6 +1021,16 @@ impl Parser {
         // | UNPARENTHESIZED-METHOD
         // | ! UNPARENTHESIZED-METHOD
         // | not NOT
+        if self.peek_punct_no_term(Punct::Mul) {
+            // EXPR : * LHS [, MLHS-ITEM]* `=' MRHS
+            let node = self.parse_mlhs_item()?;
+            if !self.consume_punct_no_term(Punct::Comma)? && !self.peek_punct_no_term(Punct::Assign)
+            {
+                let loc = self.loc();
+                return Err(self.error_unexpected(loc, "Expected '='."));
+            }
+            return Ok(self.parse_mul_assign(node)?);
+        }
         let node = self.parse_arg()?;
         if self.consume_punct_no_term(Punct::Comma)? {
             // EXPR : MLHS `=' MRHS
@@ -742,35 +1039,81 @@ impl Parser {
         Ok(node)
     }
 
+    /// Parse multiple assignment.
+    /// If MLHS is closed by `)`, return it as a nested MLHS. cf: (a, b), c = ...
     fn parse_mul_assign(&mut self, node: Node) -> Result<Node, RubyError> {
         // EXPR : MLHS `=' MRHS
+        let loc = node.loc();
+        let node = match node.kind {
+            // A parenthesized single target is a nested MLHS. cf: (a), b = ...
+            NodeKind::CompStmt(mut nodes) if nodes.len() == 1 => {
+                Node::new_mlhs(vec![nodes.remove(0)], loc)
+            }
+            _ => node,
+        };
         let mut mlhs = vec![node];
         let old = self.supress_acc_assign;
         self.supress_acc_assign = true;
         loop {
-            if self.peek_punct_no_term(Punct::Assign) {
+            if self.peek_punct_no_term(Punct::Assign) || self.peek_punct_no_term(Punct::RParen) {
                 break;
             }
-            let node = self.parse_method_call()?;
+            let node = self.parse_mlhs_item()?;
             mlhs.push(node);
             if !self.consume_punct_no_term(Punct::Comma)? {
                 break;
             }
         }
         self.supress_acc_assign = old;
+        if self.peek_punct_no_term(Punct::RParen) {
+            let loc = loc.merge(self.prev_loc());
+            return Ok(Node::new_mlhs(mlhs, loc));
+        }
         if !self.consume_punct_no_term(Punct::Assign)? {
             let loc = self.loc();
             return Err(self.error_unexpected(loc, "Expected '='."));
         }
 
         let mrhs = self.parse_mul_assign_rhs_if_allowed()?;
-        for lhs in &mlhs {
-            self.check_lhs(lhs)?;
-        }
+        self.check_mlhs(&mlhs)?;
 
         return Ok(Node::new_mul_assign(mlhs, mrhs));
     }
 
+    /// Parse an item of MLHS.
+    fn parse_mlhs_item(&mut self) -> Result<Node, RubyError> {
+        // MLHS-ITEM : LHS
+        // | * [LHS]
+        // | `(' MLHS-ITEM [, MLHS-ITEM]* `)'
+        let loc = self.loc();
+        if self.consume_punct_no_term(Punct::Mul)? {
+            let next = self.peek_no_term()?.kind;
+            let lhs = if next == TokenKind::Punct(Punct::Comma)
+                || next == TokenKind::Punct(Punct::Assign)
+                || next == TokenKind::Punct(Punct::RParen)
+            {
+                // `*` without LHS discards the rest.
+                Node::new_nil(loc)
+            } else {
+                self.parse_method_call()?
+            };
+            Ok(Node::new_splat(lhs, loc))
+        } else if self.consume_punct_no_term(Punct::LParen)? {
+            let mut mlhs = vec![];
+            loop {
+                mlhs.push(self.parse_mlhs_item()?);
+                if !self.consume_punct(Punct::Comma)? || self.peek_punct_no_term(Punct::RParen) {
+                    break;
+                }
+            }
+            self.expect_punct(Punct::RParen)?;
+            let loc = loc.merge(self.prev_loc());
+            Ok(Node::new_mlhs(mlhs, loc))
+        } else {
+            self.parse_method_call()
+        }
+    }
+
     /// Parse rhs of multiple assignment.
     /// If Parser.mul_assign_rhs is true, only a single assignment is allowed.
     fn parse_mul_assign_rhs_if_allowed(&mut self) -> Result<Vec<Node>, RubyError> {
@@ -874,8 +1217,13 @@ impl Parser {
         }
         if self.consume_punct_no_term(Punct::Assign)? {
             let mrhs = self.parse_mul_assign_rhs(None)?;
-            self.check_lhs(&lhs)?;
-            Ok(Node::new_mul_assign(vec![lhs], mrhs))
+            // (a, b) = ... is the same as a, b = ...
+            let mlhs = match lhs.kind {
+                NodeKind::Mlhs(mlhs) => mlhs,
+                _ => vec![lhs],
+            };
+            self.check_mlhs(&mlhs)?;
+            Ok(Node::new_mul_assign(mlhs, mrhs))
         } else if let Some(op) = self.consume_assign_op_no_term()? {
             // <lhs> <assign_op> <arg>
             self.parse_assign_op(lhs, op)
@@ -911,10 +1259,27 @@ impl Parser {
         }
     }
 
+    /// Check each target of MLHS.
+    fn check_mlhs(&mut self, mlhs: &[Node]) -> Result<(), RubyError> {
+        if let Some(lhs) = mlhs.iter().filter(|lhs| lhs.is_splat()).nth(1) {
+            return Err(self.error_unexpected(lhs.loc(), "Multiple splats in multiple assignment."));
+        }
+        for lhs in mlhs {
+            self.check_lhs(lhs)?;
+        }
+        Ok(())
+    }
+
     /// Check whether `lhs` is a local variable or not.
     fn check_lhs(&mut self, lhs: &Node) -> Result<(), RubyError> {
         if let NodeKind::Ident(id) = lhs.kind {
             self.add_local_var_if_new(id);
+        } else if let NodeKind::Mlhs(mlhs) = &lhs.kind {
+            self.check_mlhs(mlhs)?;
+        } else if let NodeKind::Splat(lhs) = &lhs.kind {
+            if lhs.kind != NodeKind::Nil {
+                self.check_lhs(lhs)?;
+            }
         } else if let NodeKind::Const { .. } = lhs.kind {
             for c in self.context_stack.iter().rev() {
                 match c.kind {
@@ -1268,7 +1633,13 @@ impl Parser {
         //      ｜ 一次式 ［行終端子禁止］ "." メソッド名 括弧付き実引数? ブロック?
         //      ｜ 一次式 ［行終端子禁止］ "::" メソッド名 括弧付き実引数 ブロック?
         //      ｜ 一次式 ［行終端子禁止］ "::" 定数以外のメソッド名 ブロック?
-        let (id, loc) = self.parse_method_name()?;
+        //      ｜ 一次式 ［行終端子禁止］ "." 括弧付き実引数 ブロック?
+        let (id, loc) = if self.peek_punct_no_term(Punct::LParen) {
+            // `receiver.(args)` is a syntax sugar of `receiver.call(args)`.
+            (IdentId::get_id("call"), self.loc())
+        } else {
+            self.parse_method_name()?
+        };
         let mut arglist = if !self.consume_punct_no_term(Punct::LParen)? {
             if self.is_command() {
                 return Ok(Node::new_send(
@@ -1350,14 +1721,39 @@ impl Parser {
             if self.consume_punct(Punct::Mul)? {
                 // splat argument
                 let loc = self.prev_loc();
-                let array = self.parse_arg()?;
+                let array = if self.is_anonymous_arg() {
+                    self.anonymous_arg(ParamKind::anon_rest(), loc)?
+                } else {
+                    self.parse_arg()?
+                };
                 arglist.args.push(Node::new_splat(array, loc));
             } else if self.consume_punct(Punct::DMul)? {
                 // double splat argument
-                arglist.kw_rest.push(self.parse_arg()?);
+                let loc = self.prev_loc();
+                let hash = if self.is_anonymous_arg() {
+                    self.anonymous_arg(ParamKind::anon_kwrest(), loc)?
+                } else {
+                    self.parse_arg()?
+                };
+                arglist.kw_rest.push(hash);
             } else if self.consume_punct(Punct::BitAnd)? {
                 // block argument
-                arglist.block = Some(Box::new(self.parse_arg()?));
+                let loc = self.prev_loc();
+                let block = if self.is_anonymous_arg() {
+                    self.anonymous_arg(ParamKind::anon_block(), loc)?
+                } else {
+                    self.parse_arg()?
+                };
+                arglist.block = Some(Box::new(block));
+            } else if self.consume_forwarding_arg()? {
+                // argument forwarding
+                let loc = self.prev_loc();
+                let rest = self.anonymous_arg(ParamKind::anon_rest(), loc)?;
+                arglist.args.push(Node::new_splat(rest, loc));
+                arglist
+                    .kw_rest
+                    .push(self.anonymous_arg(ParamKind::anon_kwrest(), loc)?);
+                arglist.block = Some(Box::new(self.anonymous_arg(ParamKind::anon_block(), loc)?));
             } else {
                 let node = self.parse_arg()?;
                 let loc = node.loc();
@@ -1386,7 +1782,13 @@ impl Parser {
                     NodeKind::Ident(id, ..) | NodeKind::LocalVar(id) => {
                         if self.consume_punct_no_term(Punct::Colon)? {
                             // keyword args
-                            arglist.kw_args.push((id, self.parse_arg()?));
+                            let value = if self.is_shorthand_value_omitted(punct) {
+                                // `f(x:)` is a shorthand for `f(x: x)`.
+                                node
+                            } else {
+                                self.parse_arg()?
+                            };
+                            arglist.kw_args.push((id, value));
                         } else {
                             // positional args
                             arglist.args.push(node);
@@ -1430,6 +1832,7 @@ impl Parser {
         self.context_stack.push(ParseContext::new_block());
 
         let params = if self.consume_punct(Punct::BitOr)? {
+            self.context_mut().lvar.implicit = ImplicitParam::Explicit;
             if self.consume_punct(Punct::BitOr)? {
                 vec![]
             } else {
@@ -1438,7 +1841,11 @@ impl Parser {
                 params
             }
         } else {
-            self.consume_punct(Punct::LOr)?;
+            self.context_mut().lvar.implicit = if self.consume_punct(Punct::LOr)? {
+                ImplicitParam::Explicit
+            } else {
+                ImplicitParam::Unused
+            };
             vec![]
         };
 
@@ -1448,6 +1855,12 @@ impl Parser {
         } else {
             self.expect_punct(Punct::RBrace)?;
         };
+        let body = self.with_param_mlhs(body);
+        let params = if params.is_empty() {
+            self.implicit_params(loc)
+        } else {
+            params
+        };
         let lvar = self.context_stack.pop().unwrap().lvar;
         let loc = loc.merge(self.prev_loc());
         let node = Node::new_proc(params, body, lvar, loc);
@@ -1472,6 +1885,17 @@ impl Parser {
         }
     }
 
+    fn undef_name(&mut self) -> Result<Node, RubyError> {
+        if self.consume_punct_no_term(Punct::Colon)? {
+            self.parse_symbol()
+        } else {
+            Ok(Node::new_symbol(
+                self.parse_method_def_name()?,
+                self.prev_loc(),
+            ))
+        }
+    }
+
     fn parse_primary(&mut self, suppress_unparen_call: bool) -> Result<Node, RubyError> {
         let tok = self.get()?;
         let loc = tok.loc();
@@ -1482,6 +1906,24 @@ impl Parser {
                     return Ok(self.parse_function_args(node)?);
                 };
                 let id = self.get_ident_id(name);
+                if let Some(n) = numbered_param(name) {
+                    if self.use_numbered_param(n, id, loc)? {
+                        return Ok(Node::new_lvar(id, loc));
+                    }
+                } else if name == "it" && !self.is_it_var(id) {
+                    let is_call = match self.peek_no_term() {
+                        Ok(tok) => match tok.kind {
+                            TokenKind::Punct(Punct::LBrace) | TokenKind::Reserved(Reserved::Do) => {
+                                true
+                            }
+                            _ => self.is_command(),
+                        },
+                        _ => false,
+                    };
+                    if !is_call && self.use_it_param(id, loc)? {
+                        return Ok(Node::new_lvar(id, loc));
+                    }
+                }
                 if self.is_local_var(id) {
                     Ok(Node::new_lvar(id, loc))
                 } else {
@@ -1544,7 +1986,21 @@ impl Parser {
                 Punct::LParen => {
                     let node = self.parse_comp_stmt()?;
                     self.expect_punct(Punct::RParen)?;
-                    Ok(node)
+                    match node.kind {
+                        NodeKind::CompStmt(mut nodes)
+                            if nodes.len() == 1 && matches!(nodes[0].kind, NodeKind::Mlhs(_)) =>
+                        {
+                            // Nested MLHS. cf: (a, b), c = ...
+                            if !self.peek_punct_no_term(Punct::Comma)
+                                && !self.peek_punct_no_term(Punct::Assign)
+                            {
+                                let loc = self.loc();
+                                return Err(self.error_unexpected(loc, "Expected '='."));
+                            }
+                            Ok(nodes.remove(0))
+                        }
+                        _ => Ok(node),
+                    }
                 }
                 Punct::LBracket => {
                     // Array literal
@@ -1558,6 +2014,7 @@ impl Parser {
                     // Lambda literal
                     let mut params = vec![];
                     self.context_stack.push(ParseContext::new_block());
+                    self.context_mut().lvar.implicit = ImplicitParam::Explicit;
                     if self.consume_punct(Punct::LParen)? {
                         if !self.consume_punct(Punct::RParen)? {
                             loop {
@@ -1574,6 +2031,8 @@ impl Parser {
                         let id = self.expect_ident()?;
                         self.new_param(id, self.prev_loc())?;
                         params.push(FormalParam::req_param(id, self.prev_loc()));
+                    } else {
+                        self.context_mut().lvar.implicit = ImplicitParam::Unused;
                     };
                     let body = if self.consume_punct(Punct::LBrace)? {
                         let body = self.parse_comp_stmt()?;
@@ -1591,6 +2050,11 @@ impl Parser {
                             format!("Expected 'do' or '{{'. Actual:{:?}", tok.kind),
                         ));
                     };
+                    let params = if params.is_empty() {
+                        self.implicit_params(loc)
+                    } else {
+                        params
+                    };
                     let lvar = self.context_stack.pop().unwrap().lvar;
                     Ok(Node::new_proc(params, body, lvar, loc))
                 }
@@ -1607,9 +2071,7 @@ impl Parser {
                             self.error_unexpected(loc, r#"Expected '-', '~', '"', '`', or '\''."#)
                         );
                     }
-                    let string = self.lexer.read_heredocument()?;
-                    let node = Node::new_string(string, loc);
-                    Ok(node)
+                    self.parse_heredocument(loc)
                 }
                 _ => {
                     return Err(
@@ -1702,6 +2164,10 @@ impl Parser {
                             None
                         };
                         self.consume_term()?;
+                        if cond.is_some() && self.peek()?.kind == TokenKind::Reserved(Reserved::In)
+                        {
+                            return self.parse_case_in(cond.unwrap(), loc);
+                        }
                         let mut when_ = vec![];
                         while self.consume_reserved(Reserved::When)? {
                             let arg = self.parse_mul_assign_rhs(None)?;
@@ -1813,6 +2279,8 @@ impl Parser {
                             Ok(Node::new_next(val, loc))
                         }
                     }
+                    Reserved::Redo => Ok(Node::new_redo(loc)),
+                    Reserved::Retry => Ok(Node::new_retry(loc)),
                     Reserved::True => Ok(Node::new_bool(true, loc)),
                     Reserved::False => Ok(Node::new_bool(false, loc)),
                     Reserved::Nil => Ok(Node::new_nil(loc)),
@@ -1828,12 +2296,39 @@ impl Parser {
                             Err(self.error_unexpected(tok.loc, format!("expected '('.")))
                         }
                     }
+                    Reserved::BEGIN => {
+                        Err(self.error_unexpected(loc, "BEGIN is permitted only at toplevel."))
+                    }
+                    Reserved::END => {
+                        // END { COMPSTMT } is registered in the same way as at_exit.
+                        if !self.peek_punct_no_term(Punct::LBrace) {
+                            let loc = self.loc();
+                            return Err(self.error_unexpected(loc, "Expected '{'."));
+                        }
+                        let block = self.parse_block()?.unwrap();
+                        let loc = loc.merge(self.prev_loc());
+                        Ok(Node::new_send(
+                            Node::new_self(loc),
+                            IdentId::get_id("at_exit"),
+                            ArgList::with_block(block),
+                            false,
+                            loc,
+                        ))
+                    }
                     Reserved::Alias => {
                         let new_name = self.alias_name()?;
                         let old_name = self.alias_name()?;
                         let loc = loc.merge(self.prev_loc());
                         Ok(Node::new_alias(new_name, old_name, loc))
                     }
+                    Reserved::Undef => {
+                        let mut names = vec![self.undef_name()?];
+                        while self.consume_punct_no_term(Punct::Comma)? {
+                            names.push(self.undef_name()?);
+                        }
+                        let loc = loc.merge(self.prev_loc());
+                        Ok(Node::new_undef(names, loc))
+                    }
                     _ => {
                         Err(self.error_unexpected(loc, format!("Unexpected token: {:?}", tok.kind)))
                     }
@@ -1898,6 +2393,8 @@ impl Parser {
             Punct::Plus => Ok(IdentId::_ADD),
             Punct::Minus => Ok(IdentId::_SUB),
             Punct::Mul => Ok(IdentId::_MUL),
+            Punct::Div => Ok(IdentId::_DIV),
+            Punct::Rem => Ok(IdentId::_REM),
             Punct::Shl => Ok(IdentId::_SHL),
             Punct::Shr => Ok(IdentId::_SHR),
             Punct::Cmp => Ok(IdentId::_CMP),
@@ -1927,12 +2424,12 @@ impl Parser {
 
     /// Parse string literals.
     /// Adjacent string literals are to be combined.
-    fn parse_string_literal(&mut self, s: &str) -> Result<Node, RubyError> {
+    fn parse_string_literal(&mut self, s: &[u8]) -> Result<Node, RubyError> {
         let loc = self.prev_loc();
-        let mut s = s.to_string();
+        let mut s = s.to_vec();
         while let TokenKind::StringLit(next_s) = self.peek_no_term()?.kind {
             self.get()?;
-            s = format!("{}{}", s, next_s);
+            s.extend(next_s);
         }
         Ok(Node::new_string(s, loc))
     }
@@ -1960,6 +2457,7 @@ impl Parser {
             let node = match &tok.kind {
                 TokenKind::GlobalVar(s) => Node::new_global_var(s, loc),
                 TokenKind::InstanceVar(s) => Node::new_instance_var(s, loc),
+                TokenKind::ClassVar(s) => Node::new_class_var(s, loc),
                 _ => unreachable!(format!("{:?}", tok)),
             };
             nodes.push(node);
@@ -1969,17 +2467,18 @@ impl Parser {
 
     fn parse_interporated_string_literal(
         &mut self,
-        s: &str,
+        s: &[u8],
         delimiter: char,
         level: usize,
     ) -> Result<Node, RubyError> {
         let start_loc = self.prev_loc();
-        let mut nodes = vec![Node::new_string(s.to_string(), start_loc)];
+        let mut nodes = vec![Node::new_string(s.to_vec(), start_loc)];
         loop {
             self.parse_template(&mut nodes)?;
+            let open = Lexer::open_bracket(delimiter);
             let tok = self
                 .lexer
-                .read_string_literal_double(None, delimiter, level)?;
+                .read_string_literal_double(open, delimiter, level)?;
             let loc = tok.loc();
             match &tok.kind {
                 TokenKind::StringLit(s) => {
@@ -1994,6 +2493,34 @@ impl Parser {
         }
     }
 
+    /// Parse heredoc after `<<`.
+    fn parse_heredocument(&mut self, loc: Loc) -> Result<Node, RubyError> {
+        let command = self.lexer.read_heredocument()?;
+        let mut nodes = vec![];
+        let node = loop {
+            let tok = self.lexer.read_heredoc_body()?;
+            match tok.kind {
+                TokenKind::StringLit(s) => {
+                    if nodes.is_empty() {
+                        break Node::new_string(s, loc);
+                    }
+                    nodes.push(Node::new_string(s, tok.loc));
+                    break Node::new_interporated_string(nodes, loc);
+                }
+                TokenKind::OpenString(s, _, _) => {
+                    nodes.push(Node::new_string(s, tok.loc));
+                    self.parse_template(&mut nodes)?;
+                }
+                _ => unreachable!(),
+            }
+        };
+        if command {
+            Ok(Node::new_command(node))
+        } else {
+            Ok(node)
+        }
+    }
+
     fn parse_regexp(&mut self) -> Result<Node, RubyError> {
         let start_loc = self.prev_loc();
         let tok = self.lexer.get_regexp()?;
@@ -2029,38 +2556,74 @@ impl Parser {
         let loc = tok.loc;
         if let TokenKind::PercentNotation(kind, content) = tok.kind {
             match kind {
-                // TODO: backslash-space must be valid in %w and %i.
-                // e.g. "foo\ bar" => "foo bar"
-                'w' => {
-                    let ary = content
-                        .split(|c| c == ' ' || c == '\n')
-                        .map(|x| Node::new_string(x.to_string(), loc))
-                        .collect();
-                    Ok(Node::new_array(ary, tok.loc))
-                }
-                'i' => {
-                    let ary = content
-                        .split(|c| c == ' ' || c == '\n')
-                        .map(|x| Node::new_symbol(IdentId::get_id(x), loc))
-                        .collect();
-                    Ok(Node::new_array(ary, tok.loc))
-                }
+                's' => Ok(Node::new_symbol(IdentId::get_id(content), loc)),
                 'r' => {
                     let ary = vec![Node::new_string(content + "-", loc)];
                     Ok(Node::new_regexp(ary, tok.loc))
                 }
                 _ => return Err(self.error_unexpected(loc, "Unsupported % notation.")),
             }
+        } else if let TokenKind::OpenWords(kind, term) = tok.kind {
+            self.parse_word_list(kind, term, loc)
         } else if let TokenKind::StringLit(s) = tok.kind {
             return Ok(Node::new_string(s, loc));
         } else if let TokenKind::OpenString(s, term, level) = tok.kind {
             let node = self.parse_interporated_string_literal(&s, term, level)?;
             return Ok(node);
+        } else if let TokenKind::CommandLit(s) = tok.kind {
+            let content = Node::new_string(s, loc);
+            return Ok(Node::new_command(content));
+        } else if let TokenKind::OpenCommand(s, term, level) = tok.kind {
+            let content = self.parse_interporated_string_literal(&s, term, level)?;
+            return Ok(Node::new_command(content));
         } else {
             unreachable!(format!("parse_percent_notation(): {:?}", tok.kind));
         }
     }
 
+    /// Parse word list literals. (%w, %W, %i, %I)
+    fn parse_word_list(&mut self, kind: char, term: char, loc: Loc) -> Result<Node, RubyError> {
+        let interpolate = kind == 'W' || kind == 'I';
+        let mut level = 0;
+        let mut words = vec![];
+        // Parts of the current word.
+        let mut nodes = vec![];
+        loop {
+            let (s, finished) = match self.lexer.read_word(term, &mut level, interpolate)? {
+                WordState::NewInterpolation(s) => {
+                    nodes.push(Node::new_string(s, loc));
+                    self.parse_template(&mut nodes)?;
+                    continue;
+                }
+                WordState::Separated(s) => (s, false),
+                WordState::Finished(s) => (s, true),
+            };
+            if !s.is_empty() || !nodes.is_empty() {
+                let is_symbol = kind == 'i' || kind == 'I';
+                let word = if nodes.is_empty() {
+                    if is_symbol {
+                        Node::new_symbol(IdentId::get_id(String::from_utf8_lossy(&s)), loc)
+                    } else {
+                        Node::new_string(s, loc)
+                    }
+                } else {
+                    nodes.push(Node::new_string(s, loc));
+                    let word = Node::new_interporated_string(std::mem::take(&mut nodes), loc);
+                    if is_symbol {
+                        let method = self.get_ident_id("to_sym");
+                        Node::new_send_noarg(word, method, false, loc)
+                    } else {
+                        word
+                    }
+                };
+                words.push(word);
+            }
+            if finished {
+                return Ok(Node::new_array(words, loc));
+            }
+        }
+    }
+
     fn parse_hash_literal(&mut self) -> Result<Node, RubyError> {
         let mut kvp = vec![];
         let loc = self.prev_loc();
@@ -2069,7 +2632,7 @@ impl Parser {
                 return Ok(Node::new_hash(kvp, loc.merge(self.prev_loc())));
             };
             let ident_loc = self.loc();
-            let mut symbol_flag = false;
+            let mut symbol_token = None;
             let key = if self.peek()?.can_be_symbol() {
                 self.save_state();
                 let token = self.get()?.clone();
@@ -2077,7 +2640,7 @@ impl Parser {
                 if self.consume_punct(Punct::Colon)? {
                     self.discard_state();
                     let id = self.get_ident_id(&ident);
-                    symbol_flag = true;
+                    symbol_token = Some(token);
                     Node::new_symbol(id, ident_loc)
                 } else {
                     self.restore_state();
@@ -2086,10 +2649,17 @@ impl Parser {
             } else {
                 self.parse_arg()?
             };
-            if !symbol_flag {
-                self.expect_punct(Punct::FatArrow)?
+            let value = match symbol_token {
+                Some(token) if self.is_shorthand_value_omitted(Some(Punct::RBrace)) => {
+                    // `{x:, y:}` is a shorthand for `{x: x, y: y}`.
+                    self.shorthand_value(&token)?
+                }
+                Some(_) => self.parse_arg()?,
+                None => {
+                    self.expect_punct(Punct::FatArrow)?;
+                    self.parse_arg()?
+                }
             };
-            let value = self.parse_arg()?;
             kvp.push((key, value));
             if !self.consume_punct(Punct::Comma)? {
                 break;
@@ -2279,7 +2849,14 @@ impl Parser {
 
         self.context_stack.push(ParseContext::new_method());
         let args = self.parse_def_params()?;
-        let body = self.parse_begin()?;
+        let body = if self.consume_punct_no_term(Punct::Assign)? {
+            // Endless method definition
+            // def FNAME ARGS = ARG
+            self.parse_arg()?
+        } else {
+            self.parse_begin()?
+        };
+        let body = self.with_param_mlhs(body);
         let lvar = self.context_stack.pop().unwrap().lvar;
         #[cfg(feature = "verbose")]
         {
@@ -2315,16 +2892,34 @@ impl Parser {
         }
 
         let mut args = vec![];
+        let mut destructs = vec![];
         let mut state = Kind::Reqired;
         loop {
             let mut loc = self.loc();
             if self.consume_punct(Punct::BitAnd)? {
                 // Block param
-                let id = self.expect_ident()?;
+                let id = match self.consume_ident()? {
+                    Some(id) => id,
+                    None if self.is_method_context() => ParamKind::anon_block(),
+                    None => self.expect_ident()?,
+                };
                 loc = loc.merge(self.prev_loc());
                 args.push(FormalParam::block(id, loc));
                 self.new_block_param(id, loc)?;
                 break;
+            } else if terminator == TokenKind::Punct(Punct::RParen)
+                && self.consume_punct(Punct::Range3)?
+            {
+                // Argument forwarding param
+                loc = loc.merge(self.prev_loc());
+                if state >= Kind::Rest || !self.is_method_context() {
+                    return Err(self.error_unexpected(loc, "Unexpected '...'."));
+                }
+                args.push(FormalParam::forward(loc));
+                self.new_param(ParamKind::anon_rest(), loc)?;
+                self.new_kwrest_param(ParamKind::anon_kwrest(), loc)?;
+                self.new_block_param(ParamKind::anon_block(), loc)?;
+                break;
             } else if self.consume_punct(Punct::Mul)? {
                 // Splat(Rest) param
                 loc = loc.merge(self.prev_loc());
@@ -2339,11 +2934,56 @@ impl Parser {
                         args.push(FormalParam::rest(id, loc));
                         self.new_param(id, self.prev_loc())?;
                     }
+                    None if self.is_method_context() => {
+                        let id = ParamKind::anon_rest();
+                        args.push(FormalParam::rest(id, loc));
+                        self.new_param(id, loc)?;
+                    }
                     None => args.push(FormalParam::rest_discard(loc)),
                 }
+            } else if self.consume_punct(Punct::LParen)? {
+                // Destructuring param
+                let mlhs = self.parse_param_mlhs()?;
+                loc = loc.merge(self.prev_loc());
+                let id = self.get_ident_id(&format!("({})", args.len()));
+                match state {
+                    Kind::Reqired => args.push(FormalParam::req_param(id, loc)),
+                    Kind::PostReq | Kind::Optional | Kind::Rest => {
+                        args.push(FormalParam::post(id, loc));
+                        state = Kind::PostReq;
+                    }
+                    _ => {
+                        return Err(self.error_unexpected(
+                            loc,
+                            "Required parameter is not allowed in ths position.",
+                        ))
+                    }
+                }
+                self.new_param(id, loc)?;
+                destructs.push((mlhs, Node::new_lvar(id, loc)));
             } else if self.consume_punct(Punct::DMul)? {
                 // Keyword rest param
-                let id = self.expect_ident()?;
+                if self.consume_reserved(Reserved::Nil)? {
+                    // `**nil` accepts no keyword arguments.
+                    loc = loc.merge(self.prev_loc());
+                    if state >= Kind::KWRest {
+                        return Err(self.error_unexpected(
+                            loc,
+                            "Keyword rest parameter is not allowed in ths position.",
+                        ));
+                    }
+                    state = Kind::KWRest;
+                    args.push(FormalParam::no_key(loc));
+                    if !self.consume_punct_no_term(Punct::Comma)? {
+                        break;
+                    }
+                    continue;
+                }
+                let id = match self.consume_ident()? {
+                    Some(id) => id,
+                    None if self.is_method_context() => ParamKind::anon_kwrest(),
+                    None => self.expect_ident()?,
+                };
                 loc = loc.merge(self.prev_loc());
                 if state >= Kind::KWRest {
                     return Err(self.error_unexpected(
@@ -2422,13 +3062,77 @@ impl Parser {
                 break;
             }
         }
+        // Variables in parenthesized parameters are placed after all parameters.
+        for (mlhs, param) in destructs {
+            self.new_mlhs_params(&mlhs)?;
+            // Keep MLHS nested so that a single target like `(a)` also destructures the argument.
+            let mlhs = Node::new_mlhs(mlhs, param.loc());
+            let node = Node::new_mul_assign(vec![mlhs], vec![param]);
+            self.context_mut().param_mlhs.push(node);
+        }
         Ok(args)
     }
 
+    /// Parse a parenthesized parameter after `(`.
+    fn parse_param_mlhs(&mut self) -> Result<Vec<Node>, RubyError> {
+        let mut mlhs = vec![];
+        loop {
+            let loc = self.loc();
+            if self.consume_punct(Punct::Mul)? {
+                let lhs = match self.consume_ident()? {
+                    Some(id) => Node::new_lvar(id, self.prev_loc()),
+                    None => Node::new_nil(loc),
+                };
+                mlhs.push(Node::new_splat(lhs, loc));
+            } else if self.consume_punct(Punct::LParen)? {
+                let nested = self.parse_param_mlhs()?;
+                let loc = loc.merge(self.prev_loc());
+                mlhs.push(Node::new_mlhs(nested, loc));
+            } else {
+                let id = self.expect_ident()?;
+                mlhs.push(Node::new_lvar(id, self.prev_loc()));
+            }
+            if !self.consume_punct(Punct::Comma)? {
+                break;
+            }
+        }
+        self.expect_punct(Punct::RParen)?;
+        if let Some(lhs) = mlhs.iter().filter(|lhs| lhs.is_splat()).nth(1) {
+            return Err(self.error_unexpected(lhs.loc(), "Multiple splats in multiple assignment."));
+        }
+        Ok(mlhs)
+    }
+
+    /// Add variables in a parenthesized parameter to the current context.
+    fn new_mlhs_params(&mut self, mlhs: &[Node]) -> Result<(), RubyError> {
+        for lhs in mlhs {
+            match &lhs.kind {
+                NodeKind::LocalVar(id) => self.new_param(*id, lhs.loc())?,
+                NodeKind::Splat(box Node {
+                    kind: NodeKind::LocalVar(id),
+                    loc,
+                }) => self.new_param(*id, *loc)?,
+                NodeKind::Mlhs(nested) => self.new_mlhs_params(nested)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepend destructuring assignments for parenthesized parameters to `body`.
+    fn with_param_mlhs(&mut self, body: Node) -> Node {
+        let mut nodes = std::mem::take(&mut self.context_mut().param_mlhs);
+        if nodes.is_empty() {
+            return body;
+        }
+        nodes.push(body);
+        Node::new_comp_stmt(nodes, self.prev_loc())
+    }
+
     // ( )
     // ( ident [, ident]* )
     fn parse_def_params(&mut self) -> Result<Vec<FormalParam>, RubyError> {
-        if self.consume_term()? {
+        if self.consume_term()? || self.peek_punct_no_term(Punct::Assign) {
             return Ok(vec![]);
         };
         let paren_flag = self.consume_punct(Punct::LParen)?;
@@ -2532,6 +3236,366 @@ impl Parser {
         Ok(Node::new_singleton_class_decl(singleton, body, lvar, loc))
     }
 
+    fn parse_case_in(&mut self, cond: Node, loc: Loc) -> Result<Node, RubyError> {
+        // CASE_IN : case EXPR TERM (in PATTERN_TOP [no term] ((if|unless) EXPR)? THEN COMP_STMT)+
+        //           (else COMP_STMT)? end
+        let mut branches = vec![];
+        while self.consume_reserved(Reserved::In)? {
+            let pattern = self.parse_pattern_top()?;
+            let guard = if self.consume_reserved_no_skip_line_term(Reserved::If)? {
+                Some(self.parse_expr()?)
+            } else if self.consume_reserved_no_skip_line_term(Reserved::Unless)? {
+                let loc = self.prev_loc();
+                let cond = self.parse_expr()?;
+                Some(Node::new_unop(UnOp::Not, cond, loc))
+            } else {
+                None
+            };
+            self.parse_then()?;
+            let body = self.parse_comp_stmt()?;
+            branches.push(InBranch::new(pattern, guard, body));
+        }
+        let else_ = if self.consume_reserved(Reserved::Else)? {
+            Some(self.parse_comp_stmt()?)
+        } else {
+            None
+        };
+        self.expect_reserved(Reserved::End)?;
+        let loc = loc.merge(self.prev_loc());
+        Ok(Node::new_case_in(cond, branches, else_, loc))
+    }
+
+    /// Parse a pattern at the top level of `in` clauses, `=>` or `in`,
+    /// where array patterns and hash patterns can be written without brackets or braces.
+    fn parse_pattern_top(&mut self) -> Result<Pattern, RubyError> {
+        if self.is_hash_pattern_start()? {
+            return self.parse_hash_pattern(None, None);
+        }
+        let first = self.parse_pattern_item()?;
+        if !self.peek_punct_no_term(Punct::Comma) {
+            return match first {
+                PatternItem::Pattern(pattern) => Ok(pattern),
+                rest => self.array_pattern(None, vec![rest]),
+            };
+        }
+        let mut items = vec![first];
+        while self.consume_punct_no_term(Punct::Comma)? {
+            items.push(self.parse_pattern_item()?);
+        }
+        self.array_pattern(None, items)
+    }
+
+    /// Check whether a hash pattern starts at the next token. (`key:` or `**`)
+    fn is_hash_pattern_start(&mut self) -> Result<bool, RubyError> {
+        let tok = self.peek()?;
+        if tok.kind == TokenKind::Punct(Punct::DMul) {
+            return Ok(true);
+        }
+        if !tok.can_be_symbol() {
+            return Ok(false);
+        }
+        self.save_state();
+        self.get()?;
+        let res = self.consume_punct_no_term(Punct::Colon)?;
+        self.restore_state();
+        Ok(res)
+    }
+
+    /// Parse an element of array patterns.
+    fn parse_pattern_item(&mut self) -> Result<PatternItem, RubyError> {
+        if self.consume_punct(Punct::Mul)? {
+            let id = match self.peek_no_term()?.kind {
+                TokenKind::Ident(_) => {
+                    let id = self.expect_ident()?;
+                    self.add_local_var_if_new(id);
+                    Some(id)
+                }
+                _ => None,
+            };
+            Ok(PatternItem::Rest(id))
+        } else {
+            Ok(PatternItem::Pattern(self.parse_pattern()?))
+        }
+    }
+
+    /// Parse elements of array patterns until `close`.
+    fn parse_pattern_items(&mut self, close: Punct) -> Result<Vec<PatternItem>, RubyError> {
+        let mut items = vec![];
+        loop {
+            if self.consume_punct(close)? {
+                return Ok(items);
+            }
+            items.push(self.parse_pattern_item()?);
+            if !self.consume_punct(Punct::Comma)? {
+                break;
+            }
+        }
+        self.expect_punct(close)?;
+        Ok(items)
+    }
+
+    /// Build an array pattern or a find pattern from `items`.
+    fn array_pattern(
+        &mut self,
+        const_: Option<Node>,
+        items: Vec<PatternItem>,
+    ) -> Result<Pattern, RubyError> {
+        let const_ = const_.map(Box::new);
+        let rest_num = items
+            .iter()
+            .filter(|item| matches!(item, PatternItem::Rest(_)))
+            .count();
+        if rest_num == 2 {
+            // Find pattern: [*pre, mid.., *post]
+            let len = items.len();
+            let mut pre = None;
+            let mut post = None;
+            let mut mid = vec![];
+            for (i, item) in items.into_iter().enumerate() {
+                match item {
+                    PatternItem::Rest(id) if i == 0 => pre = id,
+                    PatternItem::Rest(id) if i == len - 1 => post = id,
+                    PatternItem::Pattern(pattern) => mid.push(pattern),
+                    PatternItem::Rest(_) => {
+                        return Err(
+                            self.error_unexpected(self.prev_loc(), "Unexpected rest pattern.")
+                        )
+                    }
+                }
+            }
+            return Ok(Pattern::Find {
+                const_,
+                pre,
+                mid,
+                post,
+            });
+        } else if rest_num > 2 {
+            return Err(self.error_unexpected(self.prev_loc(), "Unexpected rest pattern."));
+        }
+        let mut pre = vec![];
+        let mut rest = None;
+        let mut post = vec![];
+        for item in items {
+            match item {
+                PatternItem::Rest(id) => rest = Some(id),
+                PatternItem::Pattern(pattern) if rest.is_none() => pre.push(pattern),
+                PatternItem::Pattern(pattern) => post.push(pattern),
+            }
+        }
+        Ok(Pattern::Array {
+            const_,
+            pre,
+            rest,
+            post,
+        })
+    }
+
+    /// Parse a hash pattern until `close`, or until the end of the pattern if `close` is None.
+    fn parse_hash_pattern(
+        &mut self,
+        const_: Option<Node>,
+        close: Option<Punct>,
+    ) -> Result<Pattern, RubyError> {
+        let mut pairs = vec![];
+        let mut rest = HashPatternRest::Omitted;
+        loop {
+            if let Some(close) = close {
+                if self.consume_punct(close)? {
+                    break;
+                }
+            }
+            if self.consume_punct(Punct::DMul)? {
+                rest = if self.consume_reserved_no_skip_line_term(Reserved::Nil)? {
+                    HashPatternRest::Nil
+                } else {
+                    let id = self.expect_ident()?;
+                    self.add_local_var_if_new(id);
+                    HashPatternRest::Var(id)
+                };
+            } else {
+                let tok = self.get()?;
+                if !tok.can_be_symbol() {
+                    return Err(self.error_unexpected(tok.loc(), "Expect a key of hash pattern."));
+                }
+                let id = self.get_ident_id(&self.token_as_symbol(&tok));
+                self.expect_punct(Punct::Colon)?;
+                // `key:` without a pattern binds the value to `key`.
+                let tok = self.peek_no_term()?;
+                let omitted = is_pattern_end(&tok);
+                if omitted {
+                    self.add_local_var_if_new(id);
+                    pairs.push((id, None));
+                } else {
+                    pairs.push((id, Some(self.parse_pattern()?)));
+                }
+            }
+            let comma = match close {
+                Some(_) => self.consume_punct(Punct::Comma)?,
+                None => self.consume_punct_no_term(Punct::Comma)?,
+            };
+            if !comma {
+                if let Some(close) = close {
+                    self.expect_punct(close)?;
+                }
+                break;
+            }
+        }
+        Ok(Pattern::Hash {
+            const_: const_.map(Box::new),
+            pairs,
+            rest,
+        })
+    }
+
+    /// Parse a pattern with alternatives and bindings. (`pattern | pattern => var`)
+    fn parse_pattern(&mut self) -> Result<Pattern, RubyError> {
+        let mut pattern = self.parse_pattern_primary()?;
+        if self.peek_punct_no_term(Punct::BitOr) {
+            let mut alts = vec![pattern];
+            while self.consume_punct_no_term(Punct::BitOr)? {
+                alts.push(self.parse_pattern_primary()?);
+            }
+            pattern = Pattern::Alt(alts);
+        }
+        while self.consume_punct_no_term(Punct::FatArrow)? {
+            let id = self.expect_ident()?;
+            self.add_local_var_if_new(id);
+            pattern = Pattern::Bind(Box::new(pattern), id);
+        }
+        Ok(pattern)
+    }
+
+    fn parse_pattern_primary(&mut self) -> Result<Pattern, RubyError> {
+        let tok = self.peek()?;
+        let loc = tok.loc();
+        match tok.kind {
+            TokenKind::Punct(Punct::LBracket) => {
+                self.get()?;
+                let items = self.parse_pattern_items(Punct::RBracket)?;
+                self.array_pattern(None, items)
+            }
+            TokenKind::Punct(Punct::LBrace) => {
+                self.get()?;
+                self.parse_hash_pattern(None, Some(Punct::RBrace))
+            }
+            TokenKind::Punct(Punct::LParen) => {
+                self.get()?;
+                let pattern = self.parse_pattern_top()?;
+                self.expect_punct(Punct::RParen)?;
+                Ok(pattern)
+            }
+            TokenKind::Punct(Punct::BitXor) => {
+                // Pinned expression: ^var, ^@ivar, ^$gvar, ^(expr)
+                self.get()?;
+                let tok = self.get()?;
+                let node = match &tok.kind {
+                    TokenKind::Ident(name) => {
+                        let id = self.get_ident_id(name);
+                        if !self.is_local_var(id) {
+                            return Err(self.error_unexpected(
+                                tok.loc(),
+                                format!("{}: no such local variable", name),
+                            ));
+                        }
+                        Node::new_lvar(id, tok.loc())
+                    }
+                    TokenKind::InstanceVar(name) => Node::new_instance_var(name, tok.loc()),
+                    TokenKind::ClassVar(name) => Node::new_class_var(name, tok.loc()),
+                    TokenKind::GlobalVar(name) => Node::new_global_var(name, tok.loc()),
+                    TokenKind::Punct(Punct::LParen) => {
+                        let node = self.parse_expr()?;
+                        self.expect_punct(Punct::RParen)?;
+                        node
+                    }
+                    _ => return Err(self.error_unexpected(tok.loc(), "Unexpected pinned value.")),
+                };
+                Ok(Pattern::Value(Box::new(node)))
+            }
+            TokenKind::Ident(name) => {
+                self.get()?;
+                let id = self.get_ident_id(&name);
+                self.add_local_var_if_new(id);
+                Ok(Pattern::Var(id))
+            }
+            TokenKind::Const(_) | TokenKind::Punct(Punct::Scope) => self.parse_pattern_const(),
+            TokenKind::Punct(Punct::Range2) | TokenKind::Punct(Punct::Range3) => {
+                // Beginless range.
+                let exclude_end = self.get()?.kind == TokenKind::Punct(Punct::Range3);
+                let end = self.parse_arg_shift()?;
+                let loc = loc.merge(end.loc());
+                let node = Node::new_range(Node::new_nil(loc), end, exclude_end, loc);
+                Ok(Pattern::Value(Box::new(node)))
+            }
+            _ => {
+                let node = self.parse_arg_shift()?;
+                Ok(Pattern::Value(Box::new(self.parse_pattern_range(node)?)))
+            }
+        }
+    }
+
+    /// Parse a range of value patterns if `..` or `...` follows `start`.
+    fn parse_pattern_range(&mut self, start: Node) -> Result<Node, RubyError> {
+        let exclude_end = if self.consume_punct_no_term(Punct::Range2)? {
+            false
+        } else if self.consume_punct_no_term(Punct::Range3)? {
+            true
+        } else {
+            return Ok(start);
+        };
+        let loc = start.loc();
+        let tok = self.peek_no_term()?;
+        let endless = is_pattern_end(&tok);
+        let end = if endless {
+            Node::new_nil(self.prev_loc())
+        } else {
+            self.parse_arg_shift()?
+        };
+        let loc = loc.merge(self.prev_loc());
+        Ok(Node::new_range(start, end, exclude_end, loc))
+    }
+
+    /// Parse a constant pattern, or an array pattern or a hash pattern with a constant.
+    fn parse_pattern_const(&mut self) -> Result<Pattern, RubyError> {
+        let loc = self.loc();
+        let mut node = if self.consume_punct(Punct::Scope)? {
+            let name = self.expect_const()?;
+            Node::new_const(&name, true, loc.merge(self.prev_loc()))
+        } else {
+            let name = self.expect_const()?;
+            Node::new_const(&name, false, loc)
+        };
+        loop {
+            if self.lexer.trailing_lparen() {
+                self.expect_punct(Punct::LParen)?;
+                return self.parse_pattern_with_const(node, Punct::RParen);
+            }
+            if self.consume_punct_no_term(Punct::Scope)? {
+                let name = self.expect_const()?;
+                node = Node::new_scope(node, &name, loc.merge(self.prev_loc()));
+            } else {
+                break;
+            }
+        }
+        if self.consume_punct_no_term(Punct::LBracket)? {
+            return self.parse_pattern_with_const(node, Punct::RBracket);
+        }
+        Ok(Pattern::Value(Box::new(self.parse_pattern_range(node)?)))
+    }
+
+    /// Parse `Const(..)` or `Const[..]`.
+    fn parse_pattern_with_const(
+        &mut self,
+        const_: Node,
+        close: Punct,
+    ) -> Result<Pattern, RubyError> {
+        if self.is_hash_pattern_start()? {
+            self.parse_hash_pattern(Some(const_), Some(close))
+        } else {
+            let items = self.parse_pattern_items(close)?;
+            self.array_pattern(Some(const_), items)
+        }
+    }
+
     fn parse_begin(&mut self) -> Result<Node, RubyError> {
         // begin式 :: "begin"  複合文  rescue節*  else節?  ensure節?  "end"
         // rescue節 :: "rescue" [行終端子禁止] 例外クラスリスト?  例外変数代入?  then節
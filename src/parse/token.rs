@@ -45,14 +45,15 @@ pub enum TokenKind {
     IntegerLit(i64),
     FloatLit(f64),
     ImaginaryLit(Real),
-    StringLit(String),
-    CommandLit(String),
+    StringLit(Vec<u8>),
+    CommandLit(Vec<u8>),
     Reserved(Reserved),
     Punct(Punct),
-    OpenString(String, char, usize), // (content, delimiter, paren_level)
-    OpenRegex(String),
-    OpenCommand(String, char, usize),
+    OpenString(Vec<u8>, char, usize), // (content, delimiter, paren_level)
+    OpenRegex(Vec<u8>),
+    OpenCommand(Vec<u8>, char, usize),
     PercentNotation(char, String),
+    OpenWords(char, char), // (kind, delimiter)
     LineTerm,
 }
 
@@ -81,11 +82,14 @@ pub enum Reserved {
     Next,
     Nil,
     Or,
+    Redo,
+    Retry,
     Return,
     Rescue,
     Self_,
     Then,
     True,
+    Undef,
     Until,
     Unless,
     When,
@@ -178,22 +182,31 @@ impl Token {
         Annot::new(TokenKind::ImaginaryLit(num), loc)
     }
 
-    pub fn new_stringlit(string: impl Into<String>, loc: Loc) -> Self {
+    pub fn new_stringlit(string: impl Into<Vec<u8>>, loc: Loc) -> Self {
         Annot::new(TokenKind::StringLit(string.into()), loc)
     }
 
-    pub fn new_open_string(s: impl Into<String>, delimiter: char, level: usize, loc: Loc) -> Self {
+    pub fn new_open_string(s: impl Into<Vec<u8>>, delimiter: char, level: usize, loc: Loc) -> Self {
         Annot::new(TokenKind::OpenString(s.into(), delimiter, level), loc)
     }
 
-    pub fn new_open_command(s: impl Into<String>, delimiter: char, level: usize, loc: Loc) -> Self {
+    pub fn new_open_command(
+        s: impl Into<Vec<u8>>,
+        delimiter: char,
+        level: usize,
+        loc: Loc,
+    ) -> Self {
         Annot::new(TokenKind::OpenCommand(s.into(), delimiter, level), loc)
     }
 
-    pub fn new_open_reg(s: impl Into<String>, loc: Loc) -> Self {
+    pub fn new_open_reg(s: impl Into<Vec<u8>>, loc: Loc) -> Self {
         Annot::new(TokenKind::OpenRegex(s.into()), loc)
     }
 
+    pub fn new_open_words(kind: char, delimiter: char, loc: Loc) -> Self {
+        Annot::new(TokenKind::OpenWords(kind, delimiter), loc)
+    }
+
     pub fn new_percent(kind: char, content: String, loc: Loc) -> Self {
         Annot::new(TokenKind::PercentNotation(kind, content), loc)
     }
@@ -249,6 +262,7 @@ impl Token {
                 | Reserved::Elsif
                 | Reserved::End
                 | Reserved::When
+                | Reserved::In
                 | Reserved::Rescue
                 | Reserved::Ensure => true,
                 _ => false,
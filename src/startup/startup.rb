@@ -27,6 +27,34 @@ class Thread
   def self.current
     CURRENT
   end
+
+  module Backtrace
+    class Location
+      attr_reader :path, :lineno, :label
+
+      def initialize(path, lineno, label)
+        @path = path
+        @lineno = lineno
+        @label = label
+      end
+
+      def absolute_path
+        File.expand_path(@path)
+      end
+
+      def base_label
+        @label.sub(/\Ablock (\(\d+ levels\) )?in /, "")
+      end
+
+      def to_s
+        "#{@path}:#{@lineno}:in `#{@label}'"
+      end
+
+      def inspect
+        to_s.inspect
+      end
+    end
+  end
 end
 
 class Delegator
@@ -39,9 +67,6 @@ class RubyVM
   end
 end
 
-class SystemExit
-end
-
 class Encoding
   UTF_8 = self.new
   US_ASCII = self.new
@@ -58,27 +83,155 @@ class Encoding
   end
 end
 
-class Errno < StandardError
-  class ENOENT; end;
-  class ENOTDIR; end;
-end
+class Method
+  def to_proc
+    m = self
+    lambda { |*args, &block| m.call(*args, &block) }
+  end
 
-class RangeError < StandardError
-end
-class FloatDomainError < RangeError
-end
-class ZeroDivisionError < StandardError
+  def curry(n = nil)
+    n = arity < 0 ? -arity - 1 : arity if n.nil?
+    to_proc.curry(n)
+  end
 end
-class LoadError < StandardError
+
+class Symbol
+  def to_proc
+    lambda { |obj, *args, &block| obj.send(self, *args, &block) }
+  end
 end
 
+class Proc
+  def curry(n = nil)
+    if n.nil?
+      n = arity < 0 ? -arity - 1 : arity
+    elsif lambda? && (arity < 0 ? n < -arity - 1 : n != arity)
+      raise ArgumentError, "wrong number of arguments (given #{n}, expected #{arity})"
+    end
+    pr = self
+    curried = nil
+    curried = lambda { |args|
+      lambda { |*a|
+        all = args + a
+        all.size >= n ? pr.call(*all) : curried.call(all)
+      }
+    }
+    curried.call([])
+  end
+
+  def >>(g)
+    f = self
+    if lambda?
+      lambda { |*args, &block| g.call(f.call(*args, &block)) }
+    else
+      proc { |*args, &block| g.call(f.call(*args, &block)) }
+    end
+  end
 
-class Module
-  def undef_method(sym); end
-  def define_method(sym); end
+  def <<(g)
+    f = self
+    if lambda?
+      lambda { |*args, &block| f.call(g.call(*args, &block)) }
+    else
+      proc { |*args, &block| f.call(g.call(*args, &block)) }
+    end
+  end
 end
 
 RUBY_PLATFORM = "x86_64-linux"
 RUBY_VERSION = "2.7.0"
 RUBY_ENGINE = "ruruby"
 RUBY_DESCRIPTION = "ruruby [x86_64-linux]"
+
+class WeakRef
+  class RefError < StandardError
+  end
+
+  def initialize(obj)
+    @__map = ObjectSpace::WeakMap.new
+    @__map[:obj] = obj
+  end
+
+  def __getobj__
+    raise RefError, "Invalid Reference - probably recycled" unless @__map.key?(:obj)
+    @__map[:obj]
+  end
+
+  def weakref_alive?
+    @__map.key?(:obj)
+  end
+
+  def method_missing(name, *args, &block)
+    __getobj__.send(name, *args, &block)
+  end
+end
+
+class Rational < Numeric
+  attr_reader :numerator, :denominator
+
+  def +(other)
+    return to_f + other if other.is_a?(Float)
+    other = other.to_r
+    Rational(@numerator * other.denominator + other.numerator * @denominator, @denominator * other.denominator)
+  end
+
+  def -(other)
+    return to_f - other if other.is_a?(Float)
+    other = other.to_r
+    Rational(@numerator * other.denominator - other.numerator * @denominator, @denominator * other.denominator)
+  end
+
+  def *(other)
+    return to_f * other if other.is_a?(Float)
+    other = other.to_r
+    Rational(@numerator * other.numerator, @denominator * other.denominator)
+  end
+
+  def /(other)
+    return to_f / other if other.is_a?(Float)
+    other = other.to_r
+    Rational(@numerator * other.denominator, @denominator * other.numerator)
+  end
+
+  def ==(other)
+    if other.is_a?(Rational) || other.is_a?(Integer)
+      other = other.to_r
+      @numerator == other.numerator && @denominator == other.denominator
+    elsif other.is_a?(Float)
+      to_f == other
+    else
+      false
+    end
+  end
+
+  def <=>(other)
+    return to_f <=> other if other.is_a?(Float)
+    return nil unless other.is_a?(Rational) || other.is_a?(Integer)
+    other = other.to_r
+    (@numerator * other.denominator) <=> (other.numerator * @denominator)
+  end
+
+  def zero?
+    @numerator == 0
+  end
+
+  def to_r
+    self
+  end
+
+  def to_i
+    @numerator < 0 ? -(-@numerator).div(@denominator) : @numerator.div(@denominator)
+  end
+
+  def to_f
+    @numerator.to_f / @denominator
+  end
+
+  def to_s
+    "#{@numerator}/#{@denominator}"
+  end
+
+  def inspect
+    "(#{to_s})"
+  end
+end
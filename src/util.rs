@@ -152,6 +152,8 @@ pub type SourceInfoRef = Ref<SourceInfo>;
 pub struct SourceInfo {
     pub path: PathBuf,
     pub code: Vec<char>,
+    /// number of lines preceding the first line of `code`. (used by eval with lineno)
+    pub line_offset: i64,
 }
 
 use std::ops::{Index, Range, RangeInclusive};
@@ -212,6 +214,7 @@ impl SourceInfo {
         SourceInfo {
             path: path,
             code: vec![],
+            line_offset: 0,
         }
     }
 
@@ -223,6 +226,12 @@ impl SourceInfo {
         eprint!("{}", self.get_location(loc));
     }
 
+    /// Return the line number (the first line is 1) of `loc` in the source code.
+    pub fn get_line(&self, loc: &Loc) -> i64 {
+        let end = std::cmp::min(loc.0 as usize, self.code.len());
+        self.code[..end].iter().filter(|ch| **ch == '\n').count() as i64 + 1 + self.line_offset
+    }
+
     /// Return a string represents the location of `loc` in the source code using '^^^'.
     pub fn get_location(&self, loc: &Loc) -> String {
         if self.code.len() == 0 {
@@ -255,7 +264,11 @@ impl SourceInfo {
             .filter(|line| line.end >= loc.0 && line.top <= loc.1)
         {
             if !found {
-                res_string += &format!("{}:{}\n", self.path.to_string_lossy(), line.no);
+                res_string += &format!(
+                    "{}:{}\n",
+                    self.path.to_string_lossy(),
+                    line.no as i64 + self.line_offset
+                );
                 found = true;
             };
 
@@ -298,7 +311,11 @@ impl SourceInfo {
             let lead = calc_width(&self[line.1..loc.0]);
             let length = calc_width(&self[loc.0..loc.1]);
             let is_cr = loc.1 as usize >= self.code.len() || self[loc.1] == '\n';
-            res_string += &format!("{}:{}\n", self.path.to_string_lossy(), line.0);
+            res_string += &format!(
+                "{}:{}\n",
+                self.path.to_string_lossy(),
+                line.0 as i64 + self.line_offset
+            );
             res_string += &(if !is_cr {
                 self[line.1..=loc.1].iter().collect::<String>()
             } else {
@@ -142,12 +142,19 @@ impl Module {
 
     /// Get method for a receiver which class is `self` and `method` (IdentId).
     pub fn get_method(self, method: IdentId) -> Option<MethodId> {
+        self.get_method_with_owner(method).map(|(method, _)| method)
+    }
+
+    /// Get method for a receiver which class is `self` and `method` (IdentId),
+    /// with the class or module which defines the method.
+    pub fn get_method_with_owner(self, method: IdentId) -> Option<(MethodId, Module)> {
         let mut class = self;
         let mut singleton_flag = self.is_singleton();
         loop {
             match class.get_instance_method(method) {
+                Some(method) if method.is_undef() => return None,
                 Some(method) => {
-                    return Some(method);
+                    return Some((method, class.real_module()));
                 }
                 None => match class.upper() {
                     Some(superclass) => class = superclass,
@@ -170,6 +177,17 @@ impl Module {
         self.method_table().get(&id).cloned()
     }
 
+    /// Undefine method `id` for `self`, so that instances of `self` no longer respond to it.
+    ///
+    /// Raise NameError if `id` is not defined for `self`.
+    pub fn undef_method(mut self, id: IdentId) -> Result<(), RubyError> {
+        if self.get_method(id).is_none() {
+            return Err(RubyError::undefined_method_for_class(id, self));
+        }
+        self.add_method(id, MethodId::default());
+        Ok(())
+    }
+
     /// Add BuiltinFunc `func` named `name` to the singleton class of `self`.
     pub fn add_builtin_class_method(self, name: &str, func: BuiltinFunc) {
         self.get_singleton_class()
@@ -206,6 +224,63 @@ impl Module {
     pub fn set_var_if_exists(&self, id: IdentId, val: Value) -> bool {
         self.get().set_var_if_exists(id, val)
     }
+
+    /// Get a class variable `id` of `self` or its ancestors.
+    pub fn get_class_var(self, id: IdentId) -> Option<Value> {
+        let mut class = self;
+        loop {
+            match class.get_var(id) {
+                Some(val) => return Some(val),
+                None => class = class.upper()?,
+            }
+        }
+    }
+
+    /// Set `val` to a class variable `id`.
+    ///
+    /// If `id` is defined in `self` or its ancestors, the variable is overwritten.
+    /// Otherwise, `id` is defined in `self`.
+    pub fn set_class_var(self, id: IdentId, val: Value) {
+        let mut class = self;
+        loop {
+            if class.set_var_if_exists(id, val) {
+                return;
+            }
+            match class.upper() {
+                Some(superclass) => class = superclass,
+                None => {
+                    self.set_var(id, val);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Module {
+    /// Get a refinement module for `class` which was defined by `Module#refine` in `self`.
+    pub fn get_refinement(&self, class: Module) -> Option<Module> {
+        let refinements = self.get_var(IdentId::get_id("/refinements"))?;
+        let refined_class = IdentId::get_id("/refined_class");
+        refinements
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| Module::new(*r))
+            .find(|r| match r.get_var(refined_class) {
+                Some(c) => c.id() == class.id(),
+                None => false,
+            })
+    }
+
+    /// Register `refinement` as a refinement module for `class` in `self`.
+    pub fn add_refinement(self, class: Module, refinement: Module) {
+        refinement.set_var_by_str("/refined_class", class.into());
+        match self.get_var(IdentId::get_id("/refinements")) {
+            Some(ary) => ary.as_array().unwrap().push(refinement.into()),
+            None => self.set_var_by_str("/refinements", Value::array_from(vec![refinement.into()])),
+        }
+    }
 }
 
 impl Module {
@@ -523,6 +598,11 @@ impl ClassInfo {
         self.ext.const_table.get(&id).cloned()
     }
 
+    /// Remove the constant `id`, and return its value.
+    pub fn remove_const(&mut self, id: IdentId) -> Option<Value> {
+        self.ext.const_table.remove(&id)
+    }
+
     pub fn get_const_by_str(&self, name: &str) -> Option<Value> {
         let id = IdentId::get_id(name);
         self.get_const(id)
@@ -24,6 +24,7 @@ pub enum ObjKind {
     Splat(Value), // internal use only.
     Hash(Box<HashInfo>),
     Proc(ProcInfo),
+    Binding(ContextRef),
     Regexp(RegexpInfo),
     Method(MethodObjInfo),
     Fiber(Box<FiberContext>),
@@ -57,10 +58,15 @@ impl GC for RValue {
             }
             ObjKind::Splat(v) => v.mark(alloc),
             ObjKind::Proc(pref) => pref.context.mark(alloc),
+            ObjKind::Binding(ctx) => ctx.mark(alloc),
             ObjKind::Method(mref) => mref.mark(alloc),
             ObjKind::Enumerator(fref) | ObjKind::Fiber(fref) => fref.mark(alloc),
             ObjKind::Exception(err) => match &err.kind {
                 RubyErrorKind::Value(val) => val.mark(alloc),
+                RubyErrorKind::Throw(tag, val) => {
+                    tag.mark(alloc);
+                    val.mark(alloc);
+                }
                 _ => {}
             },
             _ => {}
@@ -108,6 +114,7 @@ impl RValue {
                 ObjKind::Method(hinfo) => ObjKind::Method(hinfo.clone()),
                 ObjKind::Ordinary => ObjKind::Ordinary,
                 ObjKind::Proc(pref) => ObjKind::Proc(pref.clone()),
+                ObjKind::Binding(ctx) => ObjKind::Binding(*ctx),
                 ObjKind::Range(info) => ObjKind::Range(info.clone()),
                 ObjKind::Regexp(rref) => ObjKind::Regexp(rref.clone()),
                 ObjKind::Splat(v) => ObjKind::Splat(*v),
@@ -285,6 +292,14 @@ impl RValue {
         }
     }
 
+    pub fn new_binding(context: ContextRef) -> Self {
+        RValue {
+            class: BuiltinClass::binding(),
+            var_table: None,
+            kind: ObjKind::Binding(context),
+        }
+    }
+
     pub fn new_method(method_info: MethodObjInfo) -> Self {
         RValue {
             class: BuiltinClass::method(),
@@ -293,6 +308,14 @@ impl RValue {
         }
     }
 
+    pub fn new_unbound_method(method_info: MethodObjInfo) -> Self {
+        RValue {
+            class: BuiltinClass::unbound_method(),
+            var_table: None,
+            kind: ObjKind::Method(method_info),
+        }
+    }
+
     pub fn new_fiber(vm: VM, context: ContextRef) -> Self {
         let fiber = FiberContext::new_fiber(vm, context);
         RValue {
@@ -320,18 +343,12 @@ impl RValue {
 
     pub fn new_exception(exception_class: Module, err: RubyError) -> Self {
         let message = Value::string(err.message());
-        let mut backtrace = vec![];
-        for pos in 0..err.info.len() {
-            backtrace.push(Value::string(err.get_location(pos)));
-        }
-        let backtrace = Value::array_from(backtrace);
         let mut rval = RValue {
             class: exception_class,
             var_table: None,
             kind: ObjKind::Exception(err),
         };
         rval.set_var(IdentId::get_id("@message"), message);
-        rval.set_var(IdentId::get_id("@backtrace"), backtrace);
         rval
     }
 }
@@ -270,6 +270,7 @@ impl Value {
                 ObjKind::Regexp(rref) => format!("/{}/", rref.as_str()),
                 ObjKind::Splat(v) => format!("Splat[{}]", v.format(level - 1)),
                 ObjKind::Proc(p) => format!("#<Proc:0x{:x}>", p.context.id()),
+                ObjKind::Binding(ctx) => format!("#<Binding:0x{:016x}>", ctx.id()),
                 ObjKind::Method(_) => format!("Method"),
                 ObjKind::Enumerator(_) => format!("Enumerator"),
                 ObjKind::Fiber(_) => format!("Fiber"),
@@ -374,13 +375,7 @@ impl Value {
             RV::True => Cow::from("true"),
             RV::False => Cow::from("false"),
             RV::Integer(i) => Cow::from(i.to_string()),
-            RV::Float(f) => {
-                if f.fract() == 0.0 {
-                    Cow::from(format!("{:.1}", f))
-                } else {
-                    Cow::from(f.to_string())
-                }
-            }
+            RV::Float(f) => Cow::from(builtin::float::float_to_s(f)),
             RV::Symbol(i) => Cow::from(format!("{:?}", i)),
             RV::Object(oref) => match &oref.kind {
                 ObjKind::Invalid => panic!("Invalid rvalue. (maybe GC problem) {:?}", *oref),
@@ -881,6 +876,16 @@ impl Value {
         }
     }
 
+    pub fn as_binding(&self) -> Option<ContextRef> {
+        match self.as_rvalue() {
+            Some(oref) => match &oref.kind {
+                ObjKind::Binding(ctx) => Some(*ctx),
+                _ => None,
+            },
+            None => None,
+        }
+    }
+
     pub fn expect_proc(&self, _: &mut VM) -> Result<&ProcInfo, RubyError> {
         match self.as_proc() {
             Some(e) => Ok(e),
@@ -1092,8 +1097,17 @@ impl Value {
         RValue::new_proc(ProcInfo::new(context)).pack()
     }
 
-    pub fn method(name: IdentId, receiver: Value, method: MethodId) -> Self {
-        RValue::new_method(MethodObjInfo::new(name, receiver, method)).pack()
+    pub fn binding(context: ContextRef) -> Self {
+        RValue::new_binding(context).pack()
+    }
+
+    pub fn method(name: IdentId, receiver: Value, method: MethodId, owner: Module) -> Self {
+        RValue::new_method(MethodObjInfo::new(name, receiver, method, owner)).pack()
+    }
+
+    pub fn unbound_method(name: IdentId, method: MethodId, owner: Module) -> Self {
+        let info = MethodObjInfo::new(name, Value::nil(), method, owner);
+        RValue::new_unbound_method(info).pack()
     }
 
     pub fn fiber(parent_vm: &mut VM, context: ContextRef) -> Self {
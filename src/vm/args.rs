@@ -22,19 +22,16 @@ impl GC for Block {
 }
 
 impl Block {
-    pub fn to_iseq(&self) -> ISeqRef {
+    pub fn to_iseq(&self) -> Result<ISeqRef, RubyError> {
         match self {
-            Block::Proc(val) => {
-                //let val = *val;
-                val.as_proc()
-                    .unwrap_or_else(|| {
-                        unimplemented!("Block argument must be Proc. given:{:?}", val)
-                    })
-                    .context
-                    .iseq_ref
-                    .unwrap()
-            }
-            Block::Block(methodref, _) => methodref.as_iseq(),
+            Block::Proc(val) => match val.as_proc() {
+                Some(proc) => Ok(proc.context.iseq_ref.unwrap()),
+                None => Err(RubyError::typeerr(format!(
+                    "Wrong argument type {} (expected Proc).",
+                    val.get_class_name()
+                ))),
+            },
+            Block::Block(methodref, _) => Ok(methodref.as_iseq()),
             Block::None => unreachable!(),
         }
     }
@@ -15,6 +15,10 @@ pub struct Context {
     pub moved_to_heap: Option<ContextRef>,
     pub on_stack: bool,
     pub kind: ISeqKind,
+    /// The position in ISeq where this context called the next context.
+    pub pc: ISeqPos,
+    /// Exceptions being handled in this context, with the ranges of the handlers in ISeq.
+    pub handled: Vec<(ISeqPos, ISeqPos, Value)>,
 }
 
 pub type ContextRef = Ref<Context>;
@@ -84,6 +88,7 @@ impl GC for Context {
             Some(c) => c.mark(alloc),
             None => {}
         }
+        self.handled.iter().for_each(|(_, _, v)| v.mark(alloc));
     }
 }
 
@@ -110,6 +115,8 @@ impl Context {
             moved_to_heap: None,
             on_stack: true,
             kind: iseq_ref.kind,
+            pc: ISeqPos::from(0),
+            handled: vec![],
         }
     }
 
@@ -124,6 +131,8 @@ impl Context {
             moved_to_heap: None,
             on_stack: true,
             kind: ISeqKind::Block,
+            pc: ISeqPos::from(0),
+            handled: vec![],
         }
     }
 
@@ -192,13 +201,35 @@ impl Context {
         iseq: ISeqRef,
         args: &Args,
         outer: Option<ContextRef>,
+    ) -> Result<Self, RubyError> {
+        Context::from_args_inner(vm, self_value, iseq, args, outer, iseq.is_block())
+    }
+
+    /// Create new Context for a lambda.
+    /// Unlike blocks, the number of arguments is checked strictly and
+    /// a single Array argument is not expanded.
+    pub fn from_args_lambda(
+        vm: &mut VM,
+        self_value: Value,
+        iseq: ISeqRef,
+        args: &Args,
+        outer: Option<ContextRef>,
+    ) -> Result<Self, RubyError> {
+        Context::from_args_inner(vm, self_value, iseq, args, outer, false)
+    }
+
+    fn from_args_inner(
+        vm: &mut VM,
+        self_value: Value,
+        iseq: ISeqRef,
+        args: &Args,
+        outer: Option<ContextRef>,
+        is_block: bool,
     ) -> Result<Self, RubyError> {
         let mut context = Context::new(self_value, args.block.clone(), iseq, outer);
-        if iseq.opt_flag {
-            if !args.kw_arg.is_nil() {
-                return Err(RubyError::argument("Undefined keyword."));
-            };
-            if iseq.is_block() {
+        // Keyword arguments for a method without keyword parameters are passed as a Hash.
+        if iseq.opt_flag && args.kw_arg.is_nil() {
+            if is_block {
                 context.from_args_opt_block(&iseq.params, args)?;
             } else {
                 let req_len = iseq.params.req;
@@ -208,6 +239,9 @@ impl Context {
             return Ok(context);
         }
         let params = &iseq.params;
+        if params.no_keyword && !args.kw_arg.is_nil() {
+            return Err(RubyError::argument("no keywords accepted."));
+        }
         let mut keyword_flag = false;
         let kw = if params.keyword.is_empty() && !params.kwrest {
             // if no keyword param nor kwrest param exists in formal parameters,
@@ -217,7 +251,7 @@ impl Context {
             keyword_flag = !args.kw_arg.is_nil();
             Value::nil()
         };
-        if !iseq.is_block() {
+        if !is_block {
             let min = params.req + params.post;
             let kw = if kw.is_nil() { 0 } else { 1 };
             if params.rest.is_some() {
@@ -229,14 +263,14 @@ impl Context {
             }
         }
 
-        context.set_arguments(args, kw);
+        context.set_arguments(args, kw, is_block);
         if params.kwrest || keyword_flag {
             let mut kwrest = FxIndexMap::default();
             if keyword_flag {
                 let keyword = args.kw_arg.as_hash().unwrap();
+                let mut unknown = vec![];
                 for (k, v) in keyword.iter() {
-                    let id = k.as_symbol().unwrap();
-                    match params.keyword.get(&id) {
+                    match k.as_symbol().and_then(|id| params.keyword.get(&id)) {
                         Some(lvar) => {
                             context[*lvar] = v;
                         }
@@ -244,16 +278,38 @@ impl Context {
                             if params.kwrest {
                                 kwrest.insert(HashKey(k), v);
                             } else {
-                                return Err(RubyError::argument("Undefined keyword."));
+                                unknown.push(vm.val_inspect(k)?);
                             }
                         }
                     };
                 }
+                if !unknown.is_empty() {
+                    let plural = if unknown.len() == 1 { "" } else { "s" };
+                    return Err(RubyError::argument(format!(
+                        "unknown keyword{}: {}",
+                        plural,
+                        unknown.join(", ")
+                    )));
+                }
             };
             if let Some(id) = iseq.lvar.kwrest_param() {
                 context[id] = Value::hash_from_map(kwrest);
             }
         };
+        let missing: Vec<String> = params
+            .req_keyword
+            .iter()
+            .filter(|id| context[params.keyword[*id]].is_uninitialized())
+            .map(|id| format!(":{:?}", id))
+            .collect();
+        if !missing.is_empty() {
+            let plural = if missing.len() == 1 { "" } else { "s" };
+            return Err(RubyError::argument(format!(
+                "missing keyword{}: {}",
+                plural,
+                missing.join(", ")
+            )));
+        }
         if let Some(id) = iseq.lvar.block_param() {
             context[id] = match &args.block {
                 Block::Block(method, ctx) => {
@@ -267,11 +323,11 @@ impl Context {
         Ok(context)
     }
 
-    fn set_arguments(&mut self, args: &Args, kw_arg: Value) {
+    fn set_arguments(&mut self, args: &Args, kw_arg: Value, is_block: bool) {
         let iseq = self.iseq_ref.unwrap();
         let req_len = iseq.params.req;
         let post_len = iseq.params.post;
-        if iseq.is_block() && args.len() == 1 && req_len + post_len > 1 {
+        if is_block && args.len() == 1 && req_len + post_len > 1 {
             if let Some(ary) = args[0].as_array() {
                 self.fill_arguments(&ary.elements, &iseq.params, kw_arg);
                 return;
@@ -21,6 +21,8 @@ pub struct VM {
     class_context: Vec<(Module, DefineMode)>,
     exec_stack: Vec<Value>,
     temp_stack: Vec<Value>,
+    /// Tags of active `catch` blocks.
+    catch_tags: Vec<Value>,
     //exception: bool,
     pc: ISeqPos,
     pub handle: Option<FiberHandle>,
@@ -31,12 +33,14 @@ pub type VMRef = Ref<VM>;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DefineMode {
     module_function: bool,
+    singleton: bool,
 }
 
 impl DefineMode {
     pub fn default() -> Self {
         DefineMode {
             module_function: false,
+            singleton: false,
         }
     }
 }
@@ -50,6 +54,7 @@ impl GC for VM {
         self.class_context.iter().for_each(|(v, _)| v.mark(alloc));
         self.exec_stack.iter().for_each(|v| v.mark(alloc));
         self.temp_stack.iter().for_each(|v| v.mark(alloc));
+        self.catch_tags.iter().for_each(|v| v.mark(alloc));
     }
 }
 
@@ -62,6 +67,7 @@ impl VM {
             exec_context: vec![],
             exec_stack: vec![],
             temp_stack: vec![],
+            catch_tags: vec![],
             pc: ISeqPos::from(0),
             handle: None,
         };
@@ -106,6 +112,7 @@ impl VM {
             cur_context: None,
             exec_context: vec![],
             temp_stack: vec![],
+            catch_tags: vec![],
             class_context: self.class_context.clone(),
             exec_stack: vec![],
             pc: ISeqPos::from(0),
@@ -187,6 +194,11 @@ impl VM {
         }
     }
 
+    /// Returns the current length of the temporary area.
+    pub fn temp_len(&self) -> usize {
+        self.temp_stack.len()
+    }
+
     pub fn temp_pop_vec(&mut self, len: usize) -> Vec<Value> {
         self.temp_stack.split_off(len)
     }
@@ -196,9 +208,29 @@ impl VM {
         self.temp_stack.extend_from_slice(slice);
     }
 
+    /// Evaluate `block` with `tag` as a catch tag, and return the value thrown with `tag`.
+    pub fn eval_catch(&mut self, tag: Value, block: &Block) -> VMResult {
+        self.catch_tags.push(tag);
+        let res = self.eval_block(block, &Args::new1(tag));
+        self.catch_tags.pop().unwrap();
+        match res {
+            Err(err) => match err.kind {
+                RubyErrorKind::Throw(t, val) if t.id() == tag.id() => Ok(val),
+                _ => Err(err),
+            },
+            res => res,
+        }
+    }
+
+    /// Check whether `tag` is a tag of an active catch block.
+    pub fn is_catch_tag(&self, tag: Value) -> bool {
+        self.catch_tags.iter().any(|t| t.id() == tag.id())
+    }
+
     pub fn context_push(&mut self, ctx: ContextRef) {
         match self.cur_context {
-            Some(c) => {
+            Some(mut c) => {
+                c.pc = self.pc;
                 self.exec_context.push(c);
                 self.cur_context = Some(ctx);
             }
@@ -228,6 +260,17 @@ impl VM {
         self.class_context.push((val, DefineMode::default()));
     }
 
+    /// Push `val` to the class context for `instance_eval`.
+    ///
+    /// Methods defined by `def` in this context become singleton methods of self.
+    pub fn singleton_class_push(&mut self, val: Module) {
+        let mode = DefineMode {
+            module_function: false,
+            singleton: true,
+        };
+        self.class_context.push((val, mode));
+    }
+
     pub fn class_pop(&mut self) {
         self.class_context.pop().unwrap();
     }
@@ -245,6 +288,24 @@ impl VM {
         &mut self.class_context.last_mut().unwrap().1
     }
 
+    /// Get the singleton class of `self_value` pushed by `instance_eval`
+    /// if `def` in the current context defines a singleton method of `self_value`.
+    ///
+    /// Methods called in `instance_eval` are not affected, since they have their own definee.
+    /// Returns TypeError if `self_value` can not have a singleton class. (e.g. Integer)
+    pub fn singleton_definee(&self, self_value: Value) -> Result<Option<Module>, RubyError> {
+        if !self.define_mode().singleton || self.is_method() {
+            return Ok(None);
+        }
+        let singleton = self.class();
+        match singleton.singleton_for() {
+            Some(obj) if obj.id() == self_value.id() => Ok(Some(singleton)),
+            Some(_) => Ok(None),
+            // `instance_eval` pushed the class of the receiver which has no singleton class.
+            None => self_value.get_singleton_class().map(Some),
+        }
+    }
+
     pub fn module_function(&mut self, flag: bool) {
         self.define_mode_mut().module_function = flag;
     }
@@ -253,7 +314,12 @@ impl VM {
         self.pc = (self.pc + inst_offset + disp).into();
     }
 
-    pub fn parse_program(&mut self, path: PathBuf, program: &str) -> Result<MethodId, RubyError> {
+    /// Parse `program` and return the method and the text after `__END__` line.
+    pub fn parse_program(
+        &mut self,
+        path: PathBuf,
+        program: &str,
+    ) -> Result<(MethodId, Option<String>), RubyError> {
         let parser = Parser::new();
         let result = parser.parse_program(path, program)?;
 
@@ -269,17 +335,31 @@ impl VM {
             ContextKind::Method(None),
             None,
         )?;
-        Ok(methodref)
+        Ok((methodref, result.data))
     }
 
     pub fn parse_program_eval(
         &mut self,
         path: PathBuf,
         program: &str,
+        line: i64,
     ) -> Result<MethodId, RubyError> {
-        let parser = Parser::new();
         let extern_context = self.context();
-        let result = parser.parse_program_eval(path, program, Some(extern_context))?;
+        self.parse_program_binding(path, program, line, extern_context)
+    }
+
+    /// Parse `program` as a code evaluated in the scope of `extern_context`.
+    ///
+    /// `line` is the line number of the first line of `program`.
+    pub fn parse_program_binding(
+        &mut self,
+        path: PathBuf,
+        program: &str,
+        line: i64,
+        extern_context: ContextRef,
+    ) -> Result<MethodId, RubyError> {
+        let parser = Parser::new();
+        let result = parser.parse_program_eval(path, program, line, Some(extern_context))?;
 
         #[cfg(feature = "perf")]
         self.globals.perf.set_prev_inst(Perf::INVALID);
@@ -299,11 +379,24 @@ impl VM {
     }
 
     pub fn run(&mut self, path: PathBuf, program: &str) -> VMResult {
-        let method = self.parse_program(path, program)?;
+        // The main script is the first program run after the startup of VM.
+        // Files loaded by require or load are run in the context of the main script.
+        let is_main = self.globals.startup_flag && self.exec_context.is_empty();
+        let (method, data) = self.parse_program(path, program)?;
+        if is_main {
+            if let Some(data) = data {
+                let io = builtin::io::new_data_io(data);
+                self.globals.set_toplevel_constant("DATA", io);
+            }
+        }
         let mut iseq = method.as_iseq();
         iseq.class_defined = self.get_class_defined();
         let self_value = self.globals.main_object;
-        let val = self.eval_method(method, self_value, &Args::new0())?;
+        let res = self.eval_method(method, self_value, &Args::new0());
+        // at_exit procs run even if the main script raised, but the error of the script comes first.
+        let at_exit = if is_main { self.exec_at_exit() } else { Ok(()) };
+        let val = res?;
+        at_exit?;
         #[cfg(feature = "perf")]
         self.globals.perf.get_perf(Perf::INVALID);
         assert!(
@@ -314,6 +407,14 @@ impl VM {
         Ok(val)
     }
 
+    /// Execute procs registered by `at_exit` in the reverse order of registration.
+    fn exec_at_exit(&mut self) -> Result<(), RubyError> {
+        while let Some(procobj) = self.globals.at_exit_procs.pop() {
+            self.eval_proc(procobj, &Args::new0())?;
+        }
+        Ok(())
+    }
+
     #[cfg(not(tarpaulin_include))]
     pub fn run_repl(&mut self, result: ParseResult, mut context: ContextRef) -> VMResult {
         #[cfg(feature = "perf")]
@@ -370,6 +471,24 @@ impl VM {
         #[cfg(feature = "perf")]
         self.globals.perf.get_perf(Perf::GC);
         self.globals.gc();
+        self.run_finalizers();
+    }
+
+    /// Invoke the finalizers of the objects collected in the previous GC.
+    /// Errors raised in finalizers are ignored.
+    pub fn run_finalizers(&mut self) {
+        let finalizers = ALLOC.with(|m| m.borrow_mut().take_pending_finalizers());
+        if finalizers.is_empty() {
+            return;
+        }
+        let len = self.temp_len();
+        for (_, proc) in &finalizers {
+            self.temp_push(*proc);
+        }
+        for (id, proc) in finalizers {
+            let _ = self.eval_proc(proc, &Args::new1(id));
+        }
+        self.temp_pop_vec(len);
     }
 
     fn jmp_cond(&mut self, iseq: &ISeq, cond: bool, inst_offset: usize, dest_offset: usize) {
@@ -428,6 +547,9 @@ impl VM {
                         _ => {}
                     }
                     err.info.push((self.source_info(), self.get_loc()));
+                    if err.info.len() == 1 {
+                        self.set_error_info(&mut err);
+                    }
                     //eprintln!("{:?}", iseq.exception_table);
                     if let RubyErrorKind::Internal(msg) = &err.kind {
                         eprintln!();
@@ -446,6 +568,12 @@ impl VM {
                         self.set_stack_len(stack_len);
                         let val = err.to_exception_val();
                         self.stack_push(val);
+                        let dest = entry.dest.into_usize();
+                        let mut context = self.context();
+                        context.handled.retain(|(start, end, _)| {
+                            start.into_usize() < dest && dest < end.into_usize()
+                        });
+                        context.handled.push((entry.dest, entry.dest_end, val));
                     } else {
                         // Exception raised outside of begin-end.
                         self.context_pop().unwrap();
@@ -464,6 +592,135 @@ impl VM {
 }
 
 impl VM {
+    /// Get frames of the current call stack from the innermost one.
+    pub fn backtrace(&self) -> Vec<Frame> {
+        self.frames()
+            .filter_map(|(context, pc)| {
+                let iseq = context.iseq_ref?;
+                let loc = match iseq
+                    .iseq_sourcemap
+                    .iter()
+                    .rev()
+                    .find(|x| x.0.into_usize() <= pc.into_usize())
+                {
+                    Some((_, loc)) => *loc,
+                    None => iseq.loc,
+                };
+                Some(Frame {
+                    source_info: iseq.source_info,
+                    loc,
+                    label: VM::frame_label(context),
+                })
+            })
+            .collect()
+    }
+
+    /// Iterate over contexts in the current call stack and their current positions in ISeq
+    /// from the innermost one.
+    fn frames(&self) -> impl Iterator<Item = (ContextRef, ISeqPos)> + '_ {
+        let pc = self.pc;
+        self.cur_context
+            .iter()
+            .map(move |context| (*context, pc))
+            .chain(
+                self.exec_context
+                    .iter()
+                    .rev()
+                    .map(|context| (*context, context.pc)),
+            )
+    }
+
+    /// Get a label of `context` for backtraces.
+    fn frame_label(context: ContextRef) -> String {
+        let iseq = match context.iseq_ref {
+            Some(iseq) => iseq,
+            None => return "<main>".to_string(),
+        };
+        match iseq.kind {
+            ISeqKind::Method(Some(id)) => IdentId::get_ident_name(id),
+            ISeqKind::Method(None) => "<main>".to_string(),
+            ISeqKind::Class(id) => format!("<class:{}>", IdentId::get_ident_name(id)),
+            ISeqKind::Other => match context.outer {
+                Some(outer) => VM::frame_label(outer),
+                None => "<main>".to_string(),
+            },
+            ISeqKind::Block => {
+                let mut level = 1;
+                let mut outer = context.outer;
+                while let Some(ctx) = outer {
+                    match ctx.iseq_ref {
+                        Some(iseq) if iseq.kind == ISeqKind::Block => {
+                            level += 1;
+                            outer = ctx.outer;
+                        }
+                        _ => break,
+                    }
+                }
+                let method = match outer {
+                    Some(ctx) => VM::frame_label(ctx),
+                    None => "<main>".to_string(),
+                };
+                if level == 1 {
+                    format!("block in {}", method)
+                } else {
+                    format!("block ({} levels) in {}", level, method)
+                }
+            }
+        }
+    }
+
+    /// Get the exception being handled in rescue or ensure clauses in the current call stack.
+    pub fn handled_exception(&self) -> Option<Value> {
+        for (context, pc) in self.frames() {
+            let pc = pc.into_usize();
+            let handled = context
+                .handled
+                .iter()
+                .rev()
+                .find(|(start, end, _)| start.into_usize() <= pc && pc < end.into_usize());
+            if let Some((_, _, val)) = handled {
+                return Some(*val);
+            }
+        }
+        None
+    }
+
+    /// Record the current call stack to `err` as its backtrace,
+    /// and set the exception being handled as its cause.
+    fn set_error_info(&mut self, err: &mut RubyError) {
+        match err.kind {
+            RubyErrorKind::RuntimeErr { .. } | RubyErrorKind::Value(_) | RubyErrorKind::None(_) => {
+            }
+            _ => return,
+        }
+        let backtrace = self.backtrace();
+        let cause = self.handled_exception();
+        match err.kind {
+            RubyErrorKind::Value(mut val) => {
+                if let Some(rval) = val.as_mut_rvalue() {
+                    if let ObjKind::Exception(inner) = &mut rval.kind {
+                        if inner.backtrace.is_empty() {
+                            inner.backtrace = backtrace;
+                        }
+                    }
+                }
+                if let Some(cause) = cause {
+                    if cause.id() != val.id() && val.get_var(IdentId::get_id("/cause")).is_none() {
+                        val.set_var_by_str("/cause", cause);
+                    }
+                }
+            }
+            _ => {
+                err.backtrace = backtrace;
+                if let Some(cause) = cause {
+                    let val = err.to_exception_val();
+                    val.set_var_by_str("/cause", cause);
+                    err.kind = RubyErrorKind::Value(val);
+                }
+            }
+        }
+    }
+
     fn get_loc(&self) -> Loc {
         match self.context().iseq_ref {
             None => Loc(1, 1),
@@ -477,6 +734,41 @@ impl VM {
         }
     }
 
+    /// Invoke a hook method `hook` of `receiver` with `arg`.
+    ///
+    /// Do nothing if `receiver` does not respond to `hook`.
+    pub fn invoke_hook(
+        &mut self,
+        hook: &str,
+        receiver: Value,
+        arg: Value,
+    ) -> Result<(), RubyError> {
+        let hook = IdentId::get_id(hook);
+        if let Some(method) = MethodRepo::find_method_from_receiver(receiver, hook) {
+            self.eval_method(method, receiver, &Args::new1(arg))?;
+        }
+        Ok(())
+    }
+
+    /// Invoke `method_added` hook for a method `id` defined on `target_obj`.
+    ///
+    /// When the method was defined on a singleton class,
+    /// `singleton_method_added` of the attached object is invoked instead.
+    pub fn invoke_method_added(
+        &mut self,
+        target_obj: Value,
+        id: IdentId,
+    ) -> Result<(), RubyError> {
+        let class = match target_obj.if_mod_class() {
+            Some(module) => module,
+            None => target_obj.get_class(),
+        };
+        match class.singleton_for() {
+            Some(obj) => self.invoke_hook("singleton_method_added", obj, Value::symbol(id)),
+            None => self.invoke_hook("method_added", class.into(), Value::symbol(id)),
+        }
+    }
+
     /// Get class list in the current context.
     ///
     /// At first, this method searches the class list of outer context,
@@ -512,56 +804,57 @@ impl VM {
     }
 
     /// Search class inheritance chain for the constant.
-    pub fn get_super_const(mut class: Module, id: IdentId) -> VMResult {
+    pub fn find_super_const(mut class: Module, id: IdentId) -> Option<Value> {
         let is_module = class.is_module();
         loop {
             match class.get_const(id) {
-                Some(val) => return Ok(val),
+                Some(val) => return Some(val),
                 None => match class.upper() {
                     Some(upper) => class = upper,
                     None => {
                         if is_module {
-                            if let Some(val) = BuiltinClass::object().get_const(id) {
-                                return Ok(val);
-                            }
+                            return BuiltinClass::object().get_const(id);
                         }
-                        return Err(RubyError::name(format!("Uninitialized constant {:?}.", id)));
+                        return None;
                     }
                 },
             }
         }
     }
 
-    pub fn get_const(&self, parent: Module, id: IdentId) -> VMResult {
+    /// Search class inheritance chain for the constant.
+    ///
+    /// If the constant was not found, return NameError.
+    pub fn get_super_const(class: Module, id: IdentId) -> VMResult {
+        match VM::find_super_const(class, id) {
+            Some(val) => Ok(val),
+            None => Err(RubyError::uninitialized_constant(id)),
+        }
+    }
+
+    pub fn get_const(&mut self, parent: Module, id: IdentId) -> VMResult {
         match parent.get_const(id) {
             Some(val) => Ok(val),
-            None => Err(RubyError::name(format!("Uninitialized constant {:?}.", id))),
+            None => self.const_missing(parent, id),
         }
     }
 
+    /// Invoke `const_missing` hook of `class` for the constant `id`.
+    pub fn const_missing(&mut self, class: Module, id: IdentId) -> VMResult {
+        self.send1(IdentId::get_id("const_missing"), class.into(), Value::symbol(id))
+    }
+
     fn set_class_var(&self, id: IdentId, val: Value) -> Result<(), RubyError> {
         if self.exec_context.len() == 0 {
             return Err(RubyError::runtime("class varable access from toplevel."));
         }
         let self_val = self.context().self_value;
-        let org_class = match self_val.if_mod_class() {
+        let class = match self_val.if_mod_class() {
             Some(module) => module,
             None => self_val.get_class(),
         };
-        let mut class = org_class;
-        loop {
-            if class.set_var_if_exists(id, val) {
-                return Ok(());
-            } else {
-                match class.upper() {
-                    Some(superclass) => class = superclass,
-                    None => {
-                        org_class.set_var(id, val);
-                        return Ok(());
-                    }
-                }
-            };
-        }
+        class.set_class_var(id, val);
+        Ok(())
     }
 
     fn get_class_var(&self, id: IdentId) -> VMResult {
@@ -569,27 +862,13 @@ impl VM {
             return Err(RubyError::runtime("class varable access from toplevel."));
         }
         let self_val = self.context().self_value;
-        let mut class = match self_val.if_mod_class() {
+        let class = match self_val.if_mod_class() {
             Some(module) => module,
             None => self_val.get_class(),
         };
-        loop {
-            match class.get_var(id) {
-                Some(val) => {
-                    return Ok(val);
-                }
-                None => match class.upper() {
-                    Some(superclass) => {
-                        class = superclass;
-                    }
-                    None => {
-                        return Err(RubyError::name(format!(
-                            "Uninitialized class variable {:?}.",
-                            id
-                        )));
-                    }
-                },
-            }
+        match class.get_class_var(id) {
+            Some(val) => Ok(val),
+            None => Err(RubyError::uninitialized_class_var(id)),
         }
     }
 }
@@ -597,7 +876,7 @@ impl VM {
 // Utilities for method call
 impl VM {
     pub fn send(&mut self, method_id: IdentId, receiver: Value, args: &Args) -> VMResult {
-        match MethodRepo::find_method_from_receiver(receiver, method_id) {
+        match self.find_method_from_receiver(receiver, method_id) {
             Some(method) => return self.eval_method(method, receiver, args),
             None => {}
         };
@@ -623,7 +902,8 @@ impl VM {
         args: &Args,
     ) -> Result<(), RubyError> {
         let rec_class = receiver.get_class_for_method();
-        match MethodRepo::find_method_inline_cache(cache, rec_class, method_id) {
+        let caller = self.get_method_iseq();
+        match MethodRepo::find_method_inline_cache(cache, caller, rec_class, method_id) {
             Some(method) => return self.invoke_method(method, receiver, args),
             None => {}
         }
@@ -636,7 +916,7 @@ impl VM {
         receiver: Value,
         args: &Args,
     ) -> Result<(), RubyError> {
-        match MethodRepo::find_method_from_receiver(receiver, IdentId::_METHOD_MISSING) {
+        match self.find_method_from_receiver(receiver, IdentId::_METHOD_MISSING) {
             Some(method) => {
                 let len = args.len();
                 let mut new_args = Args::new(len + 1);
@@ -993,7 +1273,7 @@ impl VM {
     pub fn eval_teq(&mut self, rhs: Value, lhs: Value) -> Result<bool, RubyError> {
         match lhs.as_rvalue() {
             Some(oref) => match &oref.kind {
-                ObjKind::Module(_) => {
+                ObjKind::Module(_) | ObjKind::Range(_) => {
                     Ok(self.fallback_for_binop(IdentId::_TEQ, lhs, rhs)?.to_bool())
                 }
                 ObjKind::Regexp(re) => {
@@ -1015,6 +1295,10 @@ impl VM {
     }
 
     fn eval_rescue(&self, val: Value, exceptions: &[Value]) -> Result<bool, RubyError> {
+        // `throw` can not be rescued.
+        if let Some(RubyErrorKind::Throw(..)) = val.if_exception().map(|err| &err.kind) {
+            return Ok(false);
+        }
         let mut module = if val.is_class() {
             Module::new(val)
         } else {
@@ -1158,7 +1442,7 @@ impl VM {
                 ObjKind::Array(aref) => aref.get_elem1(idx)?,
                 ObjKind::Hash(href) => match href.get(&idx) {
                     Some(val) => *val,
-                    None => Value::nil(),
+                    None => builtin::hash::get_default(receiver),
                 },
                 _ => self.send(IdentId::_INDEX, receiver, &Args::new1(idx))?,
             },
@@ -1175,7 +1459,7 @@ impl VM {
                 ObjKind::Array(aref) => aref.get_elem_imm(idx),
                 ObjKind::Hash(href) => match href.get(&Value::integer(idx as i64)) {
                     Some(val) => *val,
-                    None => Value::nil(),
+                    None => builtin::hash::get_default(receiver),
                 },
                 ObjKind::Method(mref) => {
                     let args = Args::new1(Value::integer(idx as i64));
@@ -1247,6 +1531,9 @@ impl VM {
                     Module::class_under(super_val)
                 };
                 self.globals.set_const(current_class, id, val);
+                if let Some(superclass) = val.superclass() {
+                    self.invoke_hook("inherited", superclass.into(), val.into())?;
+                }
                 Ok(val)
             }
         }
@@ -1302,13 +1589,7 @@ impl VM {
             RV::True => "true".to_string(),
             RV::False => "false".to_string(),
             RV::Integer(i) => i.to_string(),
-            RV::Float(f) => {
-                if f.fract() == 0.0 {
-                    format!("{:.1}", f)
-                } else {
-                    f.to_string()
-                }
-            }
+            RV::Float(f) => builtin::float::float_to_s(f),
             RV::Symbol(sym) => format!(":{:?}", sym),
             RV::Object(oref) => match &oref.kind {
                 ObjKind::Invalid => "[Invalid]".to_string(),
@@ -1451,7 +1732,12 @@ impl VM {
     /// Evaluate Proc object.
     pub fn eval_proc(&mut self, proc: Value, args: &Args) -> VMResult {
         let pref = proc.as_proc().unwrap();
-        let context = Context::from_args(
+        let from_args = if pref.context.kind == ISeqKind::Block {
+            Context::from_args
+        } else {
+            Context::from_args_lambda
+        };
+        let context = from_args(
             self,
             pref.context.self_value,
             pref.context.iseq_ref.unwrap(),
@@ -1495,6 +1781,18 @@ impl VM {
                 let context = Context::from_args(self, self_val, iseq, args, outer)?;
                 self.run_context(&context)
             }
+            ProcFunc { proc } => {
+                // The body of the method is evaluated like a lambda with `self_val` as self.
+                let pref = proc.as_proc().unwrap();
+                let context = Context::from_args_lambda(
+                    self,
+                    self_val,
+                    pref.context.iseq_ref.unwrap(),
+                    args,
+                    pref.context.outer,
+                )?;
+                self.run_context(&context)
+            }
             _ => unreachable!(),
         }
     }
@@ -1651,6 +1949,24 @@ impl VM {
         Ok(())
     }
 
+    /// Find method(MethodId) for class, respecting refinements active in the current method.
+    pub fn find_method(&self, rec_class: Module, method_id: IdentId) -> Option<MethodId> {
+        let caller = match self.cur_context {
+            Some(_) => self.get_method_context().iseq_ref,
+            None => None,
+        };
+        MethodRepo::find_method_refined(caller, rec_class, method_id)
+    }
+
+    /// Find method(MethodId) for receiver, respecting refinements active in the current method.
+    pub fn find_method_from_receiver(
+        &self,
+        receiver: Value,
+        method_id: IdentId,
+    ) -> Option<MethodId> {
+        self.find_method(receiver.get_class_for_method(), method_id)
+    }
+
     /// Get method(MethodId) for class.
     ///
     /// If the method was not found, return NoMethodError.
@@ -1659,7 +1975,7 @@ impl VM {
         rec_class: Module,
         method_id: IdentId,
     ) -> Result<MethodId, RubyError> {
-        match MethodRepo::find_method(rec_class, method_id) {
+        match self.find_method(rec_class, method_id) {
             Some(m) => Ok(m),
             None => Err(RubyError::undefined_method_for_class(method_id, rec_class)),
         }
@@ -1727,12 +2043,72 @@ impl VM {
     }
 
     pub fn create_range(&mut self, start: Value, end: Value, exclude_end: bool) -> VMResult {
-        if self.eval_compare(start, end)?.is_nil() {
+        // Beginless and endless ranges have nil as the start or the end.
+        if !start.is_nil() && !end.is_nil() && self.eval_compare(start, end)?.is_nil() {
             return Err(RubyError::argument("Bad value for range."));
         }
         Ok(Value::range(start, end, exclude_end))
     }
 
+    /// Convert `val` to elements for multiple assignment.
+    /// Objects which do not respond to `to_ary` are treated as a one-element Array.
+    fn to_ary_elements(&mut self, val: Value) -> Result<Vec<Value>, RubyError> {
+        if let Some(ary) = val.as_array() {
+            return Ok(ary.elements.clone());
+        }
+        let method =
+            match MethodRepo::find_method(val.get_class_for_method(), IdentId::get_id("to_ary")) {
+                Some(method) => method,
+                None => return Ok(vec![val]),
+            };
+        let res = self.eval_method(method, val, &Args::new0())?;
+        match res.as_array() {
+            Some(ary) => Ok(ary.elements.clone()),
+            None if res.is_nil() => Ok(vec![val]),
+            None => Err(RubyError::typeerr(format!(
+                "Can't convert {} to Array ({}#to_ary gives {}).",
+                val.get_class_name(),
+                val.get_class_name(),
+                res.get_class_name()
+            ))),
+        }
+    }
+
+    /// Expand `val` on the stack for multiple assignment with `pre` targets,
+    /// an optional splat target and `post` targets.
+    /// Values are pushed in reverse order so that the first target comes at the top.
+    pub fn expand_array(
+        &mut self,
+        val: Value,
+        pre: usize,
+        post: usize,
+        rest: bool,
+    ) -> Result<(), RubyError> {
+        let elems = self.to_ary_elements(val)?;
+        let len = elems.len();
+        let post_start = if rest {
+            std::cmp::max(pre, len.saturating_sub(post))
+        } else {
+            pre
+        };
+        let get = |i: usize| elems.get(i).cloned().unwrap_or_else(Value::nil);
+        for i in (post_start..post_start + post).rev() {
+            self.stack_push(get(i));
+        }
+        if rest {
+            let rest = if pre < post_start {
+                elems[pre..post_start].to_vec()
+            } else {
+                vec![]
+            };
+            self.stack_push(Value::array_from(rest));
+        }
+        for i in (0..pre).rev() {
+            self.stack_push(get(i));
+        }
+        Ok(())
+    }
+
     /// Create new Proc object from `method`,
     /// moving outer `Context`s on stack to heap.
     pub fn create_proc(&mut self, block: &Block) -> VMResult {
@@ -1746,6 +2122,48 @@ impl VM {
         }
     }
 
+    /// Create a Binding object for the current context.
+    pub fn create_binding(&mut self) -> Value {
+        let context = self.move_outer_to_heap(self.context());
+        Value::binding(context)
+    }
+
+    /// Convert `val` given as a block argument (`&val`) to a Proc object by calling `to_proc`.
+    ///
+    /// Procs generated from Symbols are cached,
+    /// so that `map(&:to_s)` does not allocate a new Proc for each call.
+    pub fn to_block_proc(&mut self, val: Value) -> VMResult {
+        if val.as_proc().is_some() {
+            return Ok(val);
+        }
+        let sym = val.as_symbol();
+        if let Some(id) = sym {
+            if let Some(proc) = self.globals.sym_procs.get(&id) {
+                return Ok(*proc);
+            }
+        }
+        let to_proc = IdentId::get_id("to_proc");
+        if MethodRepo::find_method(val.get_class_for_method(), to_proc).is_none() {
+            return Err(RubyError::typeerr(format!(
+                "Wrong argument type {} (expected Proc).",
+                val.get_class_name()
+            )));
+        }
+        let proc = self.send0(to_proc, val)?;
+        if proc.as_proc().is_none() {
+            return Err(RubyError::typeerr(format!(
+                "Can't convert {} to Proc ({}#to_proc gives {}).",
+                val.get_class_name(),
+                val.get_class_name(),
+                proc.get_class_name()
+            )));
+        }
+        if let Some(id) = sym {
+            self.globals.sym_procs.insert(id, proc);
+        }
+        Ok(proc)
+    }
+
     /// Create new Lambda object from `method`,
     /// moving outer `Context`s on stack to heap.
     pub fn create_lambda(&mut self, block: &Block) -> VMResult {
@@ -91,6 +91,12 @@ impl VM {
                 Inst::THROW => {
                     // - raise error
                     let val = self.stack_pop();
+                    if let Some(err) = val.if_exception() {
+                        // Resume `throw` after ensure clauses were executed.
+                        if let RubyErrorKind::Throw(..) = err.kind {
+                            return Err(err.clone());
+                        }
+                    }
                     return Err(RubyError::value(val));
                 }
                 Inst::PUSH_NIL => {
@@ -360,12 +366,15 @@ impl VM {
                 Inst::CONCAT_STRING => {
                     let num = iseq.read32(self.pc + 1) as usize;
                     let stack_len = self.stack_len();
-                    let res = self
-                        .exec_stack
-                        .drain(stack_len - num..)
-                        .fold(String::new(), |acc, x| acc + x.as_string().unwrap());
+                    let res =
+                        self.exec_stack
+                            .drain(stack_len - num..)
+                            .fold(vec![], |mut acc, x| {
+                                acc.extend_from_slice(x.as_bytes().unwrap());
+                                acc
+                            });
 
-                    let val = Value::string(res);
+                    let val = Value::bytes(res);
                     self.stack_push(val);
                     self.pc += 5;
                 }
@@ -433,11 +442,16 @@ impl VM {
                         Some(val) => val,
                         None => {
                             let val = match self.get_env_const(id) {
-                                Some(val) => val,
-                                None => VM::get_super_const(self.class(), id)?,
+                                Some(val) => Some(val),
+                                None => VM::find_super_const(self.class(), id),
                             };
-                            self.globals.set_const_cache(slot, val);
-                            val
+                            match val {
+                                Some(val) => {
+                                    self.globals.set_const_cache(slot, val);
+                                    val
+                                }
+                                None => self.const_missing(self.class(), id)?,
+                            }
                         }
                     };
 
@@ -786,10 +800,16 @@ impl VM {
                     let method = iseq.read_method(self.pc + 5);
                     let mut iseq = method.as_iseq();
                     iseq.class_defined = self.get_method_iseq().class_defined.clone();
-                    self.define_method(self_value, id, method);
-                    if self.define_mode().module_function {
-                        self.define_singleton_method(self_value, id, method)?;
-                    };
+                    if let Some(singleton) = self.singleton_definee(self_value)? {
+                        self.define_method(singleton.into(), id, method);
+                        self.invoke_method_added(singleton.into(), id)?;
+                    } else {
+                        self.define_method(self_value, id, method);
+                        if self.define_mode().module_function {
+                            self.define_singleton_method(self_value, id, method)?;
+                        };
+                        self.invoke_method_added(self_value, id)?;
+                    }
                     self.pc += 9;
                 }
                 Inst::DEF_SMETHOD => {
@@ -802,6 +822,7 @@ impl VM {
                     if self.define_mode().module_function {
                         self.define_method(singleton, id, method);
                     };
+                    self.invoke_hook("singleton_method_added", singleton, Value::symbol(id))?;
                     self.pc += 9;
                 }
                 Inst::TO_S => {
@@ -837,35 +858,13 @@ impl VM {
                     self.stack_push(val);
                     self.pc += 5;
                 }
-                Inst::TAKE => {
-                    let len = iseq.read_usize(self.pc + 1);
+                Inst::EXPAND_ARRAY => {
+                    let pre = iseq.read_usize(self.pc + 1);
+                    let post = iseq.read_usize(self.pc + 5);
+                    let rest = iseq.read8(self.pc + 9) == 1;
                     let val = self.stack_pop();
-                    match val.as_array() {
-                        Some(info) => {
-                            let elem = &info.elements;
-                            let ary_len = elem.len();
-                            if len <= ary_len {
-                                for i in 0..len {
-                                    self.stack_push(elem[i]);
-                                }
-                            } else {
-                                for i in 0..ary_len {
-                                    self.stack_push(elem[i]);
-                                }
-                                for _ in ary_len..len {
-                                    self.stack_push(Value::nil());
-                                }
-                            }
-                        }
-                        None => {
-                            self.stack_push(val);
-                            for _ in 0..len - 1 {
-                                self.stack_push(Value::nil());
-                            }
-                        }
-                    }
-
-                    self.pc += 5;
+                    self.expand_array(val, pre, post, rest)?;
+                    self.pc += 10;
                 }
                 Inst::REP_UNINIT => {
                     let mut val = self.stack_pop();
@@ -899,8 +898,13 @@ impl VM {
         let mut kwrest = vec![];
         for _ in 0..kw_rest_num {
             let val = self.stack_pop();
-            kwrest.push(val);
+            // `**nil` passes no keyword arguments.
+            if !val.is_nil() {
+                kwrest.push(val);
+            }
         }
+        // Later double splats take precedence.
+        kwrest.reverse();
 
         let keyword = if flag & 0b01 == 1 {
             let mut val = self.stack_pop();
@@ -920,7 +924,12 @@ impl VM {
                     hash.insert(HashKey(k), v);
                 }
             }
-            Value::hash_from_map(hash)
+            // `**{}` passes no keyword arguments.
+            if hash.is_empty() {
+                Value::nil()
+            } else {
+                Value::hash_from_map(hash)
+            }
         };
 
         let block = if block != 0 {
@@ -930,14 +939,7 @@ impl VM {
             if val.is_nil() {
                 Block::None
             } else {
-                if val.as_proc().is_none() {
-                    return Err(RubyError::internal(format!(
-                        "Must be Proc. {:?}:{}",
-                        val,
-                        val.get_class_name()
-                    )));
-                }
-                Block::Proc(val)
+                Block::Proc(self.to_block_proc(val)?)
             }
         } else {
             Block::None
@@ -957,7 +959,8 @@ impl VM {
         let len = self.stack_len();
         let arg_slice = &self.exec_stack[len - args_num..];
         let rec_class = receiver.get_class_for_method();
-        match MethodRepo::find_method_inline_cache(cache_id, rec_class, method_id) {
+        let caller = self.get_method_iseq();
+        match MethodRepo::find_method_inline_cache(cache_id, caller, rec_class, method_id) {
             Some(method) => match MethodRepo::get(method) {
                 MethodInfo::BuiltinFunc { func, name } => {
                     let mut args = Args::from_slice(arg_slice);
@@ -1008,6 +1011,15 @@ impl VM {
                         self.run_context(&context)
                     }
                 }
+                MethodInfo::ProcFunc { .. } => {
+                    let mut args = Args::from_slice(arg_slice);
+                    args.block = match block {
+                        0 => Block::None,
+                        i => Block::Block(MethodId::from(i), self.context()),
+                    };
+                    self.set_stack_len(len - args_num);
+                    self.invoke_func(method, receiver, None, &args)
+                }
                 _ => unreachable!(),
             },
             None => {
@@ -1030,7 +1042,8 @@ impl VM {
         let args = Args::new0_block(block);
         let cache = iseq.read32(self.pc + 5);
         let rec_class = receiver.get_class_for_method();
-        match MethodRepo::find_method_inline_cache(cache, rec_class, IdentId::EACH) {
+        let caller = self.get_method_iseq();
+        match MethodRepo::find_method_inline_cache(cache, caller, rec_class, IdentId::EACH) {
             Some(method) => match MethodRepo::get(method) {
                 MethodInfo::BuiltinFunc { func, name } => {
                     let val = self.invoke_native(&func, method, name, receiver, &args)?;
@@ -1041,6 +1054,7 @@ impl VM {
                     let context = Context::from_args(self, receiver, iseq, &args, None)?;
                     self.run_context(&context)
                 }
+                MethodInfo::ProcFunc { .. } => self.invoke_func(method, receiver, None, &args),
                 _ => unreachable!(),
             },
             None => self.send_method_missing(IdentId::EACH, receiver, &args),
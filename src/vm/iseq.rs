@@ -193,6 +193,12 @@ impl ISeq {
         self.gen_const_val(id);
     }
 
+    pub fn gen_bytes(&mut self, globals: &mut Globals, b: &[u8]) {
+        let val = Value::bytes(b.to_vec());
+        let id = globals.const_values.insert(val);
+        self.gen_const_val(id);
+    }
+
     pub fn gen_complex(&mut self, globals: &mut Globals, i: Real) {
         let val = Value::complex(Value::integer(0), i.to_val());
         let id = globals.const_values.insert(val);
@@ -214,6 +220,13 @@ impl ISeq {
         self.push32(len as u32);
     }
 
+    pub fn gen_expand_array(&mut self, pre: usize, post: usize, rest: bool) {
+        self.push(Inst::EXPAND_ARRAY);
+        self.push32(pre as u32);
+        self.push32(post as u32);
+        self.push8(rest as u8);
+    }
+
     pub fn gen_create_hash(&mut self, len: usize) {
         self.push(Inst::CREATE_HASH);
         self.push32(len as u32);
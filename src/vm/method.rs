@@ -28,6 +28,12 @@ impl MethodId {
     pub fn as_iseq(&self) -> ISeqRef {
         METHODS.with(|m| m.borrow()[*self].as_iseq())
     }
+
+    /// Whether `self` is the marker put in a method table by `undef`,
+    /// which hides the method of the same name in the ancestors.
+    pub fn is_undef(&self) -> bool {
+        *self == MethodId::default()
+    }
 }
 
 impl From<u64> for MethodId {
@@ -155,6 +161,7 @@ pub struct MethodRepo {
     class_version: u32,
     i_cache: InlineCache,
     m_cache: MethodCache,
+    refinements: Vec<ActiveRefinement>,
 }
 
 impl std::ops::Index<MethodId> for MethodRepo {
@@ -189,6 +196,7 @@ impl MethodRepo {
             class_version: 0,
             i_cache: InlineCache::new(),
             m_cache: MethodCache::new(),
+            refinements: vec![],
         }
     }
 
@@ -219,8 +227,26 @@ impl MethodRepo {
         METHODS.with(|m| m.borrow_mut().i_cache.add_entry())
     }
 
+    /// Activate refinements in `module` for the lexical scope (cref) of `caller`.
+    pub fn activate_refinements(caller: ISeqRef, module: Module) {
+        METHODS.with(|m| {
+            let mut repo = m.borrow_mut();
+            repo.refinements.push(ActiveRefinement {
+                source_info: caller.source_info,
+                cref: caller.class_defined.clone(),
+                module,
+            });
+            repo.class_version += 1;
+        })
+    }
+
+    /// Search inline method cache of `id` with receiver class and method name.
+    ///
+    /// When the cache was missed, refinements activated in the scope of `caller` are searched first.
+    /// If the method was not found, return None.
     pub fn find_method_inline_cache(
         id: u32,
+        caller: ISeqRef,
         rec_class: Module,
         method_name: IdentId,
     ) -> Option<MethodId> {
@@ -241,10 +267,7 @@ impl MethodRepo {
             };
             #[cfg(feature = "perf-method")]
             MethodPerf::inc_inline_missed();
-            if let Some(method_id) = repo
-                .m_cache
-                .get_method(class_version, rec_class, method_name)
-            {
+            if let Some(method_id) = repo.lookup(Some(caller), rec_class, method_name) {
                 repo.i_cache.update_entry(
                     id,
                     InlineCacheEntry::new(class_version, rec_class, method_id),
@@ -258,13 +281,68 @@ impl MethodRepo {
 
     /// Search global method cache with receiver class and method name.
     ///
+    /// Refinements are not searched.
     /// If the method was not found, return None.
     pub fn find_method(rec_class: Module, method_id: IdentId) -> Option<MethodId> {
-        METHODS.with(|m| {
-            let mut repo = m.borrow_mut();
-            let class_version = repo.class_version;
-            repo.m_cache.get_method(class_version, rec_class, method_id)
-        })
+        Self::find_method_refined(None, rec_class, method_id)
+    }
+
+    /// Search method with receiver class and method name, respecting refinements
+    /// activated in the lexical scope of `caller`.
+    ///
+    /// If the method was not found, return None.
+    pub fn find_method_refined(
+        caller: Option<ISeqRef>,
+        rec_class: Module,
+        method_id: IdentId,
+    ) -> Option<MethodId> {
+        METHODS.with(|m| m.borrow_mut().lookup(caller, rec_class, method_id))
+    }
+
+    /// Search method, trying refinements active in `caller` before the class chain of `rec_class`.
+    fn lookup(
+        &mut self,
+        caller: Option<ISeqRef>,
+        rec_class: Module,
+        method: IdentId,
+    ) -> Option<MethodId> {
+        let class_version = self.class_version;
+        let refinements: Vec<Module> = match caller {
+            Some(caller) => self
+                .refinements
+                .iter()
+                .rev()
+                .filter(|r| r.is_active(caller))
+                .map(|r| r.module)
+                .collect(),
+            None => vec![],
+        };
+        if refinements.is_empty() {
+            return self.m_cache.get_method(class_version, rec_class, method);
+        }
+        let mut class = rec_class;
+        let mut singleton_flag = rec_class.is_singleton();
+        loop {
+            let real_class = class.real_module();
+            for module in &refinements {
+                if let Some(refinement) = module.get_refinement(real_class) {
+                    if let Some(method) = refinement.get_instance_method(method) {
+                        return Some(method);
+                    }
+                }
+            }
+            if let Some(method) = class.get_instance_method(method) {
+                return Some(method).filter(|method| !method.is_undef());
+            }
+            class = match class.upper() {
+                Some(superclass) => superclass,
+                None if singleton_flag => {
+                    singleton_flag = false;
+                    rec_class.class()
+                }
+                None => return None,
+            };
+        }
     }
 
     /// Search global method cache with receiver object and method class_name.
@@ -279,6 +357,26 @@ impl MethodRepo {
         let keys: Vec<Module> =
             METHODS.with(|m| m.borrow().m_cache.cache.keys().map(|(v, _)| *v).collect());
         keys.iter().for_each(|m| m.mark(alloc));
+        let refinements: Vec<Module> = METHODS.with(|m| {
+            m.borrow()
+                .refinements
+                .iter()
+                .flat_map(|r| r.cref.iter().chain(std::iter::once(&r.module)))
+                .cloned()
+                .collect()
+        });
+        refinements.iter().for_each(|m| m.mark(alloc));
+        let procs: Vec<Value> = METHODS.with(|m| {
+            m.borrow()
+                .table
+                .iter()
+                .filter_map(|info| match info {
+                    MethodInfo::ProcFunc { proc } => Some(*proc),
+                    _ => None,
+                })
+                .collect()
+        });
+        procs.iter().for_each(|p| p.mark(alloc));
     }
 }
 
@@ -341,6 +439,7 @@ pub enum MethodInfo {
     AttrReader { id: IdentId },
     AttrWriter { id: IdentId },
     BuiltinFunc { name: IdentId, func: BuiltinFunc },
+    ProcFunc { proc: Value }, // defined by define_method
     Void,
 }
 
@@ -348,6 +447,7 @@ impl GC for MethodInfo {
     fn mark(&self, alloc: &mut Allocator) {
         match self {
             MethodInfo::RubyFunc { iseq } => iseq.class_defined.iter().for_each(|c| c.mark(alloc)),
+            MethodInfo::ProcFunc { proc } => proc.mark(alloc),
             _ => return,
         };
     }
@@ -360,6 +460,7 @@ impl std::fmt::Debug for MethodInfo {
             MethodInfo::AttrReader { id } => write!(f, "AttrReader {:?}", id),
             MethodInfo::AttrWriter { id } => write!(f, "AttrWriter {:?}", id),
             MethodInfo::BuiltinFunc { name, .. } => write!(f, "BuiltinFunc {:?}", name),
+            MethodInfo::ProcFunc { .. } => write!(f, "ProcFunc"),
             MethodInfo::Void => write!(f, "Void"),
         }
     }
@@ -433,6 +534,38 @@ impl InlineCache {
     }
 }
 
+///---------------------------------------------------------------------------------------------------
+///
+/// Refinements
+///
+/// This module holds refinements activated by `main.using` or `Module#using`.
+/// Refinements are active in the lexical scope (cref) where `using` was called:
+/// the whole file for `main.using`, or the module definition for `Module#using`.
+/// The position of the `using` call is not checked, so methods defined before `using`
+/// in the same scope also see the refinements once `using` was called.
+///
+///---------------------------------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+struct ActiveRefinement {
+    source_info: SourceInfoRef,
+    /// The lexical class nesting where `using` was called.
+    cref: Vec<Module>,
+    module: Module,
+}
+
+impl ActiveRefinement {
+    /// Examine if the refinements are active in `caller`, which is lexically nested in the cref.
+    fn is_active(&self, caller: ISeqRef) -> bool {
+        self.source_info == caller.source_info
+            && caller.class_defined.len() >= self.cref.len()
+            && self
+                .cref
+                .iter()
+                .zip(caller.class_defined.iter())
+                .all(|(c1, c2)| c1.id() == c2.id())
+    }
+}
+
 ///---------------------------------------------------------------------------------------------------
 ///
 /// Global method cache
@@ -513,7 +646,11 @@ pub struct ISeqParams {
     pub post: usize,
     pub block: bool,
     pub keyword: FxHashMap<IdentId, LvarId>,
+    /// Keyword parameters without default values.
+    pub req_keyword: Vec<IdentId>,
     pub kwrest: bool,
+    /// `**nil` is given. Keyword arguments are not accepted.
+    pub no_keyword: bool,
 }
 
 impl ISeqParams {
@@ -525,6 +662,63 @@ impl ISeqParams {
             && self.keyword.is_empty()
             && !self.kwrest
     }
+
+    /// Returns the arity of the method or lambda with these parameters.
+    /// A negative value -(n+1) means that n arguments are required and more are acceptable.
+    pub fn arity(&self) -> i64 {
+        let required = (self.req + self.post) as i64;
+        if self.opt != 0 || self.rest.is_some() {
+            -required - 1
+        } else {
+            required
+        }
+    }
+
+    /// Returns an Array of [kind, name] pairs which describes these parameters.
+    /// When `is_proc` is true, required parameters are reported as :opt.
+    pub fn parameters(&self, is_proc: bool) -> Value {
+        fn param(kind: &str, id: Option<IdentId>) -> Value {
+            let mut v = vec![Value::symbol_from_str(kind)];
+            if let Some(id) = id {
+                v.push(Value::symbol(id));
+            }
+            Value::array_from(v)
+        }
+        let req = if is_proc { "opt" } else { "req" };
+        let mut ident = self.param_ident.iter().cloned();
+        let mut ary = vec![];
+        for _ in 0..self.req {
+            ary.push(param(req, ident.next()));
+        }
+        for _ in 0..self.opt {
+            ary.push(param("opt", ident.next()));
+        }
+        match self.rest {
+            Some(true) => ary.push(param("rest", ident.next())),
+            Some(false) => ary.push(param("rest", None)),
+            None => {}
+        }
+        for _ in 0..self.post {
+            ary.push(param(req, ident.next()));
+        }
+        for _ in 0..self.keyword.len() {
+            let id = ident.next();
+            let kind = match id {
+                Some(id) if self.req_keyword.contains(&id) => "keyreq",
+                _ => "key",
+            };
+            ary.push(param(kind, id));
+        }
+        if self.kwrest {
+            ary.push(param("keyrest", ident.next()));
+        } else if self.no_keyword {
+            ary.push(param("nokey", None));
+        }
+        if self.block {
+            ary.push(param("block", ident.next()));
+        }
+        Value::array_from(ary)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -561,6 +755,8 @@ pub struct ISeqInfo {
     pub class_defined: Vec<Module>,
     pub iseq_sourcemap: Vec<(ISeqPos, Loc)>,
     pub source_info: SourceInfoRef,
+    /// The location where this method or block was defined.
+    pub loc: Loc,
     pub kind: ISeqKind,
     pub forvars: Vec<(u32, u32)>,
 }
@@ -593,6 +789,7 @@ impl ISeqInfo {
         exception_table: Vec<ExceptionEntry>,
         iseq_sourcemap: Vec<(ISeqPos, Loc)>,
         source_info: SourceInfoRef,
+        loc: Loc,
         kind: ISeqKind,
         forvars: Vec<(u32, u32)>,
     ) -> Self {
@@ -609,6 +806,7 @@ impl ISeqInfo {
             class_defined: vec![],
             iseq_sourcemap,
             source_info,
+            loc,
             kind,
             forvars,
         }
@@ -627,6 +825,13 @@ impl ISeqInfo {
             _ => false,
         }
     }
+
+    /// Returns [path, line] where this method or block was defined.
+    pub fn source_location(&self) -> Value {
+        let path = Value::string(self.source_info.get_file_name());
+        let line = Value::integer(self.source_info.get_line(&self.loc));
+        Value::array_from(vec![path, line])
+    }
 }
 
 //----------------------------------------------------------------------------------
@@ -634,16 +839,20 @@ impl ISeqInfo {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MethodObjInfo {
     pub name: IdentId,
+    /// The receiver of the method. nil for UnboundMethod.
     pub receiver: Value,
     pub method: MethodId,
+    /// The class or module which defines the method.
+    pub owner: Module,
 }
 
 impl MethodObjInfo {
-    pub fn new(name: IdentId, receiver: Value, method: MethodId) -> Self {
+    pub fn new(name: IdentId, receiver: Value, method: MethodId, owner: Module) -> Self {
         MethodObjInfo {
             name,
             receiver,
             method,
+            owner,
         }
     }
 }
@@ -651,5 +860,6 @@ impl MethodObjInfo {
 impl GC for MethodObjInfo {
     fn mark(&self, alloc: &mut Allocator) {
         self.receiver.mark(alloc);
+        self.owner.mark(alloc);
     }
 }
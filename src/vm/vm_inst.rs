@@ -52,13 +52,13 @@ impl Inst {
 
     pub const POP: u8 = 80;
     pub const DUP: u8 = 81;
-    pub const TAKE: u8 = 82;
     pub const SPLAT: u8 = 83;
     pub const CONCAT_STRING: u8 = 84;
     pub const TO_S: u8 = 85;
     pub const SINKN: u8 = 86;
     pub const TOPN: u8 = 87;
     pub const REP_UNINIT: u8 = 88;
+    pub const EXPAND_ARRAY: u8 = 89;
 
     pub const DEF_CLASS: u8 = 90;
     pub const DEF_SCLASS: u8 = 91;
@@ -233,13 +233,13 @@ impl Inst {
 
             Inst::POP => "POP",
             Inst::DUP => "DUP",
-            Inst::TAKE => "TAKE",
             Inst::SPLAT => "SPLAT",
             Inst::CONCAT_STRING => "CONCAT_STR",
             Inst::TO_S => "TO_S",
             Inst::SINKN => "SINKN",
             Inst::TOPN => "TOPN",
             Inst::REP_UNINIT => "REP_UNINIT",
+            Inst::EXPAND_ARRAY => "EXPAND_ARRAY",
 
             Inst::DEF_CLASS => "DEF_CLASS",
             Inst::DEF_SCLASS => "DEF_SCLASS",
@@ -339,7 +339,6 @@ impl Inst {
             | Inst::JMP_F_LE            // disp: i32
 
             | Inst::DUP                 // number of items: u32
-            | Inst::TAKE                // number of items: u32
             | Inst::CONCAT_STRING       // number of items: u32
             | Inst::SINKN               // number of items: u32
             | Inst::TOPN                // number of items: u32
@@ -381,6 +380,7 @@ impl Inst {
             | Inst::DEF_SMETHOD         // method_id: u32 / method: u32
             => 9,
             Inst::DEF_CLASS => 10,      // is_module: u8 / method_id: u32 / block: u32
+            Inst::EXPAND_ARRAY => 10,   // number of pre items: u32 / number of post items: u32 / rest: u8
             Inst::OPT_SEND | Inst::OPT_SEND_SELF | Inst::OPT_NSEND | Inst::OPT_NSEND_SELF => 15,
                                 // method_id: u32 / number of args: u16 / block: u32 / icache: u32
             Inst::SEND | Inst::SEND_SELF => 17,
@@ -525,7 +525,6 @@ impl Inst {
             | Inst::CREATE_PROC
             | Inst::CREATE_HASH
             | Inst::DUP
-            | Inst::TAKE
             | Inst::SINKN
             | Inst::TOPN
             | Inst::CONCAT_STRING
@@ -549,6 +548,12 @@ impl Inst {
                 iseq.ident_name(pc + 2),
                 iseq.read32(pc + 6)
             ),
+            Inst::EXPAND_ARRAY => format!(
+                "EXPAND_ARRAY pre:{} post:{}{}",
+                iseq.read32(pc + 1),
+                iseq.read32(pc + 5),
+                if iseq.read8(pc + 9) == 1 { " rest" } else { "" }
+            ),
             Inst::DEF_SCLASS => format!("DEF_SCLASS"),
             Inst::DEF_METHOD => format!("DEF_METHOD '{}'", iseq.ident_name(pc + 1)),
             Inst::DEF_SMETHOD => format!("DEF_SMETHOD '{}'", iseq.ident_name(pc + 1)),
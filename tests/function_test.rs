@@ -123,6 +123,89 @@ fn kwrest_parameters() {
     assert_script(program);
 }
 
+#[test]
+fn required_keyword_parameters() {
+    let program = "
+        def fn(a, b:, c: 3)
+            [a, b, c]
+        end
+        assert [1, 2, 3], fn(1, b: 2)
+        assert [1, 2, 4], fn(1, c: 4, b: 2)
+        begin
+            fn(1)
+        rescue ArgumentError => e
+            assert 'missing keyword: :b', e.message
+        end
+        begin
+            fn(1, b: 2, d: 5, e: 6)
+        rescue ArgumentError => e
+            assert 'unknown keywords: :d, :e', e.message
+        end
+        def gn(x:, y:) = [x, y]
+        begin
+            gn
+        rescue ArgumentError => e
+            assert 'missing keywords: :x, :y', e.message
+        end
+        def hn(h) = h
+        assert({a: 1}, hn(a: 1))
+        def nokw(a, **nil) = a
+        assert 1, nokw(1)
+        assert_error { nokw(1, k: 2) }
+        assert [[:req, :a], [:keyreq, :b], [:key, :c]], method(:fn).parameters
+        assert [[:req, :a], [:nokey]], method(:nokw).parameters
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn double_splat_merge() {
+    let program = "
+        def fn(**kw) = kw
+        a = {x: 1, y: 2}
+        b = {y: 3, z: 4}
+        assert({x: 1, y: 3, z: 4}, fn(**a, **b))
+        assert({x: 1, y: 2, w: 0}, fn(w: 0, **a))
+        assert({}, fn(**nil))
+        assert({x: 1, y: 2}, fn(**a, **nil))
+        def gn(a = 0) = a
+        assert 0, gn(**{})
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn keyword_arguments_forwarding() {
+    let program = "
+        class Base
+            def foo(a, k: 0, **rest, &b)
+                [a, k, rest, b ? b.call : nil]
+            end
+        end
+        class Sub < Base
+            def foo(a, k: 1, **rest)
+                super
+            end
+        end
+        class Sub2 < Base
+            def foo(a, k: 1)
+                super(a, k: k * 10)
+            end
+        end
+        assert [5, 1, {}, nil], Sub.new.foo(5)
+        assert [5, 2, {z: 3}, 9], Sub.new.foo(5, k: 2, z: 3) { 9 }
+        assert [5, 20, {}, 7], Sub2.new.foo(5, k: 2) { 7 }
+        assert [1, 2, {}, nil], Base.new.send(:foo, 1, k: 2)
+        assert [1, 2, {}, nil], Base.new.method(:foo).call(1, k: 2)
+        class Base
+            define_method(:bar) { |a, k: 0| [a, k] }
+        end
+        assert [1, 5], Base.new.bar(1, k: 5)
+        assert [1, 0], Base.new.bar(1)
+        ";
+    assert_script(program);
+}
+
 #[test]
 fn return1() {
     let program = "
@@ -239,6 +322,90 @@ fn double_splat_argument() {
     assert_script(program);
 }
 
+#[test]
+fn endless_method() {
+    let program = r#"
+        def sq(x) = x * x
+        def answer = 42
+        def greet(name) = "Hello, #{name}"
+        def self.twice(x) = x * 2
+        assert 49, sq(7)
+        assert 42, answer
+        assert "Hello, ruby", greet("ruby")
+        assert 10, twice(5)
+    "#;
+    assert_script(program);
+}
+
+#[test]
+fn argument_forwarding() {
+    let program = "
+        def target(*a, **k, &b)
+            [a, k, b ? b.call : nil]
+        end
+        def foo(...) = target(...)
+        def bar(x, ...)
+            target(x * 10, ...)
+        end
+        assert [[1, 2], {}, nil], foo(1, 2)
+        assert [[1], {k: 3}, 5], foo(1, k: 3) { 5 }
+        assert [[10, 2], {}, 7], bar(1, 2) { 7 }
+        params = method(:foo).parameters.map { [_1, _2.to_s] }
+        assert [[:rest, '*'], [:keyrest, '**'], [:block, '&']], params
+        assert_error { eval('def baz = target(...)') }
+    ";
+    assert_script(program);
+}
+
+#[test]
+fn anonymous_arguments() {
+    let program = "
+        def target(*a, **k, &b)
+            [a, k, b ? b.call : nil]
+        end
+        def f(*, **, &) = target(*, **, &)
+        def g(*) = target(0, *)
+        def h(**) = target(**, z: 1)
+        def i(&) = target(&)
+        assert [[1, 2], {x: 3}, 4], f(1, 2, x: 3) { 4 }
+        assert [[0, 1, 2], {}, nil], g(1, 2)
+        assert [[], {y: 2, z: 1}, nil], h(y: 2)
+        assert [[], {}, 9], i { 9 }
+        assert_error { eval('def j = target(*)') }
+    ";
+    assert_script(program);
+}
+
+#[test]
+fn hash_shorthand() {
+    let program = "
+        def target(x:, y: 0) = [x, y]
+        def z = 30
+        x = 1
+        y = 2
+        assert({x: 1, y: 2}, {x:, y:})
+        assert({x: 1, z: 30}, {x:, z:})
+        assert [1, 2], target(x:, y:)
+        assert [1, 0], target(x:)
+        h = {
+          x:
+            10,
+          y:
+        }
+        assert({x: 10, y: 2}, h)
+        assert [5, 2], target(x:
+          5, y:)
+        assert [1, 7], target(
+          x:,
+          y:
+            7
+        )
+        r = target x:
+        assert [1, 0], r
+    ";
+    assert_script(program);
+}
+
 #[test]
 fn intrinsic_conversion_to_hash() {
     let program = r#"
@@ -138,6 +138,101 @@ Ruby.
     assert_script(program);
 }
 
+#[test]
+fn heredocument_squiggly() {
+    let program = r###"
+    a = <<~EOS
+      def foo
+        bar
+      end
+
+    EOS
+    assert "def foo\n  bar\nend\n\n", a
+    b = <<~EOS
+		tab
+	    indent
+    EOS
+    assert "\ttab\nindent\n", b
+    "###;
+    assert_script(program);
+}
+
+#[test]
+fn heredocument_interpolation() {
+    let program = r###"
+    name = "Ruby"
+    a = <<~EOS
+      Hello, #{name}!
+        #{1 + 2}\tthree
+      \#{name}
+    EOS
+    assert "Hello, Ruby!\n  3\tthree\n\#{name}\n", a
+    b = <<~'EOS'
+      Hello, #{name}!\n
+    EOS
+    assert "Hello, \#{name}!\\n\n", b
+    c = <<-"EOS"
+    #{name}
+    EOS
+    assert "    Ruby\n", c
+    d = <<~`EOS`
+      echo #{name}
+    EOS
+    assert "Ruby\n", d
+    "###;
+    assert_script(program);
+}
+
+#[test]
+fn heredocument_rest_of_line() {
+    let program = r###"
+    def f(a, b)
+      a + b
+    end
+    assert "abc\n", <<~EOS.strip + "\n"
+      abc
+    EOS
+    a = f(<<~A, <<~B) + "!"
+      first
+    A
+      second
+    B
+    assert "first\nsecond\n!", a
+    b = [<<~A.upcase,
+      foo
+    A
+      "bar"]
+    assert ["FOO\n", "bar"], b
+    c = <<~EOS # comment
+      x
+    EOS
+    assert "x\n", c
+    "###;
+    assert_script(program);
+}
+
+#[test]
+fn heredocument_nested() {
+    let program = r###"
+    a = <<~A
+      outer #{<<~B.strip} end
+        inner
+      B
+      last
+    A
+    assert "outer inner end\nlast\n", a
+    b = [<<~X, <<~Y]
+      x #{<<~Z.chomp}
+        z
+      Z
+    X
+      y
+    Y
+    assert ["x z\n", "y\n"], b
+    "###;
+    assert_script(program);
+}
+
 #[test]
 fn heredocument_error1() {
     let program = r###"
@@ -239,3 +334,83 @@ fn percent_notation() {
     "#;
     assert_script(program);
 }
+
+#[test]
+fn percent_notation2() {
+    let program = r##"
+        x = 3
+        assert([], %w())
+        assert(["a", "b"], %w(  a
+          b  ))
+        assert(["a b", "c]", "d\\"], %w[a\ b c\] d\\])
+        assert(["a\\nb"], %w(a\nb))
+        assert(["a[b]", "c"], %w[a[b] c])
+        assert(["a3b", "c d", "3", "e\n"], %W(a#{x}b c\ d #{x} e\n))
+        assert([:a, :b, :"c d"], %i[a b c\ d])
+        assert([:a3, :b], %I(a#{x} b))
+        assert(:"foo bar", %s(foo bar))
+        assert(:foo, %s!foo!)
+        assert("ok\n", %x(echo ok))
+        assert("3\n", %x{echo #{x}})
+        assert("a(b)c", %q(a(b)c))
+        assert("a)b", %q(a\)b))
+        assert("a(3(b))", %Q(a(#{x}(b))))
+        assert("a|b", %Q|a\|b|)
+        assert("x^y", %^x\^y^)
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn escape_sequence() {
+    let program = r##"
+        assert("😀A", "\u{1F600 41}")
+        assert("A", "\u{41}")
+        assert("\x04", "\x4")
+        assert("A", "\x41")
+        assert("A", "\101")
+        assert("\x00", "\0")
+        assert("\x01", "\C-a")
+        assert("\x01", "\ca")
+        assert("\x7f", "\c?")
+        assert("\xe1", "\M-a")
+        assert("\x81", "\M-\C-a")
+        assert(1, "\M-a".bytesize)
+        assert(1, "\xe1".bytesize)
+        assert(1, "\341".bytesize)
+        assert([225], "\M-a".bytes)
+        assert(2, "\u00e1".bytesize)
+        assert("あ", "\xe3\x81\x82")
+        assert("あ", "\xe3#{"\x81"}\x82")
+        assert_error { eval('"\u{ }"') }
+        assert_error { eval('"\u{}"') }
+        assert("ab", "a\
+b")
+        assert("\n", ?\n)
+        assert(" ", ?\s)
+        assert("\x01", ?\C-a)
+        assert("A", ?\u{41})
+        assert("a", ?a)
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn interpolation_shorthand() {
+    let program = r##"
+        @a = 1
+        $g = 2
+        assert("1 2", "#@a #$g")
+        assert("1-2", %Q(#@a-#$g))
+        assert(["1", "2"], %W(#@a #$g))
+        assert("#@ #$ #", "#@ #$ #")
+        class C
+          @@c = 3
+          def c
+            "c=#@@c"
+          end
+        end
+        assert("c=3", C.new.c)
+    "##;
+    assert_script(program);
+}
@@ -675,6 +675,218 @@ fn case_less_case() {
     assert_script(program);
 }
 
+#[test]
+fn case_in() {
+    let program = r##"
+        def f(x)
+          case x
+          in 0 then :zero
+          in Integer | Float => n if n < 0 then :negative
+          in 1..9 then :small
+          in Integer then :large
+          in "a" | "b" then :ab
+          in nil then :nil
+          in [] then :empty
+          in [1, *rest] then rest
+          in [Integer => a, String => b] then [b, a]
+          in [_, _, *, [x, y]] then x + y
+          in {name: String => name, age: 18.. => age} then "#{name}:#{age}"
+          in {name:} then name
+          in {} then :empty_hash
+          else :other
+          end
+        end
+        assert :zero, f(0)
+        assert :negative, f(-3)
+        assert :negative, f(-1.5)
+        assert :small, f(7)
+        assert :large, f(100)
+        assert :ab, f("b")
+        assert :nil, f(nil)
+        assert :empty, f([])
+        assert [2, 3], f([1, 2, 3])
+        assert ["x", 5], f([5, "x"])
+        assert 7, f([0, 0, 0, [3, 4]])
+        assert "bob:20", f({name: "bob", age: 20})
+        assert "ann", f({name: "ann", age: 10})
+        assert :empty_hash, f({})
+        assert :other, f({age: 1})
+        assert :other, f(12.5)
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn case_in_find_pin_guard() {
+    let program = r##"
+        case [1, 42, "x", :y, 5]
+        in [*pre, String => s, Symbol => t, *post]
+          assert [1, 42], pre
+          assert "x", s
+          assert :y, t
+          assert [5], post
+        end
+        case [1, 2, 3]
+        in [*, 4, *] then r = 0
+        in [*, 3, *post] then r = post
+        end
+        assert [], r
+        x = 5
+        case 5
+        in ^x then r = :pinned
+        end
+        assert :pinned, r
+        @v = [1, 2]
+        assert true, ([1, 2] in ^@v)
+        assert true, (3 in ^(1 + 2))
+        case 4
+        in Integer => n unless n.even? then r = :odd
+        in Integer => n if n.even? then r = :even
+        end
+        assert :even, r
+        case {status: "ok", data: {items: [1, 2]}}
+        in {status: "ok", data: {items: [first, *]}}
+          assert 1, first
+        end
+        case {a: 1, b: 2}
+        in {a: Integer, **rest}
+          assert({b: 2}, rest)
+        end
+        case {a: 1, b: 2}
+        in {a: 1, **nil} then r = :exact
+        in {a: 1} then r = :loose
+        end
+        assert :loose, r
+        case 5
+        in ..3 then r = :low
+        in 4.. then r = :high
+        end
+        assert :high, r
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn case_in_deconstruct() {
+    let program = r##"
+        Point = Struct.new(:x, :y)
+        case Point.new(1, 2)
+        in Point[x, y]
+          assert [1, 2], [x, y]
+        end
+        case Point.new(3, 4)
+        in Point(x:, y: 4)
+          assert 3, x
+        end
+        assert [5, 6], Point.new(5, 6).deconstruct
+        assert({x: 5}, Point.new(5, 6).deconstruct_keys([:x]))
+        assert({x: 5, y: 6}, Point.new(5, 6).deconstruct_keys(nil))
+        class Vec
+          def initialize(a, b); @a = a; @b = b; end
+          def deconstruct; [@a, @b]; end
+          def deconstruct_keys(keys); {a: @a, b: @b}; end
+        end
+        case Vec.new(7, 8)
+        in [a, b]
+          assert [7, 8], [a, b]
+        end
+        case Vec.new(7, 8)
+        in {a:, b:}
+          assert [7, 8], [a, b]
+        end
+        assert false, (Vec.new(1, 2) in [_])
+        assert false, (5 in [_])
+        assert false, (5 in {a:})
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn pattern_match_oneline() {
+    let program = r##"
+        {name: "bob", roles: [:admin, :dev]} => {name:, roles: [role, *]}
+        assert "bob", name
+        assert :admin, role
+        [1, [2, 3]] => [a, [b, c]]
+        assert [1, 2, 3], [a, b, c]
+        42 => Integer => n
+        assert 42, n
+        assert true, (1 in Integer)
+        assert false, (1 in String)
+        assert true, ([1, "a"] in [Integer, String])
+        begin
+          5 => String
+        rescue NoMatchingPatternError => e
+          assert "5", e.message
+        end
+        begin
+          case [1, 2]
+          in [a]
+          end
+        rescue NoMatchingPatternError => e
+          assert "[1, 2]", e.message
+        end
+        begin
+          {a: 1} => {b:}
+        rescue NoMatchingPatternError => e
+          assert NoMatchingPatternKeyError, e.class
+          assert "key not found: :b", e.message
+          assert :b, e.key
+          assert({a: 1}, e.matchee)
+        end
+        {a: 1} => {b: 1} | {a: 1}
+        {a: 2} => {a: 1} | {a: x}
+        assert 2, x
+        [{a: 1}, {b: 2}] => [*, {b:}, *]
+        assert 2, b
+        begin
+          {a: 1} => {b: 1} | {c: 1}
+        rescue NoMatchingPatternError => e
+          assert NoMatchingPatternError, e.class
+        end
+        begin
+          [{a: 1}] => [*, {b:}, *]
+        rescue NoMatchingPatternError => e
+          assert NoMatchingPatternError, e.class
+        end
+    "##;
+    assert_script(program);
+}
+
+#[test]
+fn block_numbered_param() {
+    let program = "
+        assert [2, 4, 6], [1, 2, 3].map { _1 * 2 }
+        assert [3, 7], [[1, 2], [3, 4]].map { _1 + _2 }
+        assert [[1, 2], [3, 4]], [[1, 2], [3, 4]].map { _1 }
+        assert [2, 4], [[1, 2], [3, 4]].map { _2 }
+        assert 1, proc { _1 }.arity
+        assert 3, proc { _3 }.arity
+        assert 0, proc { 1 }.arity
+        assert 9, ->{ _1 * _1 }.call(3)
+        assert [[2], [4]], [1, 2].map { x = _1 * 2; [x] }
+        assert [11, 21], [1, 2].map { [10, 20].map { _1 + 1 } }.first
+        assert_error { eval('[1].each { |x| _1 }') }
+        assert_error { eval('[1].each { || _1 }') }
+        assert_error { eval('[1].each { _1; [2].each { _1 } }') }
+    ";
+    assert_script(program);
+}
+
+#[test]
+fn block_it_param() {
+    let program = "
+        assert [2, 4, 6], [1, 2, 3].map { it * 2 }
+        assert [[1, 2]], [[1, 2]].map { it }
+        assert 1, proc { it }.arity
+        assert [[4, 4]], [1].map { [2].map { [it + 2, it * 2] } }.first
+        assert_error { eval('[1].each { _1 + it }') }
+        it = 10
+        assert [11], [1].map { it + _1 }
+    ";
+    assert_script(program);
+}
+
 #[test]
 fn block_break() {
     let program = "
@@ -951,6 +1163,118 @@ fn mul_assign9() {
     assert_script(program);
 }
 
+#[test]
+fn mul_assign_splat() {
+    let program = "
+        a, *b = 1, 2, 3
+        assert([1, [2, 3]], [a, b])
+        *a, b = 1, 2, 3
+        assert([[1, 2], 3], [a, b])
+        a, *b, c = 1
+        assert([1, [], nil], [a, b, c])
+        a, *b, c = [1, 2, 3, 4]
+        assert([1, [2, 3], 4], [a, b, c])
+        a, * = 5, 6, 7
+        assert(5, a)
+        *a = nil
+        assert([nil], a)
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn mul_assign_nested() {
+    let program = "
+        a, (b, c), *d = 1, [2, 3], 4, 5
+        assert([1, 2, 3, [4, 5]], [a, b, c, d])
+        (a, b), c = [1, 2], 3
+        assert([1, 2, 3], [a, b, c])
+        a, (b, (c, *d)), e = 1, [2, [3, 4, 5]], 6
+        assert([1, 2, 3, [4, 5], 6], [a, b, c, d, e])
+        (a, b) = 7, 8
+        assert([7, 8], [a, b])
+        x = (a, (b, c) = [1, [2, 3]])
+        assert([1, [2, 3]], x)
+        (a), b = [1, 2], 3
+        assert([1, 3], [a, b])
+        a, (b) = 1, [2, 3]
+        assert([1, 2], [a, b])
+        a, ((b)) = 4, [[5, 6]]
+        assert([4, 5], [a, b])
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn mul_assign_attr_and_index() {
+    let program = "
+        class Foo
+          attr_accessor :x
+        end
+        foo = Foo.new
+        h = {}
+        foo.x, h[:y] = 1, 2
+        assert(1, foo.x)
+        assert({y: 2}, h)
+        h[:a], (foo.x, h[:b]) = 3, [4, 5]
+        assert({y: 2, a: 3, b: 5}, h)
+        assert(4, foo.x)
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn mul_assign_to_ary() {
+    let program = "
+        class Pair
+          def to_ary
+            [:left, :right]
+          end
+        end
+        a, b = Pair.new
+        assert([:left, :right], [a, b])
+        a, (b, c) = 1, Pair.new
+        assert([1, :left, :right], [a, b, c])
+        a, b = 1
+        assert([1, nil], [a, b])
+        ";
+    assert_script(program);
+}
+
+#[test]
+fn block_param_destructuring() {
+    let program = "
+        r = []
+        h = {a: [1, 2], b: [3, 4]}
+        h.each { |k, (x, y)| r << [k, x, y] }
+        assert([[:a, 1, 2], [:b, 3, 4]], r)
+        r = []
+        [[1, [2, 3]], [4, [5, 6]]].each { |a, (b, c)| r << a + b + c }
+        assert([6, 15], r)
+        r = []
+        [[1, 2, 3]].each { |(a, *b)| r << a << b }
+        assert([1, [2, 3]], r)
+        r = []
+        [[[1, 2], 3]].each do |((a, b), c)|
+          r << a << b << c
+        end
+        assert([1, 2, 3], r)
+        def f((a, b), c)
+          [a, b, c]
+        end
+        assert([1, 2, 3], f([1, 2], 3))
+        r = []
+        [[1, 2], [3, 4]].each { |(a)| r << a }
+        assert([1, 3], r)
+        def g((a))
+          a
+        end
+        assert(1, g([1, 2]))
+        assert(5, g(5))
+        ";
+    assert_script(program);
+}
+
 #[test]
 fn assign1() {
     let program = "
@@ -1435,6 +1759,58 @@ fn defined() {
     assert_script(program);
 }
 
+#[test]
+fn begin_end_blocks() {
+    let program = "
+    END { assert([:begin, :main, :at_exit], $order) }
+    at_exit { $order << :at_exit }
+    $order << :main
+    BEGIN { $order = [:begin] }
+    ";
+    assert_script(program);
+}
+
+#[test]
+fn begin_block_not_toplevel() {
+    assert_error("if true; BEGIN { }; end");
+    assert_error("while false\n BEGIN { }\n end");
+    assert_error("begin\n BEGIN { }\n end");
+    assert_error("p(BEGIN { })");
+    assert_error("def foo; BEGIN { }; end");
+}
+
+#[test]
+fn at_exit_after_error() {
+    let mut globals = GlobalsRef::new_globals();
+    let mut vm = globals.create_main_fiber();
+    let program = r#"
+    at_exit { raise "at_exit" }
+    raise "main"
+    "#;
+    let err = vm.run(std::path::PathBuf::from(""), program).unwrap_err();
+    assert_eq!("main", err.message());
+}
+
+#[test]
+fn data_after_end() {
+    let program = r#"
+    assert("foo\n", DATA.gets)
+    assert(["bar\n", "baz"], DATA.readlines)
+    assert(true, DATA.eof?)
+    assert(nil, DATA.gets)
+    DATA.rewind
+    assert("foo\nbar\nbaz", DATA.read)
+    DATA.rewind
+    lines = []
+    DATA.each_line { |line| lines << line.chomp }
+    assert(["foo", "bar", "baz"], lines)
+__END__
+foo
+bar
+baz"#;
+    assert_script(program);
+}
+
 #[test]
 fn exception1() {
     let program = r##"